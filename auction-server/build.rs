@@ -40,6 +40,7 @@ fn build_evm_contracts() {
 const SUBMIT_BID_INSTRUCTION_SVM: &str = "submit_bid";
 const PERMISSION_ACCOUNT_SVM: &str = "permission";
 const ROUTER_ACCOUNT_SVM: &str = "router";
+const RELAYER_SIGNER_ACCOUNT_SVM: &str = "relayer_signer";
 const IDL_LOCATION: &str = "../contracts/svm/target/idl/express_relay.json";
 
 fn extract_account_position(idl: Idl, instruction_name: &str, account_name: &str) -> usize {
@@ -83,6 +84,14 @@ fn verify_and_extract_idl_data() {
             ROUTER_ACCOUNT_SVM,
         )
     );
+    println!(
+        "cargo:rustc-env=SUBMIT_BID_RELAYER_SIGNER_ACCOUNT_POSITION={}",
+        extract_account_position(
+            express_relay_idl.clone(),
+            SUBMIT_BID_INSTRUCTION_SVM,
+            RELAYER_SIGNER_ACCOUNT_SVM,
+        )
+    );
 }
 
 fn build_svm_contracts() {
@@ -110,9 +119,35 @@ fn build_svm_contracts() {
     }
 }
 
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn set_build_info() {
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    // These values are exposed by the version endpoint to identify the running build.
+    let git_commit =
+        command_output("git", &["rev-parse", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let build_time = command_output("date", &["-u", "+%Y-%m-%dT%H:%M:%SZ"])
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+}
+
+fn build_grpc_protos() {
+    println!("cargo:rerun-if-changed=proto");
+    tonic_build::compile_protos("proto/auction.proto").expect("Failed to compile grpc protos");
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=migrations");
 
+    set_build_info();
+    build_grpc_protos();
     build_evm_contracts();
     build_svm_contracts();
     verify_and_extract_idl_data();
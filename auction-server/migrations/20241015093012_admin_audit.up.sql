@@ -0,0 +1,10 @@
+CREATE TABLE admin_audit
+(
+    id             UUID         PRIMARY KEY,
+    creation_time  TIMESTAMP    NOT NULL,
+    action         VARCHAR(255) NOT NULL,
+    parameters     JSONB        NOT NULL,
+    admin_identity VARCHAR(255) NOT NULL
+);
+
+CREATE INDEX admin_audit_creation_time_idx ON admin_audit (creation_time);
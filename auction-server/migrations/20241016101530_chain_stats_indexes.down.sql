@@ -0,0 +1,2 @@
+DROP INDEX bid_auction_id_idx;
+DROP INDEX auction_chain_id_creation_time_idx;
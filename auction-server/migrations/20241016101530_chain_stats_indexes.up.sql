@@ -0,0 +1,2 @@
+CREATE INDEX auction_chain_id_creation_time_idx ON auction (chain_id, creation_time);
+CREATE INDEX bid_auction_id_idx ON bid (auction_id);
@@ -0,0 +1 @@
+ALTER TABLE auction DROP CONSTRAINT auction_permission_key_chain_id_bid_collection_time_key;
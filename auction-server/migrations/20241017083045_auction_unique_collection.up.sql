@@ -0,0 +1,21 @@
+-- Merges the duplicate auctions of a bid collection into the submitted one, or the earliest one if none was submitted
+CREATE TEMPORARY TABLE duplicate_auction AS
+SELECT id, kept_id FROM (
+    SELECT id, first_value(id) OVER (
+        PARTITION BY permission_key, chain_id, bid_collection_time
+        ORDER BY tx_hash IS NULL, creation_time, id
+    ) AS kept_id
+    FROM auction
+    WHERE bid_collection_time IS NOT NULL
+) ranked_auction
+WHERE id <> kept_id;
+
+UPDATE bid SET auction_id = duplicate_auction.kept_id
+FROM duplicate_auction
+WHERE bid.auction_id = duplicate_auction.id;
+
+DELETE FROM auction USING duplicate_auction WHERE auction.id = duplicate_auction.id;
+
+DROP TABLE duplicate_auction;
+
+ALTER TABLE auction ADD CONSTRAINT auction_permission_key_chain_id_bid_collection_time_key UNIQUE (permission_key, chain_id, bid_collection_time);
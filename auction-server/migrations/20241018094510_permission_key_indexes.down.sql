@@ -0,0 +1,2 @@
+DROP INDEX opportunity_permission_key_idx;
+DROP INDEX bid_permission_key_idx;
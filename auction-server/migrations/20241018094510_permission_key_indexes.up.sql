@@ -0,0 +1,2 @@
+CREATE INDEX bid_permission_key_idx ON bid (permission_key);
+CREATE INDEX opportunity_permission_key_idx ON opportunity (permission_key);
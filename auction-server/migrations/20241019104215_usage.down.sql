@@ -0,0 +1,2 @@
+DROP TABLE usage_quota;
+DROP TABLE usage;
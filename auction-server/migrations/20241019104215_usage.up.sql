@@ -0,0 +1,17 @@
+CREATE TABLE usage
+(
+    profile_id        UUID   NOT NULL REFERENCES profile(id) ON DELETE CASCADE,
+    period_start      DATE   NOT NULL,
+    requests          BIGINT NOT NULL DEFAULT 0,
+    bids              BIGINT NOT NULL DEFAULT 0,
+    bytes_transferred BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (profile_id, period_start)
+);
+
+CREATE TABLE usage_quota
+(
+    profile_id        UUID   PRIMARY KEY REFERENCES profile(id) ON DELETE CASCADE,
+    requests          BIGINT,
+    bids              BIGINT,
+    bytes_transferred BIGINT
+);
@@ -0,0 +1,12 @@
+CREATE TABLE rejected_bid
+(
+    id            UUID         PRIMARY KEY,
+    creation_time TIMESTAMP    NOT NULL,
+    chain_id      VARCHAR(255) NOT NULL,
+    profile_id    UUID         REFERENCES profile(id) ON DELETE SET NULL,
+    bid           JSONB        NOT NULL,
+    reason        TEXT         NOT NULL
+);
+
+CREATE INDEX rejected_bid_creation_time_idx ON rejected_bid (creation_time);
+CREATE INDEX rejected_bid_chain_id_creation_time_idx ON rejected_bid (chain_id, creation_time);
@@ -0,0 +1 @@
+DROP TABLE token_spoof_info;
@@ -0,0 +1,12 @@
+CREATE TABLE token_spoof_info
+(
+    chain_id       TEXT           NOT NULL,
+    token          BYTEA          NOT NULL CHECK (LENGTH(token) = 20),
+    bytecode_hash  BYTEA          NOT NULL CHECK (LENGTH(bytecode_hash) = 32),
+    -- Both slots are null if the token can not be spoofed
+    balance_slot   NUMERIC(78, 0),
+    allowance_slot NUMERIC(78, 0),
+    creation_time  TIMESTAMP      NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chain_id, token),
+    CHECK ((balance_slot IS NULL) = (allowance_slot IS NULL))
+);
@@ -0,0 +1 @@
+ALTER TABLE bid DROP COLUMN good_till;
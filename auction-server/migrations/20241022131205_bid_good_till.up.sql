@@ -0,0 +1 @@
+ALTER TABLE bid ADD COLUMN good_till TIMESTAMP;
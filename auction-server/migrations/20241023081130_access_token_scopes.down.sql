@@ -0,0 +1,3 @@
+ALTER TABLE access_token DROP COLUMN scopes;
+
+DROP TYPE access_token_scope;
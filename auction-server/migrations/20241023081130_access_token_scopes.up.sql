@@ -0,0 +1,4 @@
+CREATE TYPE access_token_scope AS ENUM ('read', 'submit');
+
+-- Existing tokens keep the full access
+ALTER TABLE access_token ADD COLUMN scopes access_token_scope[] NOT NULL DEFAULT '{read,submit}';
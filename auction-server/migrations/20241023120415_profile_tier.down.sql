@@ -0,0 +1,3 @@
+ALTER TABLE profile DROP COLUMN tier;
+
+DROP TYPE profile_tier;
@@ -0,0 +1,3 @@
+CREATE TYPE profile_tier AS ENUM ('standard', 'premium');
+
+ALTER TABLE profile ADD COLUMN tier profile_tier NOT NULL DEFAULT 'standard';
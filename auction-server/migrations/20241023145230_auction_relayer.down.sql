@@ -0,0 +1 @@
+ALTER TABLE auction DROP COLUMN relayer;
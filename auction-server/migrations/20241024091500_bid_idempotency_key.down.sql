@@ -0,0 +1,3 @@
+DROP INDEX bid_profile_id_idempotency_key_idx;
+ALTER TABLE bid DROP COLUMN idempotency_payload_hash;
+ALTER TABLE bid DROP COLUMN idempotency_key;
@@ -0,0 +1,3 @@
+ALTER TABLE bid ADD COLUMN idempotency_key TEXT;
+ALTER TABLE bid ADD COLUMN idempotency_payload_hash BYTEA;
+CREATE UNIQUE INDEX bid_profile_id_idempotency_key_idx ON bid (profile_id, idempotency_key);
@@ -0,0 +1,14 @@
+CREATE TYPE audit_action AS ENUM ('create_profile', 'create_access_token', 'revoke_access_token');
+
+CREATE TABLE audit_log
+(
+    id                UUID         PRIMARY KEY,
+    creation_time     TIMESTAMP    NOT NULL,
+    actor_profile_id  UUID,
+    action            audit_action NOT NULL,
+    target_profile_id UUID         NOT NULL,
+    target_token_id   UUID
+);
+
+CREATE INDEX audit_log_target_profile_id_creation_time_idx ON audit_log (target_profile_id, creation_time);
+CREATE INDEX audit_log_actor_profile_id_creation_time_idx ON audit_log (actor_profile_id, creation_time);
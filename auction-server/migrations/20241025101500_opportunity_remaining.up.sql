@@ -0,0 +1,3 @@
+ALTER TABLE opportunity ADD COLUMN remaining_sell_tokens JSONB;
+ALTER TABLE opportunity ADD COLUMN remaining_buy_tokens JSONB;
+ALTER TYPE opportunity_removal_reason ADD VALUE 'consumed';
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS auction_conclusion_block_number_idx;
+ALTER TABLE auction DROP COLUMN conclusion_block_hash;
+ALTER TABLE auction DROP COLUMN conclusion_block_number;
@@ -0,0 +1,3 @@
+ALTER TABLE auction ADD COLUMN conclusion_block_number BIGINT;
+ALTER TABLE auction ADD COLUMN conclusion_block_hash BYTEA;
+CREATE INDEX auction_conclusion_block_number_idx ON auction (chain_id, conclusion_block_number) WHERE conclusion_block_number IS NOT NULL;
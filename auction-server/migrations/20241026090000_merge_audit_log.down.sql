@@ -0,0 +1,26 @@
+CREATE TYPE audit_action AS ENUM ('create_profile', 'create_access_token', 'revoke_access_token');
+
+CREATE TABLE audit_log
+(
+    id                UUID         PRIMARY KEY,
+    creation_time     TIMESTAMP    NOT NULL,
+    actor_profile_id  UUID,
+    action            audit_action NOT NULL,
+    target_profile_id UUID         NOT NULL,
+    target_token_id   UUID
+);
+
+CREATE INDEX audit_log_target_profile_id_creation_time_idx ON audit_log (target_profile_id, creation_time);
+CREATE INDEX audit_log_actor_profile_id_creation_time_idx ON audit_log (actor_profile_id, creation_time);
+
+INSERT INTO audit_log (id, creation_time, actor_profile_id, action, target_profile_id, target_token_id)
+SELECT id, creation_time, actor_profile_id, action::audit_action, target_profile_id, target_token_id
+FROM admin_audit
+WHERE target_profile_id IS NOT NULL;
+
+DELETE FROM admin_audit WHERE target_profile_id IS NOT NULL;
+
+ALTER TABLE admin_audit
+    DROP COLUMN actor_profile_id,
+    DROP COLUMN target_profile_id,
+    DROP COLUMN target_token_id;
@@ -0,0 +1,23 @@
+ALTER TABLE admin_audit
+    ADD COLUMN actor_profile_id  UUID,
+    ADD COLUMN target_profile_id UUID,
+    ADD COLUMN target_token_id   UUID;
+
+UPDATE admin_audit SET actor_profile_id = substring(admin_identity FROM 9)::UUID WHERE admin_identity LIKE 'profile:%';
+
+INSERT INTO admin_audit (id, creation_time, action, parameters, admin_identity, actor_profile_id, target_profile_id, target_token_id)
+SELECT id,
+       creation_time,
+       action::TEXT,
+       'null'::JSONB,
+       COALESCE('profile:' || actor_profile_id::TEXT, 'secret_key'),
+       actor_profile_id,
+       target_profile_id,
+       target_token_id
+FROM audit_log;
+
+DROP TABLE audit_log;
+DROP TYPE audit_action;
+
+CREATE INDEX admin_audit_target_profile_id_creation_time_idx ON admin_audit (target_profile_id, creation_time);
+CREATE INDEX admin_audit_actor_profile_id_creation_time_idx ON admin_audit (actor_profile_id, creation_time);
@@ -1,11 +1,31 @@
 use {
     crate::{
         api::{
+            admin::{
+                AdminAudit,
+                AdminAudits,
+            },
             bid::{
+                BidAmountUnit,
+                BidBatchResult,
                 BidResult,
+                BidStatusResponse,
                 SimulatedBids,
             },
-            opportunity::OpportunityParamsWithMetadata,
+            chain::{
+                AdapterConfig,
+                AuctionConfig,
+                ChainStats,
+                NextAuction,
+                SupportedChain,
+                SupportedChains,
+                TargetContractAllowlist,
+            },
+            opportunity::{
+                OpportunityParamsWithMetadata,
+                RemovedOpportunity,
+                SimulateOpportunityBid,
+            },
             ws::{
                 APIResponse,
                 ClientMessage,
@@ -21,18 +41,26 @@ use {
             BidSvm,
         },
         config::{
+            AuctionSchedule,
             ChainId,
+            MinBidIncrement,
+            MinBids,
+            MinBidsFallback,
             RunOptions,
         },
         models,
-        opportunity_adapter::OpportunityBid,
-        server::{
-            EXIT_CHECK_INTERVAL,
-            SHOULD_EXIT,
+        opportunity_adapter::{
+            OpportunityBid,
+            OpportunityBidSimulation,
         },
+        server::ShutdownReceiver,
         state::{
+            BidLostReason,
+            BidRank,
             BidStatus,
             BidStatusWithId,
+            ConcludedAuction,
+            OpportunityAmounts,
             OpportunityParams,
             OpportunityParamsV1,
             SimulatedBid,
@@ -41,6 +69,7 @@ use {
             Store,
             TokenAmount,
         },
+        usage::UsageKind,
     },
     anyhow::Result,
     axum::{
@@ -49,9 +78,17 @@ use {
             self,
             FromRef,
             FromRequestParts,
+            State,
         },
         http::{
+            header::{
+                ACCEPT,
+                CONTENT_LENGTH,
+                CONTENT_TYPE,
+            },
             request::Parts,
+            HeaderMap,
+            HeaderValue,
             StatusCode,
         },
         middleware,
@@ -63,6 +100,7 @@ use {
             delete,
             get,
             post,
+            put,
         },
         Json,
         Router,
@@ -79,14 +117,17 @@ use {
         PrometheusMetricLayerBuilder,
     },
     clap::crate_version,
-    ethers::types::Bytes,
+    ethers::types::{
+        Bytes,
+        U256,
+    },
     serde::{
         Deserialize,
         Serialize,
     },
-    std::sync::{
-        atomic::Ordering,
-        Arc,
+    std::{
+        collections::HashSet,
+        sync::Arc,
     },
     time::OffsetDateTime,
     tower_http::cors::CorsLayer,
@@ -113,11 +154,39 @@ async fn root() -> String {
     format!("Express Relay Auction Server API {}", crate_version!())
 }
 
-mod bid;
+mod admin;
+mod auction;
+pub(crate) mod bid;
+mod chain;
 pub(crate) mod opportunity;
 pub mod profile;
 pub(crate) mod ws;
 
+/// Coarse reason of a temporary failure, so the clients can tell whether retrying is worth it.
+/// The details of the failure are only logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnavailableReason {
+    /// The database failed or could not be reached.
+    Db,
+    /// The blockchain node failed or could not be reached.
+    Chain,
+    /// The server is at capacity.
+    Overloaded,
+    /// An unexpected failure inside the server.
+    Internal,
+}
+
+impl UnavailableReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnavailableReason::Db => "db",
+            UnavailableReason::Chain => "chain",
+            UnavailableReason::Overloaded => "overloaded",
+            UnavailableReason::Internal => "internal",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum RestError {
     /// The request contained invalid parameters
@@ -126,6 +195,11 @@ pub enum RestError {
     InvalidOpportunity(String),
     /// The chain id is not supported
     InvalidChainId,
+    /// The chain of the submission is not configured on the server
+    UnsupportedChain {
+        chain_id:         ChainId,
+        supported_chains: Vec<ChainId>,
+    },
     /// The simulation failed
     SimulationError { result: Bytes, reason: String },
     /// The opportunity was not found
@@ -133,9 +207,13 @@ pub enum RestError {
     /// The bid was not found
     BidNotFound,
     /// Internal error occurred during processing the request
-    TemporarilyUnavailable,
+    TemporarilyUnavailable(UnavailableReason),
+    /// The usage quota of the profile is exceeded
+    TooManyRequests,
     /// Invalid auth token
     InvalidToken,
+    /// The access token does not grant the scope required by the request
+    Unauthorized,
 }
 
 impl RestError {
@@ -152,6 +230,17 @@ impl RestError {
                 StatusCode::NOT_FOUND,
                 "The chain id is not found".to_string(),
             ),
+            RestError::UnsupportedChain {
+                chain_id,
+                supported_chains,
+            } => (
+                StatusCode::NOT_FOUND,
+                format!(
+                    "Chain {} is not supported, the supported chains are: {}",
+                    chain_id,
+                    supported_chains.join(", ")
+                ),
+            ),
             RestError::SimulationError { result, reason } => (
                 StatusCode::BAD_REQUEST,
                 format!("Simulation failed: {} ({})", result, reason),
@@ -164,14 +253,25 @@ impl RestError {
                 StatusCode::NOT_FOUND,
                 "Bid with the specified id was not found".to_string(),
             ),
-            RestError::TemporarilyUnavailable => (
+            RestError::TemporarilyUnavailable(reason) => (
                 StatusCode::SERVICE_UNAVAILABLE,
-                "This service is temporarily unavailable".to_string(),
+                format!(
+                    "This service is temporarily unavailable ({})",
+                    reason.as_str()
+                ),
             ),
             RestError::InvalidToken => (
                 StatusCode::UNAUTHORIZED,
                 "Invalid authorization token".to_string(),
             ),
+            RestError::TooManyRequests => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "The monthly usage quota of the profile is exceeded".to_string(),
+            ),
+            RestError::Unauthorized => (
+                StatusCode::FORBIDDEN,
+                "The access token is not allowed to perform this request".to_string(),
+            ),
         }
     }
 }
@@ -182,6 +282,119 @@ struct ErrorBodyResponse {
     error: String,
 }
 
+const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// An error body following RFC 7807, returned when the client accepts `application/problem+json`.
+#[derive(ToSchema, Serialize, Clone)]
+pub struct ProblemDetails {
+    /// A URI reference identifying the problem type.
+    #[serde(rename = "type")]
+    #[schema(example = "urn:express-relay:error:simulation_error")]
+    pub problem_type:     String,
+    /// A short summary of the problem type.
+    #[schema(example = "Simulation failed")]
+    pub title:            String,
+    /// The HTTP status code of the response.
+    #[schema(example = 400)]
+    pub status:           u16,
+    /// A human readable explanation specific to this occurrence of the problem.
+    pub detail:           String,
+    /// The reason the bid was rejected if the problem is a failed simulation, or the reason the service is
+    /// unavailable: `db`, `chain`, `overloaded` or `internal`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason:           Option<String>,
+    /// The raw result of the failed simulation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub result:           Option<Bytes>,
+    /// The chains supported by the server, if the problem is an unsupported chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supported_chains: Option<Vec<ChainId>>,
+}
+
+impl RestError {
+    fn problem_kind(&self) -> (&'static str, &'static str) {
+        match self {
+            RestError::BadParameters(_) => ("bad_parameters", "Bad parameters"),
+            RestError::InvalidOpportunity(_) => ("invalid_opportunity", "Invalid opportunity"),
+            RestError::InvalidChainId => ("invalid_chain_id", "Chain not found"),
+            RestError::UnsupportedChain { .. } => ("unsupported_chain", "Chain not supported"),
+            RestError::SimulationError { .. } => ("simulation_error", "Simulation failed"),
+            RestError::OpportunityNotFound => ("opportunity_not_found", "Opportunity not found"),
+            RestError::BidNotFound => ("bid_not_found", "Bid not found"),
+            RestError::TemporarilyUnavailable(_) => {
+                ("temporarily_unavailable", "Service temporarily unavailable")
+            }
+            RestError::InvalidToken => ("invalid_token", "Invalid authorization token"),
+            RestError::TooManyRequests => ("too_many_requests", "Usage quota exceeded"),
+            RestError::Unauthorized => ("unauthorized", "Insufficient token scope"),
+        }
+    }
+
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        let (status, detail) = self.to_status_and_message();
+        let (kind, title) = self.problem_kind();
+        let (reason, result) = match self {
+            RestError::SimulationError { result, reason } => {
+                (Some(reason.clone()), Some(result.clone()))
+            }
+            RestError::TemporarilyUnavailable(reason) => (Some(reason.as_str().to_string()), None),
+            _ => (None, None),
+        };
+        ProblemDetails {
+            problem_type: format!("urn:express-relay:error:{}", kind),
+            title: title.to_string(),
+            status: status.as_u16(),
+            detail,
+            reason,
+            result,
+            supported_chains: match self {
+                RestError::UnsupportedChain {
+                    supported_chains, ..
+                } => Some(supported_chains.clone()),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn accepts_problem_json(request: &extract::Request) -> bool {
+    request
+        .headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_type| {
+            media_type
+                .split(';')
+                .next()
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(PROBLEM_JSON_CONTENT_TYPE))
+        })
+}
+
+/// Rewrites error responses as RFC 7807 problem details if the client asks for them in the `Accept` header.
+/// The default error body is left untouched otherwise.
+async fn problem_details_middleware(request: extract::Request, next: middleware::Next) -> Response {
+    let wants_problem_json = accepts_problem_json(&request);
+    let mut response = next.run(request).await;
+    if !wants_problem_json {
+        return response;
+    }
+    match response.extensions_mut().remove::<ProblemDetails>() {
+        Some(problem) => {
+            let status = response.status();
+            let mut problem_response = (status, Json(problem)).into_response();
+            problem_response.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+            );
+            problem_response
+        }
+        None => response,
+    }
+}
+
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -197,24 +410,83 @@ fn default_opportunity_mode() -> OpportunityMode {
 #[derive(Serialize, Deserialize, IntoParams)]
 pub struct GetOpportunitiesQueryParams {
     #[param(example = "op_sepolia", value_type = Option < String >)]
-    pub chain_id:       Option<ChainId>,
+    pub chain_id:              Option<ChainId>,
     /// Get opportunities in live or historical mode
     #[param(default = "live")]
     #[serde(default = "default_opportunity_mode")]
-    pub mode:           OpportunityMode,
+    pub mode:                  OpportunityMode,
     /// The permission key to filter the opportunities by. Used only in historical mode.
     #[param(example = "0xdeadbeef", value_type = Option< String >)]
-    pub permission_key: Option<Bytes>,
+    pub permission_key:        Option<Bytes>,
     /// The time to get the opportunities from. Used only in historical mode.
     #[param(example="2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
     #[serde(default, with = "crate::serde::nullable_datetime")]
-    pub from_time:      Option<OffsetDateTime>,
+    pub from_time:             Option<OffsetDateTime>,
+    /// The time to get the opportunities until, inclusive. Used only in historical mode.
+    #[param(example="2024-05-24T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub to_time:               Option<OffsetDateTime>,
+    /// Comma separated list of the fields to include in each opportunity. All fields are included if not provided.
+    #[param(example = "opportunity_id,chain_id,permission_key", value_type = Option<String>)]
+    pub fields:                Option<String>,
+    /// Only return the opportunities with a target call value of at least this amount, in wei.
+    #[param(example = "1000000000000000", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_u256")]
+    pub min_target_call_value: Option<U256>,
+    /// Include the removal time and reason of the opportunities which are removed, to see why they disappeared.
+    /// Used only in historical mode.
+    #[param(default = false)]
+    #[serde(default)]
+    pub include_removed:       bool,
+}
+
+/// Rejects the time ranges which end before they start.
+pub fn verify_time_range(
+    from_time: Option<OffsetDateTime>,
+    to_time: Option<OffsetDateTime>,
+) -> Result<(), RestError> {
+    match (from_time, to_time) {
+        (Some(from_time), Some(to_time)) if from_time > to_time => Err(RestError::BadParameters(
+            "from_time should not be after to_time".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Serializes the items keeping only the requested top level fields.
+/// All the fields are kept if no field is requested.
+pub fn select_fields<T: Serialize>(
+    items: Vec<T>,
+    fields: Option<&str>,
+) -> Result<Vec<serde_json::Value>, RestError> {
+    let fields: Option<HashSet<&str>> = fields.map(|fields| {
+        fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .collect()
+    });
+    items
+        .into_iter()
+        .map(|item| {
+            let mut value = serde_json::to_value(item).map_err(|e| {
+                tracing::error!("Failed to serialize response item: {}", e);
+                RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+            })?;
+            if let (Some(fields), serde_json::Value::Object(object)) = (&fields, &mut value) {
+                object.retain(|key, _| fields.contains(key.as_str()));
+            }
+            Ok(value)
+        })
+        .collect()
 }
 
 impl IntoResponse for RestError {
     fn into_response(self) -> Response {
         let (status, msg) = self.to_status_and_message();
-        (status, Json(ErrorBodyResponse { error: msg })).into_response()
+        let mut response = (status, Json(ErrorBodyResponse { error: msg })).into_response();
+        response.extensions_mut().insert(self.to_problem_details());
+        response
     }
 }
 
@@ -222,6 +494,41 @@ pub async fn live() -> Response {
     (StatusCode::OK, "OK").into_response()
 }
 
+#[derive(Serialize, ToSchema, ToResponse)]
+pub struct VersionResponse {
+    /// The version of the auction server.
+    #[schema(example = "0.10.1")]
+    pub version:     String,
+    /// The git commit the server is built from.
+    #[schema(example = "c5b3d1d4a3f1b2e5d6c7a8b9e0f1a2b3c4d5e6f7")]
+    pub git_commit:  String,
+    /// The time the server was built, formatted in rfc3339.
+    #[schema(example = "2024-05-23T21:26:57Z")]
+    pub build_time:  String,
+    /// The hash of the effective config, excluding secrets.
+    #[schema(example = "8e0d1ad6b2c4a5f3e7d9c1b3a5f7e9d1c3b5a7f9e1d3c5b7a9f1e3d5c7b9a1f3")]
+    pub config_hash: String,
+}
+
+/// Returns the version and build information of the server along with the fingerprint of its config.
+///
+/// Instances running the same build and config return the same response.
+#[utoipa::path(get, path = "/version", responses(
+    (status = 200, description = "The version of the server", body = VersionResponse),
+),)]
+pub async fn version(State(store): State<Arc<Store>>) -> Result<Json<VersionResponse>, RestError> {
+    let config_hash = store.get_config_map().fingerprint().map_err(|e| {
+        tracing::error!("Failed to compute config fingerprint: {:?}", e);
+        RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+    })?;
+    Ok(Json(VersionResponse {
+        version: crate_version!().to_string(),
+        git_commit: env!("GIT_COMMIT").to_string(),
+        build_time: env!("BUILD_TIME").to_string(),
+        config_hash,
+    }))
+}
+
 #[derive(Clone)]
 pub enum Auth {
     Admin,
@@ -229,6 +536,39 @@ pub enum Auth {
     Unauthorized,
 }
 
+impl Auth {
+    /// Authenticates a bearer token as the admin or as the owner profile of the token.
+    pub async fn from_token(
+        store: &Store,
+        token: models::AccessTokenToken,
+    ) -> Result<Self, RestError> {
+        if store.secret_key == token {
+            return Ok(Auth::Admin);
+        }
+        let profile = store.get_profile_by_token(&token).await?;
+        Ok(Auth::Authorized(token, profile))
+    }
+
+    /// Rejects authorized requests whose access token does not grant the scope.
+    /// The admin and the anonymous requests are not restricted by scopes.
+    pub async fn verify_scope(
+        &self,
+        store: &Store,
+        scope: models::AccessTokenScope,
+    ) -> Result<(), RestError> {
+        match self {
+            Auth::Authorized(token, _) => {
+                if store.get_access_token_scopes(token).await.contains(&scope) {
+                    Ok(())
+                } else {
+                    Err(RestError::Unauthorized)
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[async_trait]
 impl FromRequestParts<Arc<Store>> for Auth {
     type Rejection = RestError;
@@ -241,16 +581,7 @@ impl FromRequestParts<Arc<Store>> for Auth {
             Ok(token) => {
                 let state = Arc::from_ref(state);
                 let token: models::AccessTokenToken = token.token().to_string();
-
-                let is_admin = state.secret_key == token;
-                if is_admin {
-                    return Ok(Auth::Admin);
-                }
-
-                match state.get_profile_by_token(&token).await {
-                    Ok(profile) => Ok(Auth::Authorized(token, profile)),
-                    Err(e) => Err(e),
-                }
+                Auth::from_token(&state, token).await
             }
             Err(e) => {
                 if e.is_missing() {
@@ -269,6 +600,49 @@ async fn admin_middleware(auth: Auth, req: extract::Request, next: middleware::N
     }
 }
 
+fn content_length(headers: &HeaderMap) -> i64 {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Meters the requests of the authorized profiles and rejects them once the monthly quota is exceeded.
+/// Transferred bytes are counted from the content length, so streamed bodies are not counted.
+async fn usage_middleware(
+    State(store): State<Arc<Store>>,
+    auth: Auth,
+    req: extract::Request,
+    next: middleware::Next,
+) -> Response {
+    let (Some(usage), Auth::Authorized(_, profile)) = (&store.usage, &auth) else {
+        return next.run(req).await;
+    };
+    if usage
+        .is_quota_exceeded(
+            profile.id,
+            &[UsageKind::Requests, UsageKind::BytesTransferred],
+        )
+        .await
+    {
+        return RestError::TooManyRequests.into_response();
+    }
+    let request_bytes = content_length(req.headers());
+    let response = next.run(req).await;
+    usage
+        .record(
+            profile.id,
+            models::Usage {
+                requests:          1,
+                bids:              0,
+                bytes_transferred: request_bytes + content_length(response.headers()),
+            },
+        )
+        .await;
+    response
+}
+
 async fn require_login_middleware(
     auth: Auth,
     req: extract::Request,
@@ -301,18 +675,36 @@ macro_rules! login_required {
     };
 }
 
-pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()> {
+pub async fn start_api(
+    run_options: RunOptions,
+    store: Arc<Store>,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
     // Make sure functions included in the paths section have distinct names, otherwise some api generators will fail
     #[derive(OpenApi)]
     #[openapi(
     paths(
     bid::bid,
+    bid::bid_batch,
     bid::bid_status,
     bid::get_bids_by_time,
+    bid::get_leading_bids,
     opportunity::post_opportunity,
     opportunity::opportunity_bid,
     opportunity::get_opportunities,
+    opportunity::simulate_opportunity_bid,
+    opportunity::post_opportunity_fill,
     profile::delete_profile_access_token,
+    chain::get_supported_chains,
+    chain::get_target_contract_allowlist,
+    chain::put_target_contract_allowlist,
+    chain::get_next_auction,
+    chain::get_chain_stats,
+    chain::get_adapter_config,
+    chain::get_auction_config,
+    admin::get_admin_audits,
+    auction::get_auctions_by_time,
+    version,
     ),
     components(
     schemas(
@@ -322,29 +714,68 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
     BidEvm,
     BidStatus,
     BidStatusWithId,
+    BidLostReason,
+    BidRank,
+    BidStatusResponse,
     BidResult,
+    BidBatchResult,
+    BidAmountUnit,
     SimulatedBid,
     SimulatedBidEvm,
     SimulatedBidSvm,
     SimulatedBids,
     OpportunityParamsV1,
     OpportunityBid,
+    OpportunityBidSimulation,
+    SimulateOpportunityBid,
     OpportunityMode,
     OpportunityParams,
     OpportunityParamsWithMetadata,
+    OpportunityAmounts,
+    RemovedOpportunity,
+    models::OpportunityRemovalReason,
     TokenAmount,
     ErrorBodyResponse,
+    ProblemDetails,
     ClientRequest,
     ClientMessage,
     ServerResultMessage,
     ServerUpdateResponse,
     ServerResultResponse,
+    ConcludedAuction,
+    AdminAudit,
+    AdminAudits,
+    auction::Auction,
+    auction::Auctions,
+    AdapterConfig,
+    AuctionConfig,
+    AuctionSchedule,
+    MinBidIncrement,
+    MinBids,
+    MinBidsFallback,
+    ChainStats,
+    NextAuction,
+    SupportedChain,
+    SupportedChains,
+    models::ChainType,
+    TargetContractAllowlist,
+    VersionResponse,
     ),
     responses(
     ErrorBodyResponse,
     OpportunityParamsWithMetadata,
     BidResult,
+    BidStatusResponse,
     SimulatedBids,
+    AdminAudits,
+    auction::Auctions,
+    AdapterConfig,
+    AuctionConfig,
+    ChainStats,
+    NextAuction,
+    SupportedChains,
+    TargetContractAllowlist,
+    VersionResponse,
     ),
     ),
     tags(
@@ -372,12 +803,25 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
 
     let bid_routes = Router::new()
         .route("/", post(bid::bid))
+        .route("/batch", post(bid::bid_batch))
         .route("/", login_required!(store, get(bid::get_bids_by_time)))
+        .route(
+            "/leading",
+            login_required!(store, get(bid::get_leading_bids)),
+        )
         .route("/:bid_id", get(bid::bid_status));
     let opportunity_routes = Router::new()
         .route("/", post(opportunity::post_opportunity))
         .route("/", get(opportunity::get_opportunities))
-        .route("/:opportunity_id/bids", post(opportunity::opportunity_bid));
+        .route("/:opportunity_id/bids", post(opportunity::opportunity_bid))
+        .route(
+            "/simulate",
+            login_required!(store, post(opportunity::simulate_opportunity_bid)),
+        )
+        .route(
+            "/:opportunity_id/fills",
+            admin_only!(store, post(opportunity::post_opportunity_fill)),
+        );
     let profile_routes = Router::new()
         .route("/", admin_only!(store, post(profile::post_profile)))
         .route(
@@ -388,6 +832,27 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
             "/access_tokens",
             login_required!(store, delete(profile::delete_profile_access_token)),
         );
+    let chain_routes = Router::new()
+        .route("/", get(chain::get_supported_chains))
+        .route(
+            "/:chain_id/target_contracts",
+            admin_only!(store, get(chain::get_target_contract_allowlist)),
+        )
+        .route(
+            "/:chain_id/target_contracts",
+            admin_only!(store, put(chain::put_target_contract_allowlist)),
+        )
+        .route("/:chain_id/next_auction", get(chain::get_next_auction))
+        .route("/:chain_id/adapter", get(chain::get_adapter_config))
+        .route("/:chain_id/auction_config", get(chain::get_auction_config))
+        .route(
+            "/:chain_id/stats",
+            admin_only!(store, get(chain::get_chain_stats)),
+        );
+    let admin_routes =
+        Router::new().route("/audit", admin_only!(store, get(admin::get_admin_audits)));
+    let auction_routes =
+        Router::new().route("/", admin_only!(store, get(auction::get_auctions_by_time)));
 
     let v1_routes = Router::new().nest(
         "/v1",
@@ -395,6 +860,9 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
             .nest("/bids", bid_routes)
             .nest("/opportunities", opportunity_routes)
             .nest("/profiles", profile_routes)
+            .nest("/chains", chain_routes)
+            .nest("/admin", admin_routes)
+            .nest("/auctions", auction_routes)
             .route("/ws", get(ws::ws_route_handler)),
     );
 
@@ -410,6 +878,12 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
         .merge(v1_routes)
         .route("/", get(root))
         .route("/live", get(live))
+        .route("/version", get(version))
+        .layer(middleware::from_fn_with_state(
+            store.clone(),
+            usage_middleware,
+        ))
+        .layer(middleware::from_fn(problem_details_middleware))
         .layer(CorsLayer::permissive())
         .layer(middleware::from_extractor_with_state::<Auth, Arc<Store>>(
             store.clone(),
@@ -419,10 +893,8 @@ pub async fn start_api(run_options: RunOptions, store: Arc<Store>) -> Result<()>
 
     let listener = tokio::net::TcpListener::bind(&run_options.server.listen_addr).await?;
     axum::serve(listener, app)
-        .with_graceful_shutdown(async {
-            while !SHOULD_EXIT.load(Ordering::Acquire) {
-                tokio::time::sleep(EXIT_CHECK_INTERVAL).await;
-            }
+        .with_graceful_shutdown(async move {
+            shutdown.recv().await;
             tracing::info!("Shutting down RPC server...");
         })
         .await?;
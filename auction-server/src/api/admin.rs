@@ -0,0 +1,118 @@
+use {
+    crate::{
+        api::{
+            ErrorBodyResponse,
+            RestError,
+        },
+        models,
+        state::Store,
+    },
+    axum::{
+        extract::{
+            Query,
+            State,
+        },
+        Json,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    sqlx::types::{
+        time::{
+            OffsetDateTime,
+            UtcOffset,
+        },
+        JsonValue,
+    },
+    std::sync::Arc,
+    utoipa::{
+        IntoParams,
+        ToResponse,
+        ToSchema,
+    },
+};
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct AdminAudit {
+    /// The unique id of the audit entry.
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = String)]
+    pub id:                models::AdminAuditId,
+    /// The time the action was taken formatted in rfc3339.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = String)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub creation_time:     OffsetDateTime,
+    /// The name of the action.
+    #[schema(example = "create_profile")]
+    pub action:            String,
+    /// The parameters the action was taken with.
+    #[schema(value_type = Object)]
+    pub parameters:        JsonValue,
+    /// The identity of the admin who took the action.
+    #[schema(example = "secret_key")]
+    pub admin_identity:    String,
+    /// The profile which took the action. Null if it was taken with the admin secret key.
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    pub actor_profile_id:  Option<models::ProfileId>,
+    /// The profile which was changed, for the profile and access token changes.
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    pub target_profile_id: Option<models::ProfileId>,
+    /// The id of the access token which was changed, if any. The token itself is never recorded.
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    pub target_token_id:   Option<models::TokenId>,
+}
+
+impl From<models::AdminAudit> for AdminAudit {
+    fn from(audit: models::AdminAudit) -> Self {
+        AdminAudit {
+            id:                audit.id,
+            creation_time:     audit.creation_time.assume_offset(UtcOffset::UTC),
+            action:            audit.action,
+            parameters:        audit.parameters,
+            admin_identity:    audit.admin_identity,
+            actor_profile_id:  audit.actor_profile_id,
+            target_profile_id: audit.target_profile_id,
+            target_token_id:   audit.target_token_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToResponse, ToSchema, Clone)]
+pub struct AdminAudits {
+    pub items: Vec<AdminAudit>,
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+pub struct GetAdminAuditsQueryParams {
+    /// The profile to get the actions taken by or on. All the actions are returned if not set.
+    #[param(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    #[serde(default)]
+    pub profile_id: Option<models::ProfileId>,
+    #[param(example="2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub from_time:  Option<OffsetDateTime>,
+}
+
+/// Returns at most 20 admin audit entries which were created after a specific time.
+/// If no time is provided, the server will return the first entries. If a profile is provided,
+/// only the actions taken by or on the profile and its access tokens are returned.
+#[utoipa::path(get, path = "/v1/admin/audit",
+    security(
+        ("bearerAuth" = []),
+    ),
+    responses(
+    (status = 200, description = "Paginated list of admin actions", body = AdminAudits),
+    (status = 400, response = ErrorBodyResponse),
+),  params(GetAdminAuditsQueryParams),
+)]
+pub async fn get_admin_audits(
+    State(store): State<Arc<Store>>,
+    query: Query<GetAdminAuditsQueryParams>,
+) -> Result<Json<AdminAudits>, RestError> {
+    let audits = store
+        .get_admin_audits_by_time(query.profile_id, query.from_time)
+        .await?;
+    Ok(Json(AdminAudits {
+        items: audits.into_iter().map(AdminAudit::from).collect(),
+    }))
+}
@@ -0,0 +1,135 @@
+use {
+    crate::{
+        api::{
+            ErrorBodyResponse,
+            RestError,
+        },
+        config::ChainId,
+        models,
+        state::{
+            PermissionKey,
+            Store,
+        },
+    },
+    axum::{
+        extract::{
+            Query,
+            State,
+        },
+        Json,
+    },
+    ethers::types::Bytes,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    sqlx::types::time::{
+        OffsetDateTime,
+        PrimitiveDateTime,
+    },
+    std::sync::Arc,
+    utoipa::{
+        IntoParams,
+        ToResponse,
+        ToSchema,
+    },
+};
+
+#[derive(Serialize, Deserialize, IntoParams)]
+pub struct GetAuctionsByTimeQueryParams {
+    /// The chain id to get the auctions for.
+    #[param(example = "op_sepolia", value_type = String)]
+    pub chain_id:  ChainId,
+    /// Only return the auctions which were created since this time, inclusive.
+    #[param(example="2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub from_time: Option<OffsetDateTime>,
+    /// Maximum number of auctions to return, between 1 and 100. Defaults to 20.
+    #[param(example = 20)]
+    pub limit:     Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub struct Auction {
+    /// The unique id of the auction.
+    #[schema(example = "beedbeed-58cc-4372-a567-0e02b2c3d479", value_type = String)]
+    pub id:              models::AuctionId,
+    /// The permission key of the auction.
+    #[schema(example = "0xdeadbeef", value_type = String)]
+    pub permission_key:  PermissionKey,
+    /// The time the auction was created formatted in rfc3339.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = String)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub creation_time:   OffsetDateTime,
+    /// The time the auction was submitted on chain formatted in rfc3339. Null if it was not submitted.
+    #[schema(example = "2024-05-23T21:26:58.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub submission_time: Option<OffsetDateTime>,
+    /// The time the auction was concluded formatted in rfc3339. Null if it was not concluded.
+    #[schema(example = "2024-05-23T21:27:02.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub conclusion_time: Option<OffsetDateTime>,
+    /// The hash of the submitted transaction in hex. Null if the auction was not submitted.
+    #[schema(example = "0x103d2d9a8e9b6e2ae1f6d9a0de5a8e10f1f3d0d8bdb1e9c5b0d3c5a0e7fb5f8a", value_type = Option<String>)]
+    pub tx_hash:         Option<Bytes>,
+    /// The number of the bids of the auction which won on chain.
+    #[schema(example = 1)]
+    pub won_bid_count:   i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct Auctions {
+    pub items: Vec<Auction>,
+}
+
+fn to_offset_date_time(time: PrimitiveDateTime) -> OffsetDateTime {
+    time.assume_utc()
+}
+
+impl From<models::AuctionWithWonBids> for Auction {
+    fn from(auction: models::AuctionWithWonBids) -> Self {
+        let models::AuctionWithWonBids {
+            auction,
+            won_bid_count,
+        } = auction;
+        Auction {
+            id: auction.id,
+            permission_key: Bytes::from(auction.permission_key),
+            creation_time: to_offset_date_time(auction.creation_time),
+            submission_time: auction.submission_time.map(to_offset_date_time),
+            conclusion_time: auction.conclusion_time.map(to_offset_date_time),
+            tx_hash: auction.tx_hash.map(Bytes::from),
+            won_bid_count,
+        }
+    }
+}
+
+/// Returns the auctions of a chain which were created after a specific time, ordered by the creation time.
+///
+/// The auctions include their submission and conclusion on chain, so they can be reconciled with the settled
+/// transactions.
+#[utoipa::path(get, path = "/v1/auctions",
+security(
+    ("bearerAuth" = []),
+),
+params(GetAuctionsByTimeQueryParams),
+responses(
+(status = 200, description = "The auctions of the chain", body = Auctions),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_auctions_by_time(
+    State(store): State<Arc<Store>>,
+    Query(query_params): Query<GetAuctionsByTimeQueryParams>,
+) -> Result<Json<Auctions>, RestError> {
+    let chain_id = query_params.chain_id;
+    if !store.chains.contains_key(&chain_id) && !store.chains_svm.contains_key(&chain_id) {
+        return Err(RestError::InvalidChainId);
+    }
+    let auctions = store
+        .get_auctions_by_time(&chain_id, query_params.from_time, query_params.limit)
+        .await?;
+    Ok(Json(Auctions {
+        items: auctions.into_iter().map(Auction::from).collect(),
+    }))
+}
@@ -2,20 +2,33 @@ use {
     super::Auth,
     crate::{
         api::{
+            select_fields,
+            verify_time_range,
             ErrorBodyResponse,
             RestError,
+            UnavailableReason,
         },
         auction::{
-            handle_bid,
-            handle_bid_svm,
+            verify_bid,
+            verify_bid_svm,
             Bid,
         },
+        bid_queue::BidLane,
+        config::ChainId,
+        models::{
+            AccessTokenScope,
+            ProfileId,
+        },
         state::{
+            BidAmount,
             BidId,
+            BidRank,
             BidStatus,
+            IdempotencyKey,
             SimulatedBid,
             Store,
         },
+        usage::UsageKind,
     },
     axum::{
         extract::{
@@ -23,13 +36,29 @@ use {
             Query,
             State,
         },
+        http::{
+            header,
+            HeaderMap,
+            HeaderValue,
+        },
         Json,
     },
+    ethers::{
+        types::H256,
+        utils::keccak256,
+    },
+    futures::future::join_all,
     serde::{
         Deserialize,
         Serialize,
     },
-    sqlx::types::time::OffsetDateTime,
+    serde_json::json,
+    solana_sdk::native_token::LAMPORTS_PER_SOL,
+    sqlx::types::time::{
+        format_description::well_known::Rfc3339,
+        OffsetDateTime,
+        UtcOffset,
+    },
     std::sync::Arc,
     utoipa::{
         IntoParams,
@@ -46,11 +75,35 @@ pub struct BidResult {
     pub id:     BidId,
 }
 
+/// Header carrying the idempotency key of a bid submission.
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+fn get_idempotency_key(headers: &HeaderMap) -> Result<Option<String>, RestError> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    match value.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => {
+            Ok(Some(key.to_string()))
+        }
+        _ => Err(RestError::BadParameters(format!(
+            "Idempotency key should be a visible ASCII string of at most {} characters",
+            MAX_IDEMPOTENCY_KEY_LENGTH
+        ))),
+    }
+}
+
 /// Bid on a specific permission key for a specific chain.
 ///
 /// Your bid will be verified by the server. Depending on the outcome of the auction, a transaction
 /// containing your bid will be sent to the blockchain expecting the bid amount to be paid in the transaction.
-#[utoipa::path(post, path = "/v1/bids", request_body = Bid, responses(
+///
+/// Authenticated searchers can set an `Idempotency-Key` header to retry a submission safely. A retry with the
+/// same key and bid returns the id of the original bid instead of placing it again.
+#[utoipa::path(post, path = "/v1/bids", request_body = Bid, params(
+    ("Idempotency-Key" = Option<String>, Header, description = "Key to deduplicate the retries of a submission"),
+), responses(
     (status = 200, description = "Bid was placed successfully", body = BidResult,
     example = json!({"status": "OK", "id": "beedbeed-b346-4fa1-8fab-2541a9e1872d"})),
     (status = 400, response = ErrorBodyResponse),
@@ -59,20 +112,84 @@ pub struct BidResult {
 pub async fn bid(
     auth: Auth,
     State(store): State<Arc<Store>>,
+    headers: HeaderMap,
     Json(bid): Json<Bid>,
 ) -> Result<Json<BidResult>, RestError> {
-    process_bid(store, bid, auth).await
+    let idempotency_key = get_idempotency_key(&headers)?;
+    process_bid(store, bid, auth, idempotency_key).await
 }
 
 pub async fn process_bid(
     store: Arc<Store>,
     bid: Bid,
     auth: Auth,
+    idempotency_key: Option<String>,
 ) -> Result<Json<BidResult>, RestError> {
-    let result = match bid {
-        Bid::Evm(bid_evm) => handle_bid(store, bid_evm, OffsetDateTime::now_utc(), auth).await,
-        Bid::Svm(bid_svm) => handle_bid_svm(store, bid_svm, OffsetDateTime::now_utc(), auth).await,
+    let initiation_time = OffsetDateTime::now_utc();
+    auth.verify_scope(&store, AccessTokenScope::Submit).await?;
+    let idempotency_key = match idempotency_key {
+        Some(key) => {
+            let Some(profile_id) = get_profile_id(&auth) else {
+                return Err(RestError::BadParameters(
+                    "Idempotency keys are only supported for authenticated searchers".to_string(),
+                ));
+            };
+            let idempotency_key = IdempotencyKey {
+                key,
+                payload_hash: H256(keccak256(serde_json::to_vec(&bid).map_err(|e| {
+                    tracing::error!("Failed to serialize bid: {}", e);
+                    RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+                })?)),
+            };
+            // A retry of a placed bid is answered without placing it again
+            if let Some(id) = store
+                .get_idempotent_bid(profile_id, &idempotency_key)
+                .await?
+            {
+                return Ok(BidResult {
+                    status: "OK".to_string(),
+                    id,
+                }
+                .into());
+            }
+            Some(idempotency_key)
+        }
+        None => None,
     };
+    store.verify_chain_supported(&get_chain_id(&bid))?;
+    verify_bid_quota(&store, &auth).await?;
+    store
+        .bid_queue
+        .process(
+            BidLane::from_auth(&auth),
+            place_bid(store.clone(), bid, initiation_time, auth, idempotency_key),
+        )
+        .await
+}
+
+async fn place_bid(
+    store: Arc<Store>,
+    bid: Bid,
+    initiation_time: OffsetDateTime,
+    auth: Auth,
+    idempotency_key: Option<IdempotencyKey>,
+) -> Result<Json<BidResult>, RestError> {
+    let rejected_bid = store.persist_rejected_bids.then(|| bid.clone());
+    let profile_id = get_profile_id(&auth);
+    let (chain_id, result) = verify_any_bid(store.clone(), bid, initiation_time, auth).await;
+    let result = match result {
+        Ok(bid) => store.add_bid(bid, idempotency_key).await,
+        Err(e) => Err(e),
+    };
+    record_bid_result(
+        &store,
+        rejected_bid,
+        chain_id,
+        profile_id,
+        &result,
+        initiation_time,
+    )
+    .await;
     match result {
         Ok(id) => Ok(BidResult {
             status: "OK".to_string(),
@@ -83,38 +200,347 @@ pub async fn process_bid(
     }
 }
 
+/// Maximum number of bids accepted in a single batch.
+const MAX_BID_BATCH_SIZE: usize = 20;
+
+/// Result of a bid submitted in a batch.
+#[derive(Serialize, Deserialize, ToResponse, ToSchema, Clone)]
+pub struct BidBatchResult {
+    /// Either OK if the bid was placed, or error if it was rejected.
+    pub status: String,
+    /// The unique id created to identify the bid, if it was placed.
+    #[schema(example = "beedbeed-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id:     Option<BidId>,
+    /// The reason the bid was rejected.
+    #[schema(example = "Bad parameters: Invalid chain id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error:  Option<String>,
+}
+
+/// Bid on several permission keys at once.
+///
+/// Each bid is verified and placed on its own, so the rejection of a bid does not affect the others.
+/// The results are returned in the order of the submitted bids.
+#[utoipa::path(post, path = "/v1/bids/batch", request_body = Vec<Bid>, responses(
+    (status = 200, description = "Result of each bid in the batch", body = Vec<BidBatchResult>,
+    example = json!([{"status": "OK", "id": "beedbeed-b346-4fa1-8fab-2541a9e1872d"}, {"status": "error", "error": "Bad parameters: Invalid chain id"}])),
+    (status = 400, response = ErrorBodyResponse),
+),)]
+pub async fn bid_batch(
+    auth: Auth,
+    State(store): State<Arc<Store>>,
+    Json(bids): Json<Vec<Bid>>,
+) -> Result<Json<Vec<BidBatchResult>>, RestError> {
+    if bids.is_empty() || bids.len() > MAX_BID_BATCH_SIZE {
+        return Err(RestError::BadParameters(format!(
+            "Batch should include between 1 and {} bids",
+            MAX_BID_BATCH_SIZE
+        )));
+    }
+    let initiation_time = OffsetDateTime::now_utc();
+    auth.verify_scope(&store, AccessTokenScope::Submit).await?;
+    verify_bid_quota(&store, &auth).await?;
+    let profile_id = get_profile_id(&auth);
+
+    // Each bid takes its own turn in the queue, so a batch does not hold a worker for all of its bids
+    let lane = BidLane::from_auth(&auth);
+    let verified_bids = join_all(bids.iter().cloned().map(|bid| {
+        let (store, auth) = (store.clone(), auth.clone());
+        async move {
+            let chain_id = get_chain_id(&bid);
+            if let Err(e) = store.verify_chain_supported(&chain_id) {
+                return (chain_id, Err(e));
+            }
+            let verification = verify_any_bid(store.clone(), bid, initiation_time, auth);
+            match store
+                .bid_queue
+                .process(lane, async move { Ok(verification.await) })
+                .await
+            {
+                Ok(verified_bid) => verified_bid,
+                Err(e) => (chain_id, Err(e)),
+            }
+        }
+    }))
+    .await;
+    let mut results = Vec::with_capacity(bids.len());
+    let mut bids_to_add = Vec::new();
+    for (chain_id, result) in verified_bids {
+        match result {
+            Ok(bid) => {
+                bids_to_add.push(bid);
+                results.push((chain_id, None));
+            }
+            Err(e) => results.push((chain_id, Some(Err(e)))),
+        }
+    }
+    let mut added_bids = store.add_bids(bids_to_add).await.into_iter();
+
+    let mut response = Vec::with_capacity(results.len());
+    for (bid, (chain_id, result)) in bids.into_iter().zip(results) {
+        let result = match result {
+            Some(result) => result,
+            None => added_bids
+                .next()
+                .expect("Each verified bid should have a result"),
+        };
+        let rejected_bid = store.persist_rejected_bids.then_some(bid);
+        record_bid_result(
+            &store,
+            rejected_bid,
+            chain_id,
+            profile_id,
+            &result,
+            initiation_time,
+        )
+        .await;
+        response.push(match result {
+            Ok(id) => BidBatchResult {
+                status: "OK".to_string(),
+                id:     Some(id),
+                error:  None,
+            },
+            Err(e) => BidBatchResult {
+                status: "error".to_string(),
+                id:     None,
+                error:  Some(e.to_status_and_message().1),
+            },
+        });
+    }
+    Ok(Json(response))
+}
+
+async fn verify_bid_quota(store: &Store, auth: &Auth) -> Result<(), RestError> {
+    if let (Some(usage), Auth::Authorized(_, profile)) = (&store.usage, auth) {
+        if usage
+            .is_quota_exceeded(profile.id, &[UsageKind::Bids])
+            .await
+        {
+            return Err(RestError::TooManyRequests);
+        }
+    }
+    Ok(())
+}
+
+fn get_profile_id(auth: &Auth) -> Option<ProfileId> {
+    match auth {
+        Auth::Authorized(_, profile) => Some(profile.id),
+        _ => None,
+    }
+}
+
+fn get_chain_id(bid: &Bid) -> ChainId {
+    match bid {
+        Bid::Evm(bid_evm) => bid_evm.chain_id.clone(),
+        Bid::Svm(bid_svm) => bid_svm.chain_id.clone(),
+    }
+}
+
+/// Verifies the bid of any chain type, returning the chain id of the bid along with the verification result.
+async fn verify_any_bid(
+    store: Arc<Store>,
+    bid: Bid,
+    initiation_time: OffsetDateTime,
+    auth: Auth,
+) -> (ChainId, Result<SimulatedBid, RestError>) {
+    let chain_id = get_chain_id(&bid);
+    let result = match bid {
+        Bid::Evm(bid_evm) => verify_bid(store, bid_evm, initiation_time, auth)
+            .await
+            .map(SimulatedBid::from),
+        Bid::Svm(bid_svm) => verify_bid_svm(store, bid_svm, initiation_time, auth)
+            .await
+            .map(SimulatedBid::from),
+    };
+    (chain_id, result)
+}
+
+/// Stores the rejected bid, if rejected bids are persisted, and records the acknowledgment latency of the bid.
+async fn record_bid_result(
+    store: &Store,
+    rejected_bid: Option<Bid>,
+    chain_id: ChainId,
+    profile_id: Option<ProfileId>,
+    result: &Result<BidId, RestError>,
+    initiation_time: OffsetDateTime,
+) {
+    if let (Some(bid), Err(e)) = (rejected_bid, result) {
+        // Failures on the server side are not caused by the bid itself
+        if !matches!(e, RestError::TemporarilyUnavailable(_)) {
+            store.add_rejected_bid(&bid, &chain_id, profile_id, e).await;
+        }
+    }
+    // Chain ids of the rejected bids are not recorded to keep the label cardinality bounded.
+    if !matches!(
+        result,
+        Err(RestError::InvalidChainId | RestError::UnsupportedChain { .. })
+    ) {
+        let labels = [
+            ("chain_id", chain_id),
+            (
+                "result",
+                if result.is_ok() { "success" } else { "error" }.to_string(),
+            ),
+        ];
+        // Measures the bid ingestion only, the auction latency is not included.
+        metrics::histogram!("bid_acknowledgment_duration_seconds", &labels)
+            .record((OffsetDateTime::now_utc() - initiation_time).as_seconds_f64());
+    }
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+pub struct GetBidStatusQueryParams {
+    /// Include the rank of the bid among the competing bids if the bid is pending.
+    #[serde(default)]
+    pub include_rank: bool,
+}
+
+#[derive(Serialize, Deserialize, ToResponse, ToSchema, Clone)]
+pub struct BidStatusResponse {
+    #[serde(flatten)]
+    pub status: BidStatus,
+    /// The rank of the bid among the pending bids for the same permission key.
+    /// Only included for pending bids when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank:   Option<BidRank>,
+}
+
 /// Query the status of a specific bid.
+///
+/// The rank of a pending bid can be included, which only reveals the position of the bid and the number of
+/// competing bids.
 #[utoipa::path(get, path = "/v1/bids/{bid_id}",
-    params(("bid_id"=String, description = "Bid id to query for")),
+    params(("bid_id"=String, description = "Bid id to query for"), GetBidStatusQueryParams),
     responses(
-    (status = 200, description = "Latest status of the bid", body = BidStatus),
+    (status = 200, description = "Latest status of the bid", body = BidStatusResponse),
     (status = 400, response = ErrorBodyResponse),
     (status = 404, description = "Bid was not found", body = ErrorBodyResponse),
 ),)]
 pub async fn bid_status(
     State(store): State<Arc<Store>>,
     Path(bid_id): Path<BidId>,
-) -> Result<Json<BidStatus>, RestError> {
-    let status_json = store.get_bid_status(bid_id).await?;
+    Query(query_params): Query<GetBidStatusQueryParams>,
+) -> Result<Json<BidStatusResponse>, RestError> {
+    let Json(status) = store.get_bid_status(bid_id).await?;
+    let rank = match status {
+        BidStatus::Pending if query_params.include_rank => store.get_bid_rank(bid_id).await,
+        _ => None,
+    };
 
-    Ok(status_json)
+    Ok(Json(BidStatusResponse { status, rank }))
 }
 
 #[derive(Serialize, Deserialize, ToResponse, ToSchema, Clone)]
 #[schema(title = "BidsResponse")]
 pub struct SimulatedBids {
-    pub items: Vec<SimulatedBid>,
+    pub items:       Vec<SimulatedBid>,
+    /// The cursor to pass as `after_id` to get the next page. Only set for paginated lists with a next page.
+    #[schema(example = "beedbeed-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<BidId>,
+}
+
+/// Returns the bids of the searcher which are currently winning their auctions.
+///
+/// The bids are ranked by the live pending bids of each permission key, so the result can change until the
+/// auctions are concluded.
+#[utoipa::path(get, path = "/v1/bids/leading",
+    security(
+        ("bearerAuth" = []),
+    ),
+    responses(
+    (status = 200, description = "List of the leading bids of the searcher", body = SimulatedBids),
+    (status = 400, response = ErrorBodyResponse),
+),)]
+pub async fn get_leading_bids(
+    auth: Auth,
+    State(store): State<Arc<Store>>,
+) -> Result<Json<SimulatedBids>, RestError> {
+    auth.verify_scope(&store, AccessTokenScope::Read).await?;
+    match auth {
+        Auth::Authorized(_, profile) => Ok(Json(SimulatedBids {
+            items:       store.get_leading_bids(profile.id).await,
+            next_cursor: None,
+        })),
+        _ => {
+            tracing::error!("Unauthorized access to get_leading_bids");
+            Err(RestError::TemporarilyUnavailable(
+                UnavailableReason::Internal,
+            ))
+        }
+    }
+}
+
+/// Unit of the bid amounts in the responses.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BidAmountUnit {
+    /// The base unit of the chain, i.e. wei or lamports.
+    #[default]
+    Base,
+    /// SOL for the SVM bids, returned in `bid_amount_sol` along with the exact `bid_amount` in lamports.
+    Sol,
+}
+
+const SOL_DECIMALS: usize = 9;
+
+/// Formats an amount in lamports as a decimal SOL string, without any loss of precision.
+fn format_lamports_as_sol(lamports: BidAmount) -> String {
+    let lamports_per_sol = BidAmount::from(LAMPORTS_PER_SOL);
+    format!(
+        "{}.{:0>width$}",
+        lamports / lamports_per_sol,
+        (lamports % lamports_per_sol).to_string(),
+        width = SOL_DECIMALS
+    )
+}
+
+fn get_bid_amount_sol(bid: &SimulatedBid) -> Option<String> {
+    match bid {
+        SimulatedBid::Svm(bid) => Some(format_lamports_as_sol(bid.core_fields.bid_amount)),
+        SimulatedBid::Evm(_) => None,
+    }
+}
+
+/// Adds the SOL amounts next to the lamport amounts of the SVM bids, if the bid amount is selected.
+fn add_sol_amounts(items: &mut [serde_json::Value], sol_amounts: Vec<Option<String>>) {
+    for (item, sol_amount) in items.iter_mut().zip(sol_amounts) {
+        if let (serde_json::Value::Object(object), Some(sol_amount)) = (item, sol_amount) {
+            if object.contains_key("bid_amount") {
+                object.insert("bid_amount_sol".to_string(), sol_amount.into());
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, IntoParams)]
 pub struct GetBidsByTimeQueryParams {
     #[param(example="2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
     #[serde(default, with = "crate::serde::nullable_datetime")]
-    pub from_time: Option<OffsetDateTime>,
+    pub from_time:   Option<OffsetDateTime>,
+    /// Only return the bids which were submitted until this time, inclusive.
+    #[param(example="2024-05-24T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub to_time:     Option<OffsetDateTime>,
+    /// Comma separated list of the fields to include in each bid. All fields are included if not provided.
+    #[param(example = "id,status,bid_amount", value_type = Option<String>)]
+    pub fields:      Option<String>,
+    /// Return the bids after the bid with this id. Use the `next_cursor` of the previous page.
+    #[param(example = "beedbeed-58cc-4372-a567-0e02b2c3d479", value_type = Option<String>)]
+    pub after_id:    Option<BidId>,
+    /// Maximum number of bids to return, between 1 and 100. Defaults to 20.
+    #[param(example = 20)]
+    pub limit:       Option<usize>,
+    /// Unit of the bid amounts. With `sol`, the SVM bids also include their amount as a decimal SOL string.
+    #[param(example = "sol")]
+    #[serde(default)]
+    pub amount_unit: BidAmountUnit,
 }
 
-/// Returns at most 20 bids which were submitted after a specific time.
+/// Returns a page of bids which were submitted after a specific time, ordered by the submission time.
 /// If no time is provided, the server will return the first bids.
+/// The next page can be fetched with the `next_cursor` of the response or the `Link` header.
 #[utoipa::path(get, path = "/v1/bids",
     security(
         ("bearerAuth" = []),
@@ -128,17 +554,60 @@ pub async fn get_bids_by_time(
     auth: Auth,
     State(store): State<Arc<Store>>,
     query: Query<GetBidsByTimeQueryParams>,
-) -> Result<Json<SimulatedBids>, RestError> {
+) -> Result<(HeaderMap, Json<serde_json::Value>), RestError> {
+    auth.verify_scope(&store, AccessTokenScope::Read).await?;
     match auth {
         Auth::Authorized(_, profile) => {
-            let bids = store
-                .get_simulated_bids_by_time(profile.id, query.from_time)
+            verify_time_range(query.from_time, query.to_time)?;
+            let (bids, next_cursor) = store
+                .get_simulated_bids_by_time(
+                    profile.id,
+                    query.from_time,
+                    query.to_time,
+                    query.after_id,
+                    query.limit,
+                )
                 .await?;
-            Ok(Json(SimulatedBids { items: bids }))
+            let sol_amounts: Vec<Option<String>> = match query.amount_unit {
+                BidAmountUnit::Sol => bids.iter().map(get_bid_amount_sol).collect(),
+                BidAmountUnit::Base => vec![],
+            };
+            let mut items = select_fields(bids, query.fields.as_deref())?;
+            add_sol_amounts(&mut items, sol_amounts);
+            let mut headers = HeaderMap::new();
+            let Some(next_cursor) = next_cursor else {
+                return Ok((headers, Json(json!({ "items": items }))));
+            };
+            let mut next_page = format!("/v1/bids?after_id={}", next_cursor);
+            if let Some(limit) = query.limit {
+                next_page.push_str(&format!("&limit={}", limit));
+            }
+            if let Some(to_time) = query.to_time {
+                let to_time = to_time
+                    .to_offset(UtcOffset::UTC)
+                    .format(&Rfc3339)
+                    .map_err(|_| RestError::BadParameters("Invalid to_time".to_string()))?;
+                next_page.push_str(&format!("&to_time={}", to_time));
+            }
+            if let Some(fields) = query.fields.as_deref() {
+                next_page.push_str(&format!("&fields={}", fields));
+            }
+            if query.amount_unit == BidAmountUnit::Sol {
+                next_page.push_str("&amount_unit=sol");
+            }
+            if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"next\"", next_page)) {
+                headers.insert(header::LINK, link);
+            }
+            Ok((
+                headers,
+                Json(json!({ "items": items, "next_cursor": next_cursor })),
+            ))
         }
         _ => {
             tracing::error!("Unauthorized access to get_bids_by_time");
-            Err(RestError::TemporarilyUnavailable)
+            Err(RestError::TemporarilyUnavailable(
+                UnavailableReason::Internal,
+            ))
         }
     }
 }
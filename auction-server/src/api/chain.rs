@@ -0,0 +1,454 @@
+use {
+    super::Auth,
+    crate::{
+        api::{
+            ErrorBodyResponse,
+            RestError,
+            UnavailableReason,
+        },
+        auction::{
+            next_grid_time,
+            ChainStore,
+        },
+        config::{
+            AuctionSchedule,
+            ChainId,
+            MinBidIncrement,
+            MinBids,
+        },
+        models,
+        state::{
+            BidAmount,
+            ChainStoreEvm,
+            ChainStoreSvm,
+            PermissionKey,
+            Store,
+        },
+    },
+    axum::{
+        extract::{
+            Path,
+            Query,
+            State,
+        },
+        Json,
+    },
+    ethers::types::{
+        Address,
+        H256,
+    },
+    serde::{
+        Deserialize,
+        Serialize,
+    },
+    serde_json::json,
+    sqlx::types::time::{
+        Duration,
+        OffsetDateTime,
+    },
+    std::sync::Arc,
+    utoipa::{
+        IntoParams,
+        ToResponse,
+        ToSchema,
+    },
+};
+
+#[derive(Serialize, ToSchema, Clone)]
+pub struct SupportedChain {
+    /// The chain id.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:   ChainId,
+    /// The type of the chain.
+    pub chain_type: models::ChainType,
+}
+
+#[derive(Serialize, ToSchema, Clone, ToResponse)]
+pub struct SupportedChains {
+    pub items: Vec<SupportedChain>,
+}
+
+/// Get the chains supported by the server along with their chain type.
+#[utoipa::path(get, path = "/v1/chains",
+responses(
+(status = 200, description = "The chains supported by the server", body = SupportedChains),
+),)]
+pub async fn get_supported_chains(State(store): State<Arc<Store>>) -> Json<SupportedChains> {
+    Json(SupportedChains {
+        items: store
+            .supported_chains()
+            .into_iter()
+            .map(|(chain_id, chain_type)| SupportedChain {
+                chain_id,
+                chain_type,
+            })
+            .collect(),
+    })
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct TargetContractAllowlist {
+    /// The target contracts which bids are allowed to call. All contracts are allowed if empty.
+    #[schema(example = json!(["0xcA11bde05977b3631167028862bE2a173976CA11"]), value_type = Vec<String>)]
+    pub target_contracts: Vec<Address>,
+}
+
+/// Get the allowlist of target contracts for EVM bids on a specific chain.
+#[utoipa::path(get, path = "/v1/chains/{chain_id}/target_contracts",
+security(
+    ("bearerAuth" = []),
+),
+params(("chain_id"=String, description = "The chain id to get the allowlist for")),
+responses(
+(status = 200, description = "The allowlist of target contracts", body = TargetContractAllowlist),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_target_contract_allowlist(
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+) -> Result<Json<TargetContractAllowlist>, RestError> {
+    let chain_store = store
+        .chains
+        .get(&chain_id)
+        .ok_or(RestError::InvalidChainId)?;
+    let mut target_contracts: Vec<Address> = chain_store
+        .target_contract_allowlist
+        .read()
+        .await
+        .iter()
+        .cloned()
+        .collect();
+    target_contracts.sort();
+    Ok(Json(TargetContractAllowlist { target_contracts }))
+}
+
+/// Replace the allowlist of target contracts for EVM bids on a specific chain.
+///
+/// Bids with a target contract outside of the allowlist are rejected. An empty allowlist allows all contracts.
+#[utoipa::path(put, path = "/v1/chains/{chain_id}/target_contracts",
+security(
+    ("bearerAuth" = []),
+),
+params(("chain_id"=String, description = "The chain id to update the allowlist for")),
+request_body = TargetContractAllowlist,
+responses(
+(status = 200, description = "The updated allowlist of target contracts", body = TargetContractAllowlist),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn put_target_contract_allowlist(
+    auth: Auth,
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+    Json(params): Json<TargetContractAllowlist>,
+) -> Result<Json<TargetContractAllowlist>, RestError> {
+    let chain_store = store
+        .chains
+        .get(&chain_id)
+        .ok_or(RestError::InvalidChainId)?;
+    *chain_store.target_contract_allowlist.write().await =
+        params.target_contracts.iter().cloned().collect();
+    store
+        .add_admin_audit(
+            &auth,
+            "update_target_contract_allowlist",
+            &json!({ "chain_id": chain_id, "target_contracts": params.target_contracts }),
+        )
+        .await;
+    Ok(Json(params))
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+pub struct NextAuctionQueryParams {
+    /// The permission key to estimate the next auction for.
+    #[param(example = "0xdeadbeef", value_type = String)]
+    pub permission_key: PermissionKey,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct NextAuction {
+    /// The chain id of the auction.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:          ChainId,
+    /// The permission key of the auction.
+    #[schema(example = "0xdeadbeef", value_type = String)]
+    pub permission_key:    PermissionKey,
+    /// The estimated time of the next auction formatted in rfc3339.
+    /// Null if there is no pending bid for the permission key.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_auction_time: Option<OffsetDateTime>,
+}
+
+/// Estimate when the next auction for a permission key will run.
+///
+/// The estimation is based on the pending bids for the permission key and the recent block times of the chain.
+#[utoipa::path(get, path = "/v1/chains/{chain_id}/next_auction",
+params(("chain_id"=String, description = "The chain id of the auction"), NextAuctionQueryParams),
+responses(
+(status = 200, description = "The estimated time of the next auction", body = NextAuction),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_next_auction(
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+    Query(query_params): Query<NextAuctionQueryParams>,
+) -> Result<Json<NextAuction>, RestError> {
+    let (auction_minimum_lifetime, auction_schedule) =
+        if let Some(chain_store) = store.chains.get(&chain_id) {
+            (
+                <&ChainStoreEvm as ChainStore>::AUCTION_MINIMUM_LIFETIME,
+                chain_store.config.auction_schedule,
+            )
+        } else if let Some(chain_store) = store.chains_svm.get(&chain_id) {
+            (
+                <&ChainStoreSvm as ChainStore>::AUCTION_MINIMUM_LIFETIME,
+                chain_store.config.auction_schedule,
+            )
+        } else {
+            return Err(RestError::InvalidChainId);
+        };
+    let key = (query_params.permission_key.clone(), chain_id.clone());
+    let next_auction_time = match auction_schedule {
+        AuctionSchedule::PerKey => {
+            store
+                .estimate_next_auction_time(&key, auction_minimum_lifetime)
+                .await
+        }
+        AuctionSchedule::Block => {
+            store
+                .estimate_next_auction_time(&key, std::time::Duration::ZERO)
+                .await
+        }
+        AuctionSchedule::Grid { interval_ms } => store
+            .estimate_next_auction_time(&key, std::time::Duration::ZERO)
+            .await
+            .map(|_| {
+                next_grid_time(
+                    OffsetDateTime::now_utc(),
+                    std::time::Duration::from_millis(interval_ms),
+                )
+            }),
+    };
+    Ok(Json(NextAuction {
+        chain_id,
+        permission_key: query_params.permission_key,
+        next_auction_time,
+    }))
+}
+
+#[derive(Serialize, Deserialize, IntoParams)]
+pub struct GetChainStatsQueryParams {
+    /// The start of the time range, defaults to a day before the end of the time range.
+    #[param(example="2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub from_time: Option<OffsetDateTime>,
+    /// The end of the time range, defaults to now.
+    #[param(example="2024-05-24T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "crate::serde::nullable_datetime")]
+    pub to_time:   Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct ChainStats {
+    /// The chain id of the stats.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:                   ChainId,
+    /// The start of the time range formatted in rfc3339.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = String)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub from_time:                  OffsetDateTime,
+    /// The end of the time range formatted in rfc3339.
+    #[schema(example = "2024-05-24T21:26:57.329954Z", value_type = String)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub to_time:                    OffsetDateTime,
+    /// The number of auctions created in the time range.
+    #[schema(example = 240)]
+    pub auction_count:              i64,
+    /// The average number of auctions per hour.
+    #[schema(example = 10.0)]
+    pub auctions_per_hour:          f64,
+    /// The average number of bids per auction. Null if there is no auction.
+    #[schema(example = 2.5)]
+    pub average_bids_per_auction:   Option<f64>,
+    /// The ratio of the won bids to the concluded bids. Null if there is no concluded bid.
+    #[schema(example = 0.4)]
+    pub win_rate:                   Option<f64>,
+    /// The sum of the won bid amounts in wei.
+    #[schema(example = "1000", value_type = String)]
+    #[serde(with = "crate::serde::u256")]
+    pub total_fees:                 BidAmount,
+    /// The average time between the submission and the conclusion of the auctions in seconds.
+    /// Null if no auction was concluded on chain.
+    #[schema(example = 4.2)]
+    pub average_settlement_latency: Option<f64>,
+}
+
+/// Get the aggregate auction statistics of a chain in a time range.
+///
+/// The auctions are selected by their creation time, and the bids by the auction they belong to.
+#[utoipa::path(get, path = "/v1/chains/{chain_id}/stats",
+security(
+    ("bearerAuth" = []),
+),
+params(("chain_id"=String, description = "The chain id to get the stats for"), GetChainStatsQueryParams),
+responses(
+(status = 200, description = "The aggregate auction statistics of the chain", body = ChainStats),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_chain_stats(
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+    Query(query_params): Query<GetChainStatsQueryParams>,
+) -> Result<Json<ChainStats>, RestError> {
+    if !store.chains.contains_key(&chain_id) && !store.chains_svm.contains_key(&chain_id) {
+        return Err(RestError::InvalidChainId);
+    }
+    let to_time = query_params.to_time.unwrap_or_else(OffsetDateTime::now_utc);
+    let from_time = query_params
+        .from_time
+        .unwrap_or(to_time - Duration::days(1));
+    if from_time >= to_time {
+        return Err(RestError::BadParameters(
+            "from_time should be before to_time".to_string(),
+        ));
+    }
+
+    let stats = store.get_chain_stats(&chain_id, from_time, to_time).await?;
+    let total_fees = BidAmount::from_dec_str(&stats.total_fees.to_string()).map_err(|e| {
+        tracing::error!("Failed to parse total fees: {} - chain_id: {}", e, chain_id);
+        RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+    })?;
+    let hours = (to_time - from_time).as_seconds_f64() / 3600.0;
+    Ok(Json(ChainStats {
+        chain_id,
+        from_time,
+        to_time,
+        auction_count: stats.auction_count,
+        auctions_per_hour: stats.auction_count as f64 / hours,
+        average_bids_per_auction: (stats.auction_count > 0)
+            .then(|| stats.bid_count as f64 / stats.auction_count as f64),
+        win_rate: (stats.concluded_bid_count > 0)
+            .then(|| stats.won_bid_count as f64 / stats.concluded_bid_count as f64),
+        total_fees,
+        average_settlement_latency: stats.average_settlement_latency,
+    }))
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct AdapterConfig {
+    /// The chain id of the adapter.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:                 ChainId,
+    /// The address of the opportunity adapter factory contract.
+    #[schema(example = "0xcA11bde05977b3631167028862bE2a173976CA11", value_type = String)]
+    pub adapter_factory_contract: Address,
+    /// The hash of the adapter contract bytecode, used for computing the adapter addresses.
+    #[schema(example = "0x3d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = String)]
+    pub adapter_bytecode_hash:    H256,
+    /// The address of the permit2 contract.
+    #[schema(example = "0x000000000022D473030F116dDEE9F6B43aC78BA3", value_type = String)]
+    pub permit2:                  Address,
+    /// The address of the weth contract.
+    #[schema(example = "0x4200000000000000000000000000000000000006", value_type = String)]
+    pub weth:                     Address,
+}
+
+/// Get the opportunity adapter config of an EVM chain.
+///
+/// Searchers can use these values to compute their adapter addresses. SVM chains have no adapter config.
+#[utoipa::path(get, path = "/v1/chains/{chain_id}/adapter",
+params(("chain_id"=String, description = "The chain id to get the adapter config for")),
+responses(
+(status = 200, description = "The opportunity adapter config of the chain", body = AdapterConfig),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_adapter_config(
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+) -> Result<Json<AdapterConfig>, RestError> {
+    let chain_store = store
+        .chains
+        .get(&chain_id)
+        .ok_or(RestError::InvalidChainId)?;
+    Ok(Json(AdapterConfig {
+        chain_id,
+        adapter_factory_contract: chain_store.config.adapter_factory_contract,
+        adapter_bytecode_hash: H256::from(chain_store.adapter_bytecode_hash),
+        permit2: chain_store.permit2,
+        weth: chain_store.weth,
+    }))
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
+pub struct AuctionConfig {
+    /// The chain id of the auction config.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:          ChainId,
+    /// The order used to break ties between bids with the same amount.
+    #[schema(example = "amount_time")]
+    pub bid_tiebreak:      String,
+    /// The maximum number of bids which can win an auction together.
+    #[schema(example = 3)]
+    pub max_winner_bids:   usize,
+    /// The minimum amount a new bid should add to the current best bid of the permission key.
+    /// Null if any increment is accepted.
+    pub min_bid_increment: Option<MinBidIncrement>,
+    /// The minimum number of bids to collect before running an auction.
+    pub min_bids:          MinBids,
+    /// The scheduling of the auctions of the chain.
+    pub auction_schedule:  AuctionSchedule,
+    /// The fee recipient which bids have to pay the protocol fee to. Null if any fee recipient is accepted.
+    #[schema(example = "0xcA11bde05977b3631167028862bE2a173976CA11", value_type = Option<String>)]
+    pub fee_recipient:     Option<String>,
+}
+
+/// Get the auction selection config of a chain.
+///
+/// Searchers can use these values to know how the bids are ranked and when the auctions run.
+#[utoipa::path(get, path = "/v1/chains/{chain_id}/auction_config",
+params(("chain_id"=String, description = "The chain id to get the auction config for")),
+responses(
+(status = 200, description = "The auction selection config of the chain", body = AuctionConfig),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn get_auction_config(
+    State(store): State<Arc<Store>>,
+    Path(chain_id): Path<ChainId>,
+) -> Result<Json<AuctionConfig>, RestError> {
+    let auction_config = if let Some(chain_store) = store.chains.get(&chain_id) {
+        let config = &chain_store.config;
+        AuctionConfig {
+            chain_id,
+            bid_tiebreak: config.bid_tiebreak.as_str().to_string(),
+            max_winner_bids: <&ChainStoreEvm as ChainStore>::MAX_WINNER_BIDS,
+            min_bid_increment: config.min_bid_increment,
+            min_bids: config.min_bids,
+            auction_schedule: config.auction_schedule,
+            fee_recipient: config
+                .fee_recipient
+                .map(|fee_recipient| format!("{:?}", fee_recipient)),
+        }
+    } else if let Some(chain_store) = store.chains_svm.get(&chain_id) {
+        let config = &chain_store.config;
+        AuctionConfig {
+            chain_id,
+            bid_tiebreak: config.bid_tiebreak.as_str().to_string(),
+            max_winner_bids: <&ChainStoreSvm as ChainStore>::MAX_WINNER_BIDS,
+            min_bid_increment: config.min_bid_increment,
+            min_bids: config.min_bids,
+            auction_schedule: config.auction_schedule,
+            fee_recipient: config
+                .fee_recipient
+                .map(|fee_recipient| fee_recipient.to_string()),
+        }
+    } else {
+        return Err(RestError::InvalidChainId);
+    };
+    Ok(Json(auction_config))
+}
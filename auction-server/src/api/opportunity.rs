@@ -3,20 +3,28 @@ use {
     crate::{
         api::{
             bid::BidResult,
-            ws::UpdateEvent::NewOpportunity,
+            select_fields,
+            verify_time_range,
             ErrorBodyResponse,
             GetOpportunitiesQueryParams,
             OpportunityMode,
             RestError,
         },
         config::ChainId,
+        models::{
+            AccessTokenScope,
+            OpportunityRemovalReason,
+        },
         opportunity_adapter::{
             handle_opportunity_bid,
+            simulate_opportunity_execution,
             verify_opportunity,
             OpportunityBid,
+            OpportunityBidSimulation,
         },
         state::{
             Opportunity,
+            OpportunityAmounts,
             OpportunityId,
             OpportunityParams,
             Store,
@@ -31,11 +39,18 @@ use {
         },
         Json,
     },
-    ethers::signers::Signer,
+    ethers::{
+        signers::Signer,
+        types::{
+            Bytes,
+            H256,
+        },
+    },
     serde::{
         Deserialize,
         Serialize,
     },
+    serde_json::json,
     sqlx::types::time::OffsetDateTime,
     std::sync::Arc,
     utoipa::{
@@ -55,11 +70,44 @@ pub struct OpportunityParamsWithMetadata {
     /// Creation time of the opportunity (in microseconds since the Unix epoch)
     #[schema(example = 1_700_000_000_000_000i128, value_type = i128)]
     creation_time:  UnixTimestampMicros,
+    /// Hash of the opportunity parameters, which is the same for the opportunities with the same content
+    #[schema(example = "0x5ca1ab1e5ca1ab1e5ca1ab1e5ca1ab1e5ca1ab1e5ca1ab1e5ca1ab1e5ca1ab1e", value_type = String)]
+    content_hash:   H256,
     /// opportunity data
     #[serde(flatten)]
     // expands params into component fields in the generated client schemas
     #[schema(inline)]
     params:         OpportunityParams,
+    /// The token amounts which are not filled yet
+    remaining:      OpportunityAmounts,
+    /// The time the opportunity was removed formatted in rfc3339. Only included for the removed opportunities in
+    /// historical mode when `include_removed` is set.
+    #[schema(example = "2024-05-23T21:27:57.329954Z", value_type = Option<String>)]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    removal_time:   Option<OffsetDateTime>,
+    /// The reason the opportunity was removed. Only included along with the removal time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    removal_reason: Option<OpportunityRemovalReason>,
+}
+
+/// An opportunity which is removed from the server, along with the reason of the removal.
+#[derive(Serialize, ToSchema, Clone, ToResponse)]
+pub struct RemovedOpportunity {
+    /// The opportunity unique id
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = String)]
+    pub opportunity_id: OpportunityId,
+    /// The chain id of the opportunity
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:       ChainId,
+    /// The permission key of the opportunity
+    #[schema(example = "0xdeadbeefcafe", value_type = String)]
+    pub permission_key: Bytes,
+    /// The reason the opportunity is removed
+    pub reason:         OpportunityRemovalReason,
 }
 
 impl OpportunityParamsWithMetadata {
@@ -68,6 +116,25 @@ impl OpportunityParamsWithMetadata {
             OpportunityParams::V1(params) => &params.chain_id,
         }
     }
+
+    pub fn get_permission_key(&self) -> &Bytes {
+        match &self.params {
+            OpportunityParams::V1(params) => &params.permission_key,
+        }
+    }
+
+    /// Sets the removal of the opportunity, if it is removed.
+    pub fn with_removal(
+        self,
+        removal_time: Option<OffsetDateTime>,
+        removal_reason: Option<OpportunityRemovalReason>,
+    ) -> Self {
+        Self {
+            removal_reason: removal_time.and(removal_reason),
+            removal_time,
+            ..self
+        }
+    }
 }
 
 impl From<Opportunity> for OpportunityParamsWithMetadata {
@@ -75,7 +142,11 @@ impl From<Opportunity> for OpportunityParamsWithMetadata {
         OpportunityParamsWithMetadata {
             opportunity_id: val.id,
             creation_time:  val.creation_time,
+            content_hash:   val.params.content_hash(),
             params:         val.params,
+            remaining:      val.remaining,
+            removal_time:   None,
+            removal_reason: None,
         }
     }
 }
@@ -83,7 +154,8 @@ impl From<Opportunity> for OpportunityParamsWithMetadata {
 /// Submit an opportunity ready to be executed.
 ///
 /// The opportunity will be verified by the server. If the opportunity is valid, it will be stored in the database
-/// and will be available for bidding.
+/// and will be available for bidding. Submitting an opportunity with the same content as a live opportunity only
+/// refreshes the creation time of the live opportunity, which is returned instead.
 #[utoipa::path(post, path = "/v1/opportunities", request_body = OpportunityParams, responses(
 (status = 200, description = "The created opportunity", body = OpportunityParamsWithMetadata),
 (status = 400, response = ErrorBodyResponse),
@@ -94,63 +166,61 @@ pub async fn post_opportunity(
     Json(versioned_params): Json<OpportunityParams>,
 ) -> Result<Json<OpportunityParamsWithMetadata>, RestError> {
     let OpportunityParams::V1(params) = versioned_params.clone();
-    let chain_store = store
-        .chains
-        .get(&params.chain_id)
-        .ok_or(RestError::InvalidChainId)?;
+    store.verify_chain_supported(&params.chain_id)?;
+    let chain_store = store.chains.get(&params.chain_id).ok_or_else(|| {
+        RestError::BadParameters("Opportunities are only supported on EVM chains".to_string())
+    })?;
+    if !chain_store.config.opportunities_enabled {
+        return Err(RestError::BadParameters(
+            "Opportunities are disabled on this chain".to_string(),
+        ));
+    }
 
     let id = Uuid::new_v4();
     let now_odt = OffsetDateTime::now_utc();
-    let opportunity = Opportunity {
+    let opportunity = Opportunity::new(
         id,
-        creation_time: now_odt.unix_timestamp_nanos() / 1000 as UnixTimestampMicros,
-        params: versioned_params.clone(),
-    };
-
-    verify_opportunity(params.clone(), chain_store, store.relayer.address())
-        .await
-        .map_err(|e| {
-            tracing::warn!(
-                "Failed to verify opportunity: {:?} - params: {:?}",
-                e,
-                versioned_params
-            );
-            RestError::InvalidOpportunity(e.to_string())
-        })?;
-
-    if store.opportunity_exists(&opportunity).await {
-        tracing::warn!("Duplicate opportunity submission: {:?}", opportunity);
-        return Err(RestError::BadParameters(
-            "Duplicate opportunity submission".to_string(),
-        ));
-    }
-    store.add_opportunity(opportunity.clone()).await?;
+        now_odt.unix_timestamp_nanos() / 1000 as UnixTimestampMicros,
+        versioned_params.clone(),
+    );
 
-    store
-        .ws
-        .broadcast_sender
-        .send(NewOpportunity(OpportunityParamsWithMetadata::from(
-            opportunity.clone(),
-        )))
-        .map_err(|e| {
-            tracing::error!(
-                "Failed to send update: {} - opportunity: {:?}",
-                e,
-                opportunity
-            );
-            RestError::TemporarilyUnavailable
-        })?;
+    verify_opportunity(
+        params.clone(),
+        &store,
+        chain_store,
+        chain_store.relayers.primary().address(),
+    )
+    .await
+    .map_err(|e| {
+        tracing::warn!(
+            "Failed to verify opportunity: {:?} - params: {:?}",
+            e,
+            versioned_params
+        );
+        RestError::InvalidOpportunity(e.to_string())
+    })?;
 
-    {
-        let opportunities_map = &store.opportunity_store.opportunities.read().await;
-        tracing::debug!("number of permission keys: {}", opportunities_map.len());
+    if let Some(duplicate) = store.refresh_duplicate_opportunity(&opportunity).await? {
         tracing::debug!(
-            "number of opportunities for key: {}",
-            opportunities_map
-                .get(&params.permission_key)
-                .map_or(0, |opps| opps.len())
+            "Refreshed duplicate opportunity submission: {:?}",
+            duplicate
         );
+        return Ok(Json(duplicate.into()));
     }
+    store.add_opportunity(opportunity.clone()).await?;
+
+    tracing::debug!(
+        "number of permission keys: {}",
+        store.opportunity_store.permission_keys_count().await
+    );
+    tracing::debug!(
+        "number of opportunities for key: {}",
+        store
+            .opportunity_store
+            .get_opportunities(&params.permission_key)
+            .await
+            .map_or(0, |opps| opps.len())
+    );
 
     let opportunity_with_metadata: OpportunityParamsWithMetadata = opportunity.into();
 
@@ -169,7 +239,7 @@ params(GetOpportunitiesQueryParams))]
 pub async fn get_opportunities(
     State(store): State<Arc<Store>>,
     query_params: Query<GetOpportunitiesQueryParams>,
-) -> Result<axum::Json<Vec<OpportunityParamsWithMetadata>>, RestError> {
+) -> Result<Json<Vec<serde_json::Value>>, RestError> {
     // make sure the chain id is valid
     if let Some(chain_id) = query_params.chain_id.clone() {
         store
@@ -180,20 +250,31 @@ pub async fn get_opportunities(
 
     match query_params.mode.clone() {
         OpportunityMode::Live => {
+            // Expired opportunities are skipped even if the expiry loop has not removed them yet
+            let now = OffsetDateTime::now_utc().unix_timestamp_nanos() / 1000;
             let opportunities: Vec<OpportunityParamsWithMetadata> = store
                 .opportunity_store
-                .opportunities
-                .read()
+                .get_all_opportunities()
                 .await
                 .iter()
                 .map(|(_key, opportunities)| {
-                    let opportunity = opportunities
+                    opportunities
                         .last()
-                        .expect("A permission key vector should have at least one opportunity");
-                    OpportunityParamsWithMetadata::from(opportunity.clone())
+                        .expect("A permission key vector should have at least one opportunity")
                 })
+                .filter(|opportunity| !store.is_opportunity_expired(opportunity, now))
+                .map(|opportunity| OpportunityParamsWithMetadata::from(opportunity.clone()))
                 .filter(|params_with_id: &OpportunityParamsWithMetadata| {
                     let OpportunityParams::V1(params) = &params_with_id.params;
+                    if !store.opportunities_enabled(&params.chain_id) {
+                        return false;
+                    }
+                    if query_params
+                        .min_target_call_value
+                        .is_some_and(|min_value| params.target_call_value < min_value)
+                    {
+                        return false;
+                    }
                     if let Some(chain_id) = &query_params.chain_id {
                         params.chain_id == *chain_id
                     } else {
@@ -202,20 +283,30 @@ pub async fn get_opportunities(
                 })
                 .collect();
 
-            Ok(opportunities.into())
+            Ok(Json(select_fields(
+                opportunities,
+                query_params.fields.as_deref(),
+            )?))
         }
         OpportunityMode::Historical => {
             let chain_id = query_params.chain_id.clone().ok_or_else(|| {
                 RestError::BadParameters("Chain id is required on historical mode".to_string())
             })?;
+            verify_time_range(query_params.from_time, query_params.to_time)?;
+            if !store.opportunities_enabled(&chain_id) {
+                return Ok(Json(vec![]));
+            }
             let opps = store
                 .get_opportunities_by_permission_key(
                     chain_id,
                     query_params.permission_key.clone(),
                     query_params.from_time,
+                    query_params.to_time,
+                    query_params.min_target_call_value,
+                    query_params.include_removed,
                 )
                 .await?;
-            Ok(opps.into())
+            Ok(Json(select_fields(opps, query_params.fields.as_deref())?))
         }
     }
 }
@@ -236,12 +327,50 @@ pub async fn opportunity_bid(
     process_opportunity_bid(store, opportunity_id, &opportunity_bid, auth).await
 }
 
+/// Record a fill of an opportunity.
+///
+/// The filled token amounts are subtracted from the remaining amounts of the opportunity, so it can be filled in
+/// multiple tranches. The opportunity is removed once all of its amounts are filled.
+#[utoipa::path(post, path = "/v1/opportunities/{opportunity_id}/fills", request_body = OpportunityAmounts,
+security(
+    ("bearerAuth" = []),
+),
+params(("opportunity_id" = String, description = "Opportunity id to fill")), responses(
+(status = 200, description = "The opportunity with its remaining amounts", body = OpportunityParamsWithMetadata),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Opportunity was not found", body = ErrorBodyResponse),
+),)]
+pub async fn post_opportunity_fill(
+    auth: Auth,
+    State(store): State<Arc<Store>>,
+    Path(opportunity_id): Path<OpportunityId>,
+    Json(filled_amounts): Json<OpportunityAmounts>,
+) -> Result<Json<OpportunityParamsWithMetadata>, RestError> {
+    if filled_amounts.sell_tokens.is_empty() && filled_amounts.buy_tokens.is_empty() {
+        return Err(RestError::BadParameters(
+            "The fill should include at least one token amount".to_string(),
+        ));
+    }
+    let opportunity = store
+        .consume_opportunity(opportunity_id, &filled_amounts)
+        .await?;
+    store
+        .add_admin_audit(
+            &auth,
+            "fill_opportunity",
+            &json!({ "opportunity_id": opportunity_id, "filled_amounts": filled_amounts }),
+        )
+        .await;
+    Ok(Json(opportunity.into()))
+}
+
 pub async fn process_opportunity_bid(
     store: Arc<Store>,
     opportunity_id: OpportunityId,
     opportunity_bid: &OpportunityBid,
     auth: Auth,
 ) -> Result<Json<BidResult>, RestError> {
+    auth.verify_scope(&store, AccessTokenScope::Submit).await?;
     match handle_opportunity_bid(
         store,
         opportunity_id,
@@ -259,3 +388,47 @@ pub async fn process_opportunity_bid(
         Err(e) => Err(e),
     }
 }
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub struct SimulateOpportunityBid {
+    /// The opportunity to execute.
+    pub opportunity: OpportunityParams,
+    /// The signed bid to execute the opportunity with.
+    pub bid:         OpportunityBid,
+}
+
+/// Simulate the execution of an opportunity with a signed bid, without placing the bid.
+///
+/// The executor is given the tokens the bid permits to be spent for the simulation, so the execution can be
+/// tested before funding the executor. Nothing is stored and the bid does not take part in any auction.
+#[utoipa::path(post, path = "/v1/opportunities/simulate", request_body = SimulateOpportunityBid,
+security(
+    ("bearerAuth" = []),
+),
+responses(
+(status = 200, description = "Result of the simulated execution", body = OpportunityBidSimulation),
+(status = 400, response = ErrorBodyResponse),
+(status = 404, description = "Chain id was not found", body = ErrorBodyResponse),
+),)]
+pub async fn simulate_opportunity_bid(
+    State(store): State<Arc<Store>>,
+    Json(simulation): Json<SimulateOpportunityBid>,
+) -> Result<Json<OpportunityBidSimulation>, RestError> {
+    let OpportunityParams::V1(params) = simulation.opportunity;
+    let chain_store = store
+        .chains
+        .get(&params.chain_id)
+        .ok_or(RestError::InvalidChainId)?;
+    if !chain_store.config.opportunities_enabled {
+        return Err(RestError::BadParameters(
+            "Opportunities are disabled on this chain".to_string(),
+        ));
+    }
+    simulate_opportunity_execution(params, simulation.bid, &store, chain_store)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::warn!("Failed to simulate opportunity bid: {:?}", e);
+            RestError::BadParameters(e.to_string())
+        })
+}
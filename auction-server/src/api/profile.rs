@@ -5,7 +5,11 @@ use {
             ErrorBodyResponse,
             RestError,
         },
-        models::ProfileId,
+        models::{
+            AccessTokenScope,
+            ProfileId,
+            ProfileTier,
+        },
         state::Store,
     },
     axum::{
@@ -32,6 +36,9 @@ pub struct CreateProfile {
     /// The email of the profile to create
     #[schema(example = "example@example.com", value_type = String)]
     pub email: String,
+    /// The tier of the profile to create. Bids of premium profiles are processed first under load.
+    #[serde(default)]
+    pub tier:  ProfileTier,
 }
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
@@ -45,6 +52,8 @@ pub struct Profile {
     /// The email of the profile
     #[schema(example = "example@example.com", value_type = String)]
     email: EmailAddress,
+    /// The tier of the profile
+    tier:  ProfileTier,
 }
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
@@ -52,13 +61,24 @@ pub struct CreateAccessToken {
     /// The id of the profile to create token for
     #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = String)]
     profile_id: ProfileId,
+    /// The scopes granted to the token. The token can both read and submit bids if not provided.
+    #[schema(example = json!(["read"]))]
+    #[serde(default = "default_access_token_scopes")]
+    scopes:     Vec<AccessTokenScope>,
+}
+
+fn default_access_token_scopes() -> Vec<AccessTokenScope> {
+    vec![AccessTokenScope::Read, AccessTokenScope::Submit]
 }
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, ToResponse)]
 pub struct AccessToken {
     /// The token for later use
     #[schema(example = "_q9zUYP-tQg8F7kQi2Rfl5c6sSy7xcc2yWh2H-nI-iI", value_type = String)]
-    token: String,
+    token:  String,
+    /// The scopes granted to the token
+    #[schema(example = json!(["read", "submit"]))]
+    scopes: Vec<AccessTokenScope>,
 }
 
 /// Create a new profile.
@@ -72,18 +92,20 @@ security(
 (status = 400, response = ErrorBodyResponse),
 ),)]
 pub async fn post_profile(
+    auth: Auth,
     State(store): State<Arc<Store>>,
     Json(params): Json<CreateProfile>,
 ) -> Result<Json<Profile>, RestError> {
-    let profile = store.create_profile(params.clone()).await?;
+    let profile = store.create_profile(&auth, params).await?;
     Ok(Json(Profile {
         id:    profile.id,
         name:  profile.name,
         email: profile.email.0,
+        tier:  profile.tier,
     }))
 }
 
-/// Create a new profile access token if no valid token exists.
+/// Create a new profile access token if no valid token with the same scopes exists.
 ///
 /// Returns the created access token object.
 #[utoipa::path(post, path = "/v1/profiles/access_tokens",
@@ -94,12 +116,16 @@ security(
 (status = 400, response = ErrorBodyResponse),
 ),)]
 pub async fn post_profile_access_token(
+    auth: Auth,
     State(store): State<Arc<Store>>,
     Json(params): Json<CreateAccessToken>,
 ) -> Result<Json<AccessToken>, RestError> {
-    let (access_token, _) = store.get_or_create_access_token(params.profile_id).await?;
+    let (access_token, _) = store
+        .get_or_create_access_token(&auth, params.profile_id, params.scopes)
+        .await?;
     Ok(Json(AccessToken {
-        token: access_token.token,
+        token:  access_token.token,
+        scopes: access_token.scopes,
     }))
 }
 
@@ -118,8 +144,8 @@ pub async fn delete_profile_access_token(
     auth: Auth,
     State(store): State<Arc<Store>>,
 ) -> Result<(), RestError> {
-    match auth {
-        Auth::Authorized(token, _) => store.revoke_access_token(&token).await,
+    match &auth {
+        Auth::Authorized(token, _) => store.revoke_access_token(&auth, token).await,
         _ => Ok(()),
     }
 }
@@ -9,10 +9,16 @@ use {
             opportunity::{
                 process_opportunity_bid,
                 OpportunityParamsWithMetadata,
+                RemovedOpportunity,
             },
+            RestError,
         },
         auction::Bid,
         config::ChainId,
+        models::{
+            AccessTokenScope,
+            ProfileId,
+        },
         opportunity_adapter::OpportunityBid,
         server::{
             EXIT_CHECK_INTERVAL,
@@ -20,8 +26,11 @@ use {
         },
         state::{
             BidId,
+            BidStatus,
             BidStatusWithId,
+            ConcludedAuction,
             OpportunityId,
+            PermissionKey,
             Store,
         },
     },
@@ -35,11 +44,14 @@ use {
                 Message,
                 WebSocket,
             },
+            Query,
             State,
             WebSocketUpgrade,
         },
         response::IntoResponse,
     },
+    axum_prometheus::metrics,
+    ethers::types::Bytes,
     futures::{
         stream::{
             SplitSink,
@@ -53,7 +65,11 @@ use {
         Serialize,
     },
     std::{
-        collections::HashSet,
+        collections::{
+            HashMap,
+            HashSet,
+            VecDeque,
+        },
         sync::{
             atomic::{
                 AtomicUsize,
@@ -63,7 +79,10 @@ use {
         },
         time::Duration,
     },
-    tokio::sync::broadcast,
+    tokio::sync::broadcast::{
+        self,
+        error::RecvError,
+    },
     tracing::{
         instrument,
         Instrument,
@@ -72,9 +91,12 @@ use {
 };
 
 pub struct WsState {
-    pub subscriber_counter: AtomicUsize,
-    pub broadcast_sender:   broadcast::Sender<UpdateEvent>,
-    pub broadcast_receiver: broadcast::Receiver<UpdateEvent>,
+    pub subscriber_counter:        AtomicUsize,
+    pub broadcast_sender:          broadcast::Sender<UpdateEvent>,
+    pub broadcast_receiver:        broadcast::Receiver<UpdateEvent>,
+    pub emit_opportunity_removals: bool,
+    pub dedup_bid_status:          bool,
+    pub reveal_winning_amounts:    bool,
 }
 
 #[derive(Deserialize, Clone, ToSchema)]
@@ -83,10 +105,28 @@ pub enum ClientMessage {
     #[serde(rename = "subscribe")]
     Subscribe {
         #[schema(value_type = Vec<String>)]
-        chain_ids: Vec<ChainId>,
+        chain_ids:       Vec<ChainId>,
+        /// Only receive the opportunities of these permission keys. All permission keys are received if
+        /// no permission key is ever subscribed to.
+        #[schema(value_type = Vec<String>)]
+        #[serde(default)]
+        permission_keys: Vec<Bytes>,
     },
     #[serde(rename = "unsubscribe")]
     Unsubscribe {
+        #[schema(value_type = Vec<String>)]
+        chain_ids:       Vec<ChainId>,
+        #[schema(value_type = Vec<String>)]
+        #[serde(default)]
+        permission_keys: Vec<Bytes>,
+    },
+    #[serde(rename = "subscribe_auctions")]
+    SubscribeAuctions {
+        #[schema(value_type = Vec<String>)]
+        chain_ids: Vec<ChainId>,
+    },
+    #[serde(rename = "unsubscribe_auctions")]
+    UnsubscribeAuctions {
         #[schema(value_type = Vec<String>)]
         chain_ids: Vec<ChainId>,
     },
@@ -118,6 +158,18 @@ pub enum ServerUpdateResponse {
     },
     #[serde(rename = "bid_status_update")]
     BidStatusUpdate { status: BidStatusWithId },
+    #[serde(rename = "update_opportunity")]
+    UpdateOpportunity {
+        opportunity: OpportunityParamsWithMetadata,
+    },
+    #[serde(rename = "remove_opportunity")]
+    RemoveOpportunity { opportunity: RemovedOpportunity },
+    #[serde(rename = "auction_concluded")]
+    AuctionConcluded { auction: ConcludedAuction },
+    /// The subscriber fell behind and missed some updates. It should resubscribe and fetch the current
+    /// opportunities and bid statuses to re-sync.
+    #[serde(rename = "lagged")]
+    Lagged { missed_updates: u64 },
 }
 
 #[derive(Serialize, Clone, ToSchema)]
@@ -143,46 +195,134 @@ pub struct ServerResultResponse {
     result: ServerResultMessage,
 }
 
+#[derive(Deserialize)]
+pub struct WsConnectQueryParams {
+    /// Comma separated list of the chain ids the connection is limited to. All chains are allowed if not provided.
+    chain_ids: Option<String>,
+}
+
 pub async fn ws_route_handler(
     auth: Auth,
     ws: WebSocketUpgrade,
     State(store): State<Arc<Store>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| websocket_handler(socket, store, auth))
+    Query(params): Query<WsConnectQueryParams>,
+) -> Result<impl IntoResponse, RestError> {
+    let allowed_chain_ids = match params.chain_ids {
+        Some(chain_ids) => {
+            let chain_ids: HashSet<ChainId> = chain_ids
+                .split(',')
+                .map(|chain_id| chain_id.trim().to_string())
+                .filter(|chain_id| !chain_id.is_empty())
+                .collect();
+            if chain_ids.iter().any(|chain_id| {
+                !store.chains.contains_key(chain_id) && !store.chains_svm.contains_key(chain_id)
+            }) {
+                return Err(RestError::InvalidChainId);
+            }
+            Some(chain_ids)
+        }
+        None => None,
+    };
+    Ok(ws.on_upgrade(move |socket| websocket_handler(socket, store, auth, allowed_chain_ids)))
 }
 
-async fn websocket_handler(stream: WebSocket, state: Arc<Store>, auth: Auth) {
+async fn websocket_handler(
+    stream: WebSocket,
+    state: Arc<Store>,
+    auth: Auth,
+    allowed_chain_ids: Option<HashSet<ChainId>>,
+) {
     let ws_state = &state.ws;
     let id = ws_state.subscriber_counter.fetch_add(1, Ordering::SeqCst);
     let (sender, receiver) = stream.split();
     let new_receiver = ws_state.broadcast_receiver.resubscribe();
-    let mut subscriber = Subscriber::new(id, state, new_receiver, receiver, sender, auth);
+    let mut subscriber = Subscriber::new(
+        id,
+        state,
+        new_receiver,
+        receiver,
+        sender,
+        auth,
+        allowed_chain_ids,
+    );
     subscriber.run().await;
 }
 
+/// The bid a status update belongs to, so the subscribers can filter the update without looking the bid up.
+#[derive(Clone)]
+pub struct BidStatusUpdateTarget {
+    pub chain_id:       ChainId,
+    pub permission_key: PermissionKey,
+    pub profile_id:     Option<ProfileId>,
+}
+
 #[derive(Clone)]
 pub enum UpdateEvent {
     NewOpportunity(OpportunityParamsWithMetadata),
-    BidStatusUpdate(BidStatusWithId),
+    BidStatusUpdate {
+        status: BidStatusWithId,
+        target: BidStatusUpdateTarget,
+    },
+    UpdateOpportunity(OpportunityParamsWithMetadata),
+    RemoveOpportunity(RemovedOpportunity),
+    AuctionConcluded(ConcludedAuction),
 }
 
 pub type SubscriberId = usize;
 
+const MAX_SENT_BID_STATUSES: usize = 1000;
+
+/// The last status sent to a subscriber for each bid, used to drop duplicate status updates.
+/// Only the most recent bids are tracked to keep the memory usage bounded.
+#[derive(Default)]
+struct SentBidStatuses {
+    statuses: HashMap<BidId, BidStatus>,
+    order:    VecDeque<BidId>,
+}
+
+impl SentBidStatuses {
+    /// Records the status and returns false if it was already the last status sent for the bid.
+    fn insert(&mut self, status: &BidStatusWithId) -> bool {
+        match self.statuses.insert(status.id, status.bid_status.clone()) {
+            Some(previous) => previous != status.bid_status,
+            None => {
+                self.order.push_back(status.id);
+                if self.order.len() > MAX_SENT_BID_STATUSES {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.statuses.remove(&oldest);
+                    }
+                }
+                true
+            }
+        }
+    }
+}
+
 /// Subscriber is an actor that handles a single websocket connection.
 /// It listens to the store for updates and sends them to the client.
 pub struct Subscriber {
-    id:                  SubscriberId,
-    closed:              bool,
-    store:               Arc<Store>,
-    notify_receiver:     broadcast::Receiver<UpdateEvent>,
-    receiver:            SplitStream<WebSocket>,
-    sender:              SplitSink<WebSocket, Message>,
-    chain_ids:           HashSet<ChainId>,
-    bid_ids:             HashSet<BidId>,
-    ping_interval:       tokio::time::Interval,
-    exit_check_interval: tokio::time::Interval,
-    responded_to_ping:   bool,
-    auth:                Auth,
+    id:                    SubscriberId,
+    closed:                bool,
+    store:                 Arc<Store>,
+    notify_receiver:       broadcast::Receiver<UpdateEvent>,
+    receiver:              SplitStream<WebSocket>,
+    sender:                SplitSink<WebSocket, Message>,
+    chain_ids:             HashSet<ChainId>,
+    /// The chains named when connecting. Subscriptions are limited to these chains if set.
+    allowed_chain_ids:     Option<HashSet<ChainId>>,
+    /// The permission keys to send the opportunities and the profile bid statuses for. All permission keys are sent if empty.
+    permission_keys:       HashSet<Bytes>,
+    /// The chains to send the auction conclusions for.
+    auction_chain_ids:     HashSet<ChainId>,
+    bid_ids:               HashSet<BidId>,
+    /// The profile whose bids on the subscribed chains and permission keys get their status updates sent,
+    /// in addition to the bids sent over this connection. Set once the profile subscribes with read access.
+    bid_status_profile_id: Option<ProfileId>,
+    sent_bid_statuses:     SentBidStatuses,
+    ping_interval:         tokio::time::Interval,
+    exit_check_interval:   tokio::time::Interval,
+    responded_to_ping:     bool,
+    auth:                  Auth,
 }
 
 const PING_INTERVAL_DURATION: Duration = Duration::from_secs(30);
@@ -202,6 +342,7 @@ impl Subscriber {
         receiver: SplitStream<WebSocket>,
         sender: SplitSink<WebSocket, Message>,
         auth: Auth,
+        allowed_chain_ids: Option<HashSet<ChainId>>,
     ) -> Self {
         Self {
             id,
@@ -210,8 +351,14 @@ impl Subscriber {
             notify_receiver,
             receiver,
             sender,
-            chain_ids: HashSet::new(),
+            // Chains named when connecting are subscribed right away
+            chain_ids: allowed_chain_ids.clone().unwrap_or_default(),
+            allowed_chain_ids,
+            permission_keys: HashSet::new(),
+            auction_chain_ids: HashSet::new(),
             bid_ids: HashSet::new(),
+            bid_status_profile_id: None,
+            sent_bid_statuses: SentBidStatuses::default(),
             ping_interval: tokio::time::interval(PING_INTERVAL_DURATION),
             exit_check_interval: tokio::time::interval(EXIT_CHECK_INTERVAL),
             responded_to_ping: true, // We start with true so we don't close the connection immediately
@@ -232,8 +379,8 @@ impl Subscriber {
         tokio::select! {
             maybe_update_event = self.notify_receiver.recv() => {
                 match maybe_update_event {
-
                     Ok(event) => self.handle_update(event).await,
+                    Err(RecvError::Lagged(missed_updates)) => self.handle_lagged(missed_updates).await,
                     Err(e) => Err(anyhow!("Error receiving update event: {:?}", e)),
                 }
             },
@@ -266,12 +413,31 @@ impl Subscriber {
         }
     }
 
+    /// The receiver skips the missed updates, so the subscriber is told to re-sync instead of silently
+    /// missing them.
+    async fn handle_lagged(&mut self, missed_updates: u64) -> Result<()> {
+        tracing::warn!(
+            subscriber = self.id,
+            "Subscriber missed {} updates",
+            missed_updates
+        );
+        metrics::counter!("ws_lagged_updates_total").increment(missed_updates);
+        let message = serde_json::to_string(&ServerUpdateResponse::Lagged { missed_updates })?;
+        self.sender.send(message.into()).await?;
+        Ok(())
+    }
+
+    fn is_subscribed(&self, chain_id: &ChainId, permission_key: &Bytes) -> bool {
+        self.chain_ids.contains(chain_id)
+            && (self.permission_keys.is_empty() || self.permission_keys.contains(permission_key))
+    }
+
     async fn handle_new_opportunity(
         &mut self,
         opportunity: OpportunityParamsWithMetadata,
     ) -> Result<()> {
         tracing::Span::current().record("name", "new_opportunity");
-        if !self.chain_ids.contains(opportunity.get_chain_id()) {
+        if !self.is_subscribed(opportunity.get_chain_id(), opportunity.get_permission_key()) {
             // Irrelevant update
             return Ok(());
         }
@@ -280,12 +446,67 @@ impl Subscriber {
         Ok(())
     }
 
-    async fn handle_bid_status_update(&mut self, status: BidStatusWithId) -> Result<()> {
+    async fn handle_update_opportunity(
+        &mut self,
+        opportunity: OpportunityParamsWithMetadata,
+    ) -> Result<()> {
+        tracing::Span::current().record("name", "update_opportunity");
+        if !self.is_subscribed(opportunity.get_chain_id(), opportunity.get_permission_key()) {
+            // Irrelevant update
+            return Ok(());
+        }
+        let message =
+            serde_json::to_string(&ServerUpdateResponse::UpdateOpportunity { opportunity })?;
+        self.sender.send(message.into()).await?;
+        Ok(())
+    }
+
+    async fn handle_remove_opportunity(&mut self, opportunity: RemovedOpportunity) -> Result<()> {
+        tracing::Span::current().record("name", "remove_opportunity");
+        if !self.is_subscribed(&opportunity.chain_id, &opportunity.permission_key) {
+            // Irrelevant update
+            return Ok(());
+        }
+        let message =
+            serde_json::to_string(&ServerUpdateResponse::RemoveOpportunity { opportunity })?;
+        self.sender.send(message.into()).await?;
+        Ok(())
+    }
+
+    async fn handle_auction_concluded(&mut self, auction: ConcludedAuction) -> Result<()> {
+        tracing::Span::current().record("name", "auction_concluded");
+        if !self.auction_chain_ids.contains(&auction.chain_id) {
+            // Irrelevant update
+            return Ok(());
+        }
+        let message = serde_json::to_string(&ServerUpdateResponse::AuctionConcluded { auction })?;
+        self.sender.send(message.into()).await?;
+        Ok(())
+    }
+
+    fn is_bid_status_subscribed(&self, id: &BidId, target: &BidStatusUpdateTarget) -> bool {
+        if self.bid_ids.contains(id) {
+            return true;
+        }
+        self.bid_status_profile_id.is_some()
+            && target.profile_id == self.bid_status_profile_id
+            && self.is_subscribed(&target.chain_id, &target.permission_key)
+    }
+
+    async fn handle_bid_status_update(
+        &mut self,
+        status: BidStatusWithId,
+        target: BidStatusUpdateTarget,
+    ) -> Result<()> {
         tracing::Span::current().record("name", "bid_status_update");
-        if !self.bid_ids.contains(&status.id) {
+        if !self.is_bid_status_subscribed(&status.id, &target) {
             // Irrelevant update
             return Ok(());
         }
+        if self.store.ws.dedup_bid_status && !self.sent_bid_statuses.insert(&status) {
+            // Duplicate update
+            return Ok(());
+        }
         let message = serde_json::to_string(&ServerUpdateResponse::BidStatusUpdate { status })?;
         self.sender.send(message.into()).await?;
         Ok(())
@@ -302,9 +523,21 @@ impl Subscriber {
                 tracing::Span::current().record("name", "new_opportunity");
                 self.handle_new_opportunity(opportunity).await
             }
-            UpdateEvent::BidStatusUpdate(status) => {
+            UpdateEvent::BidStatusUpdate { status, target } => {
                 tracing::Span::current().record("name", "bid_status_update");
-                self.handle_bid_status_update(status).await
+                self.handle_bid_status_update(status, target).await
+            }
+            UpdateEvent::UpdateOpportunity(opportunity) => {
+                tracing::Span::current().record("name", "update_opportunity");
+                self.handle_update_opportunity(opportunity).await
+            }
+            UpdateEvent::RemoveOpportunity(opportunity) => {
+                tracing::Span::current().record("name", "remove_opportunity");
+                self.handle_remove_opportunity(opportunity).await
+            }
+            UpdateEvent::AuctionConcluded(auction) => {
+                tracing::Span::current().record("name", "auction_concluded");
+                self.handle_auction_concluded(auction).await
             }
         };
         if result.is_err() {
@@ -313,13 +546,13 @@ impl Subscriber {
         result
     }
 
-    async fn handle_subscribe(
-        &mut self,
+    /// Checks that all the chain ids are available and were named when connecting, if any were named.
+    fn verify_subscription_chain_ids(
+        &self,
         id: String,
-        chain_ids: Vec<String>,
+        chain_ids: &[ChainId],
+        available_chain_ids: Vec<&ChainId>,
     ) -> Result<ServerResultResponse, ServerResultResponse> {
-        tracing::Span::current().record("name", "handle_subscribe");
-        let available_chain_ids: Vec<&ChainId> = self.store.chains.keys().collect();
         let not_found_chain_ids: Vec<&ChainId> = chain_ids
             .iter()
             .filter(|chain_id| !available_chain_ids.contains(chain_id))
@@ -327,37 +560,133 @@ impl Subscriber {
         // If there is a single chain id that is not found, we don't subscribe to any of the
         // asked correct chain ids and return an error to be more explicit and clear.
         if !not_found_chain_ids.is_empty() {
-            Err(ServerResultResponse {
+            return Err(ServerResultResponse {
                 id:     Some(id),
                 result: ServerResultMessage::Err(format!(
                     "Chain id(s) with id(s) {:?} not found",
                     not_found_chain_ids
                 )),
+            });
+        }
+        let not_allowed_chain_ids: Vec<&ChainId> = match &self.allowed_chain_ids {
+            Some(allowed_chain_ids) => chain_ids
+                .iter()
+                .filter(|chain_id| !allowed_chain_ids.contains(*chain_id))
+                .collect(),
+            None => vec![],
+        };
+        if !not_allowed_chain_ids.is_empty() {
+            Err(ServerResultResponse {
+                id:     Some(id),
+                result: ServerResultMessage::Err(format!(
+                    "Chain id(s) with id(s) {:?} were not named when connecting",
+                    not_allowed_chain_ids
+                )),
             })
         } else {
-            self.chain_ids.extend(chain_ids);
             Ok(ok_response(id))
         }
     }
 
+    async fn handle_subscribe(
+        &mut self,
+        id: String,
+        chain_ids: Vec<String>,
+        permission_keys: Vec<Bytes>,
+    ) -> Result<ServerResultResponse, ServerResultResponse> {
+        tracing::Span::current().record("name", "handle_subscribe");
+        let response =
+            self.verify_subscription_chain_ids(id, &chain_ids, self.store.chains.keys().collect())?;
+        self.chain_ids.extend(chain_ids);
+        self.permission_keys.extend(permission_keys);
+        Ok(response)
+    }
+
+    async fn handle_subscribe_auctions(
+        &mut self,
+        id: String,
+        chain_ids: Vec<String>,
+    ) -> Result<ServerResultResponse, ServerResultResponse> {
+        tracing::Span::current().record("name", "subscribe_auctions");
+        let response = self.verify_subscription_chain_ids(
+            id,
+            &chain_ids,
+            self.store
+                .chains
+                .keys()
+                .chain(self.store.chains_svm.keys())
+                .collect(),
+        )?;
+        self.auction_chain_ids.extend(chain_ids);
+        Ok(response)
+    }
+
+    async fn handle_unsubscribe_auctions(
+        &mut self,
+        id: String,
+        chain_ids: Vec<String>,
+    ) -> Result<ServerResultResponse, ServerResultResponse> {
+        tracing::Span::current().record("name", "unsubscribe_auctions");
+        self.auction_chain_ids
+            .retain(|chain_id| !chain_ids.contains(chain_id));
+        Ok(ok_response(id))
+    }
+
     async fn handle_unsubscribe(
         &mut self,
         id: String,
         chain_ids: Vec<String>,
+        permission_keys: Vec<Bytes>,
     ) -> Result<ServerResultResponse, ServerResultResponse> {
         tracing::Span::current().record("name", "unsubscribe");
         self.chain_ids
             .retain(|chain_id| !chain_ids.contains(chain_id));
+        self.permission_keys
+            .retain(|permission_key| !permission_keys.contains(permission_key));
         Ok(ok_response(id))
     }
 
+    /// Sends the current statuses of the live bids of the authorized profile on the chains,
+    /// so the searcher does not have to query them before following the updates.
+    async fn send_bid_statuses_snapshot(&mut self, chain_ids: &[ChainId]) -> Result<()> {
+        let Auth::Authorized(_, profile) = &self.auth else {
+            return Ok(());
+        };
+        if chain_ids.is_empty() {
+            return Ok(());
+        }
+        if self
+            .auth
+            .verify_scope(&self.store, AccessTokenScope::Read)
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        self.bid_status_profile_id = Some(profile.id);
+        for bid in self.store.get_live_bids(profile.id, chain_ids).await {
+            let core_fields = bid.get_core_fields();
+            let status = BidStatusWithId {
+                id:         core_fields.id,
+                bid_status: core_fields.status.clone(),
+            };
+            self.bid_ids.insert(status.id);
+            if self.store.ws.dedup_bid_status && !self.sent_bid_statuses.insert(&status) {
+                continue;
+            }
+            let message = serde_json::to_string(&ServerUpdateResponse::BidStatusUpdate { status })?;
+            self.sender.send(message.into()).await?;
+        }
+        Ok(())
+    }
+
     async fn handle_post_bid(
         &mut self,
         id: String,
         bid: Bid,
     ) -> Result<ServerResultResponse, ServerResultResponse> {
         tracing::Span::current().record("name", "post_bid");
-        match process_bid(self.store.clone(), bid, self.auth.clone()).await {
+        match process_bid(self.store.clone(), bid, self.auth.clone(), None).await {
             Ok(bid_result) => {
                 self.bid_ids.insert(bid_result.id);
                 Ok(ServerResultResponse {
@@ -443,19 +772,42 @@ impl Subscriber {
             }
         };
 
+        // Chains whose bid statuses are sent after responding to a successful subscription
+        let mut bid_snapshot_chain_ids = vec![];
         let response = match maybe_client_message {
             Err(e) => Err(ServerResultResponse {
                 id:     None,
                 result: ServerResultMessage::Err(e.to_string()),
             }),
             Ok(ClientRequest { msg, id }) => match msg {
-                ClientMessage::Subscribe { chain_ids } => {
+                ClientMessage::Subscribe {
+                    chain_ids,
+                    permission_keys,
+                } => {
                     tracing::Span::current().record("name", "subscribe");
-                    self.handle_subscribe(id, chain_ids).await
+                    let response = self
+                        .handle_subscribe(id, chain_ids.clone(), permission_keys)
+                        .await;
+                    if response.is_ok() {
+                        bid_snapshot_chain_ids = chain_ids;
+                    }
+                    response
                 }
-                ClientMessage::Unsubscribe { chain_ids } => {
+                ClientMessage::Unsubscribe {
+                    chain_ids,
+                    permission_keys,
+                } => {
                     tracing::Span::current().record("name", "unsubscribe");
-                    self.handle_unsubscribe(id, chain_ids).await
+                    self.handle_unsubscribe(id, chain_ids, permission_keys)
+                        .await
+                }
+                ClientMessage::SubscribeAuctions { chain_ids } => {
+                    tracing::Span::current().record("name", "subscribe_auctions");
+                    self.handle_subscribe_auctions(id, chain_ids).await
+                }
+                ClientMessage::UnsubscribeAuctions { chain_ids } => {
+                    tracing::Span::current().record("name", "unsubscribe_auctions");
+                    self.handle_unsubscribe_auctions(id, chain_ids).await
                 }
                 ClientMessage::PostBid { bid } => {
                     tracing::Span::current().record("name", "post_bid");
@@ -480,6 +832,8 @@ impl Subscriber {
         self.sender
             .send(serde_json::to_string(&response.unwrap_or_else(|e| e))?.into())
             .await?;
+        self.send_bid_statuses_snapshot(&bid_snapshot_chain_ids)
+            .await?;
 
         Ok(())
     }
@@ -3,19 +3,32 @@ use {
         api::{
             Auth,
             RestError,
+            UnavailableReason,
         },
         config::{
+            AuctionSchedule,
+            BidTiebreak,
+            BidTiebreakSvm,
             ChainId,
             ConfigEvm,
+            FeeStrategy,
+            MinBidIncrement,
+            MinBids,
+            MinBidsFallback,
+            PermissionKeyChainCheck,
         },
         models,
         server::{
+            ShutdownReceiver,
             EXIT_CHECK_INTERVAL,
             SHOULD_EXIT,
         },
         state::{
-            AuctionLock,
+            AuctionKey,
+            AuctionLockGuard,
             BidAmount,
+            BidId,
+            BidLostReason,
             BidStatus,
             ChainStoreEvm,
             ChainStoreSvm,
@@ -27,6 +40,7 @@ use {
             SimulatedBidSvm,
             SimulatedBidTrait,
             Store,
+            TxHash,
         },
         traced_client::TracedClient,
     },
@@ -101,6 +115,7 @@ use {
         rpc_config::{
             RpcBlockSubscribeConfig,
             RpcBlockSubscribeFilter,
+            RpcSimulateTransactionConfig,
         },
         rpc_response::{
             Response,
@@ -109,12 +124,13 @@ use {
     },
     solana_sdk::{
         commitment_config::CommitmentConfig,
+        compute_budget,
         instruction::CompiledInstruction,
-        pubkey::Pubkey,
-        signature::{
-            Signature as SignatureSvm,
-            Signer as SignerSvm,
+        pubkey::{
+            Pubkey,
+            PUBKEY_BYTES,
         },
+        signature::Signer as SignerSvm,
         transaction::{
             TransactionError,
             VersionedTransaction,
@@ -122,6 +138,11 @@ use {
     },
     sqlx::types::time::OffsetDateTime,
     std::{
+        cmp::Reverse,
+        collections::{
+            HashMap,
+            HashSet,
+        },
         fmt::Debug as DebugTrait,
         future::Future,
         pin::Pin,
@@ -132,7 +153,17 @@ use {
         },
         time::Duration,
     },
-    tokio::sync::MutexGuard,
+    tokio::{
+        sync::{
+            SemaphorePermit,
+            TryAcquireError,
+        },
+        time::{
+            Instant,
+            Interval,
+            MissedTickBehavior,
+        },
+    },
     utoipa::ToSchema,
     uuid::Uuid,
 };
@@ -165,6 +196,7 @@ impl From<([u8; 16], H160, Bytes, U256, U256, bool)> for MulticallData {
 }
 
 const EXTRA_GAS_FOR_SUBMISSION: u32 = 500 * 1000;
+const CLOCK_DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 pub fn get_simulation_call(
     relayer: Address,
@@ -237,11 +269,12 @@ fn get_bid_status(decoded_log: &MulticallIssuedFilter, receipt: &TransactionRece
     match decoded_log.multicall_status.external_success {
         true => BidStatus::Won {
             index:  decoded_log.multicall_index.as_u32(),
-            result: receipt.transaction_hash.0.to_vec(),
+            result: TxHash::Evm(receipt.transaction_hash),
         },
         false => BidStatus::Lost {
             index:  Some(decoded_log.multicall_index.as_u32()),
-            result: Some(receipt.transaction_hash.0.to_vec()),
+            result: Some(TxHash::Evm(receipt.transaction_hash)),
+            reason: None,
         },
     }
 }
@@ -271,13 +304,19 @@ async fn conclude_submitted_auction<T: ChainStore>(
     auction: models::Auction,
 ) -> Result<()> {
     if let Some(tx_hash) = auction.tx_hash.clone() {
+        let tx_hash = TxHash::from_bytes(&auction.chain_type, &tx_hash)?;
         let bids: Vec<SimulatedBid> = store.bids_for_submitted_auction(auction.clone()).await;
         let bids = T::convert_bids(bids);
-        if let Some(bid_statuses) = chain_store.get_bid_results(bids.clone(), tx_hash).await? {
+        if let Some(BidResults {
+            statuses: bid_statuses,
+            inclusion_block,
+        }) = chain_store.get_bid_results(bids.clone(), tx_hash).await?
+        {
             let auction = store
-                .conclude_auction(auction)
+                .conclude_auction(auction, inclusion_block.as_ref())
                 .await
                 .map_err(|e| anyhow!("Failed to conclude auction: {:?}", e))?;
+            increment_auction_conclusions(&auction.chain_id, "onchain");
 
             join_all(bid_statuses.iter().enumerate().map(|(index, bid_status)| {
                 let (bids, store, auction, bid_statuses) = (bids.clone(), store.clone(), auction.clone(), bid_statuses.clone());
@@ -293,6 +332,14 @@ async fn conclude_submitted_auction<T: ChainStore>(
                 }
             }))
             .await;
+            let winning_amount = bids
+                .iter()
+                .zip(bid_statuses.iter())
+                .filter(|(_, bid_status)| matches!(bid_status, BidStatus::Won { .. }))
+                .fold(BidAmount::zero(), |total, (bid, _)| {
+                    total.saturating_add(bid.get_core_fields().bid_amount)
+                });
+            store.broadcast_auction_conclusion(&auction, winning_amount);
             store.remove_submitted_auction(auction).await;
         }
     }
@@ -344,12 +391,11 @@ async fn conclude_submitted_auctions(store: Arc<Store>, chain_id: String) {
 async fn broadcast_submitted_bids<T: SimulatedBidTrait>(
     store: Arc<Store>,
     bids: Vec<T>,
-    tx_hash: Vec<u8>,
+    tx_hash: TxHash,
     auction: models::Auction,
 ) {
     join_all(bids.iter().enumerate().map(|(i, bid)| {
-        let (store, auction, index, tx_hash) =
-            (store.clone(), auction.clone(), i as u32, tx_hash.clone());
+        let (store, auction, index, tx_hash) = (store.clone(), auction.clone(), i as u32, tx_hash);
         async move {
             if let Err(err) = store
                 .broadcast_bid_status_and_update(
@@ -373,8 +419,9 @@ async fn broadcast_lost_bids<T: SimulatedBidTrait>(
     store: Arc<Store>,
     bids: Vec<T>,
     submitted_bids: Vec<T>,
-    tx_hash: Option<Vec<u8>>,
+    tx_hash: Option<TxHash>,
     auction: Option<&models::Auction>,
+    reason: Option<BidLostReason>,
 ) {
     join_all(bids.iter().filter_map(|bid| {
         if submitted_bids
@@ -384,14 +431,15 @@ async fn broadcast_lost_bids<T: SimulatedBidTrait>(
             return None;
         }
 
-        let (store, tx_hash) = (store.clone(), tx_hash.clone());
+        let (store, reason) = (store.clone(), reason.clone());
         Some(async move {
             if let Err(err) = store
                 .broadcast_bid_status_and_update(
                     bid.clone(),
                     BidStatus::Lost {
                         result: tx_hash,
-                        index:  None,
+                        index: None,
+                        reason,
                     },
                     auction,
                 )
@@ -404,6 +452,51 @@ async fn broadcast_lost_bids<T: SimulatedBidTrait>(
     .await;
 }
 
+fn increment_auction_conclusions(chain_id: &ChainId, conclusion: &'static str) {
+    let labels = [
+        ("chain_id", chain_id.clone()),
+        ("conclusion", conclusion.to_string()),
+    ];
+    metrics::counter!("auction_conclusions_total", &labels).increment(1);
+}
+
+/// Concludes an auction without submitting any transaction on chain.
+///
+/// This is used when none of the bids is worth submitting. The auction is stored without a tx_hash
+/// and all of its bids are marked as lost.
+async fn conclude_auction_offchain<T: ChainStore>(
+    store: Arc<Store>,
+    bids: Vec<T::SimulatedBid>,
+    permission_key: Bytes,
+    chain_id: ChainId,
+    bid_collection_time: OffsetDateTime,
+) -> Result<()> {
+    let Some(auction) = store
+        .init_auction::<T>(
+            permission_key.clone(),
+            chain_id.clone(),
+            bid_collection_time,
+        )
+        .await?
+    else {
+        return Ok(());
+    };
+    let auction = store
+        .conclude_auction(auction, None)
+        .await
+        .map_err(|e| anyhow!("Failed to conclude auction: {:?}", e))?;
+
+    tracing::info!(
+        "Auction for {} on chain {} concluded off-chain",
+        permission_key,
+        chain_id
+    );
+    broadcast_lost_bids(store.clone(), bids, vec![], None, Some(&auction), None).await;
+    store.broadcast_auction_conclusion(&auction, BidAmount::zero());
+    increment_auction_conclusions(&chain_id, "offchain");
+    Ok(())
+}
+
 async fn submit_auction_for_bids<'a, T: ChainStore>(
     bids: Vec<SimulatedBid>,
     bid_collection_time: OffsetDateTime,
@@ -411,38 +504,101 @@ async fn submit_auction_for_bids<'a, T: ChainStore>(
     chain_id: String,
     store: Arc<Store>,
     chain_store: T,
-    _auction_mutex_gaurd: MutexGuard<'a, ()>,
+    _auction_mutex_gaurd: AuctionLockGuard<'a>,
 ) -> Result<()> {
     let bids = T::convert_bids(bids);
+    // Bids placed after the collection time belong to the next round,
+    // and the deferred bids wait until they are persisted
+    let deferred_bid_ids = store.get_deferred_bid_ids().await;
+    let now = OffsetDateTime::now_utc();
     let bids: Vec<T::SimulatedBid> = bids
         .into_iter()
-        .filter(|bid| bid.get_core_fields().status == BidStatus::Pending)
+        .filter(|bid| {
+            let core_fields = bid.get_core_fields();
+            core_fields.status == BidStatus::Pending
+                && core_fields.initiation_time <= bid_collection_time
+                && !core_fields.is_past_good_till(now)
+                && !deferred_bid_ids.contains(&core_fields.id)
+        })
         .collect();
 
     if bids.is_empty() {
         return Ok(());
     }
 
-    if !is_ready_for_auction::<T>(bids.clone(), bid_collection_time) {
+    // In the round based schedules, every bid placed before the round is auctioned in it
+    if chain_store.get_auction_schedule() == AuctionSchedule::PerKey
+        && !is_ready_for_auction::<T>(bids.clone(), bid_collection_time)
+    {
         tracing::info!("Auction for {} is not ready yet", permission_key);
         return Ok(());
     }
 
-    let winner_bids = chain_store
+    let min_bids = chain_store.get_min_bids();
+    if bids.len() < min_bids.count {
+        let oldest_bid_age = bids
+            .iter()
+            .map(|bid| bid_collection_time - bid.get_core_fields().initiation_time)
+            .max()
+            .unwrap_or_default();
+        if oldest_bid_age < Duration::from_secs(min_bids.timeout) {
+            tracing::info!(
+                "Auction for {} is waiting for more bids: {} of {}",
+                permission_key,
+                bids.len(),
+                min_bids.count
+            );
+            return Ok(());
+        }
+
+        let fallback = match min_bids.fallback {
+            MinBidsFallback::Submit => "submit",
+            MinBidsFallback::ConcludeOffchain => "conclude_offchain",
+        };
+        let labels = [
+            ("chain_id", chain_id.clone()),
+            ("fallback", fallback.to_string()),
+        ];
+        metrics::counter!("auction_min_bids_not_met_total", &labels).increment(1);
+        if min_bids.fallback == MinBidsFallback::ConcludeOffchain {
+            return conclude_auction_offchain::<T>(
+                store,
+                bids,
+                permission_key,
+                chain_id,
+                bid_collection_time,
+            )
+            .await;
+        }
+    }
+
+    let WinnerBids {
+        winners: winner_bids,
+        conflicting: conflicting_bids,
+    } = chain_store
         .get_winner_bids(&bids, permission_key.clone(), store.clone())
         .await?;
     if winner_bids.is_empty() {
-        broadcast_lost_bids(store.clone(), bids, winner_bids, None, None).await;
-        return Ok(());
+        return conclude_auction_offchain::<T>(
+            store,
+            bids,
+            permission_key,
+            chain_id,
+            bid_collection_time,
+        )
+        .await;
     }
 
-    let mut auction = store
+    let Some(mut auction) = store
         .init_auction::<T>(
             permission_key.clone(),
             chain_id.clone(),
             bid_collection_time,
         )
-        .await?;
+        .await?
+    else {
+        return Ok(());
+    };
 
     tracing::info!(
         "Submission for {} on chain {} started at {}",
@@ -455,22 +611,31 @@ async fn submit_auction_for_bids<'a, T: ChainStore>(
         .submit_bids(permission_key.clone(), winner_bids.clone(), store.clone())
         .await
     {
-        Ok(tx_hash) => {
+        Ok(AuctionSubmission { tx_hash, relayer }) => {
             tracing::debug!("Submitted transaction: {:?}", tx_hash);
-            auction = store.submit_auction(auction, tx_hash.clone()).await?;
+            auction = store.submit_auction(auction, tx_hash, relayer).await?;
             tokio::join!(
                 broadcast_submitted_bids(
                     store.clone(),
                     winner_bids.clone(),
-                    tx_hash.clone(),
+                    tx_hash,
                     auction.clone()
                 ),
+                broadcast_lost_bids(
+                    store.clone(),
+                    conflicting_bids.clone(),
+                    vec![],
+                    Some(tx_hash),
+                    Some(&auction),
+                    Some(BidLostReason::Conflict)
+                ),
                 broadcast_lost_bids(
                     store.clone(),
                     bids,
-                    winner_bids,
+                    [winner_bids, conflicting_bids].concat(),
                     Some(tx_hash),
-                    Some(&auction)
+                    Some(&auction),
+                    None
                 ),
             );
         }
@@ -485,9 +650,11 @@ async fn submit_auction_for_lock(
     store: Arc<Store>,
     permission_key: Bytes,
     chain_id: String,
-    auction_lock: AuctionLock,
+    round_time: Option<OffsetDateTime>,
 ) -> Result<()> {
-    let acquired_lock = auction_lock.lock().await;
+    let acquired_lock = store
+        .try_lock_auction((permission_key.clone(), chain_id.clone()))
+        .await?;
     let chain_store = store.chains.get(&chain_id);
     let chain_store_svm = store.chains_svm.get(&chain_id);
 
@@ -499,7 +666,7 @@ async fn submit_auction_for_lock(
         tracing::error!("Chain found in both EVM and SVM chains: {}", chain_id);
     }
 
-    let bid_collection_time: OffsetDateTime = OffsetDateTime::now_utc();
+    let bid_collection_time: OffsetDateTime = round_time.unwrap_or_else(OffsetDateTime::now_utc);
     let bids: Vec<SimulatedBid> = store
         .get_bids(&(permission_key.clone(), chain_id.clone()))
         .await;
@@ -531,37 +698,213 @@ async fn submit_auction_for_lock(
 }
 
 #[tracing::instrument(skip_all)]
-async fn submit_auction(store: Arc<Store>, permission_key: Bytes, chain_id: String) -> Result<()> {
+async fn submit_auction(
+    store: Arc<Store>,
+    permission_key: Bytes,
+    chain_id: String,
+    round_time: Option<OffsetDateTime>,
+) -> Result<()> {
     let key = (permission_key.clone(), chain_id.clone());
-    let auction_lock = store.get_auction_lock(key.clone()).await;
-    let result =
-        submit_auction_for_lock(store.clone(), permission_key, chain_id, auction_lock).await;
+    let result = submit_auction_for_lock(store.clone(), permission_key, chain_id, round_time).await;
     store.remove_auction_lock(&key).await;
     result
 }
 
+/// Returns the gas oracle applying the fee strategy and the fee cap of the chain.
+pub fn get_gas_oracle(
+    provider: Provider<TracedClient>,
+    chain_config: &ConfigEvm,
+) -> EthProviderOracle<Provider<TracedClient>> {
+    let oracle = EthProviderOracle::new(provider)
+        .with_max_fee_per_gas(chain_config.max_fee_per_gas.map(U256::from));
+    match chain_config.get_fee_strategy() {
+        FeeStrategy::Legacy => oracle,
+        FeeStrategy::Eip1559 {
+            priority_fee_percentile,
+        } => oracle.with_reward_percentile(priority_fee_percentile),
+    }
+}
+
 pub fn get_express_relay_contract(
-    address: Address,
+    chain_config: &ConfigEvm,
     provider: Provider<TracedClient>,
     relayer: LocalWallet,
-    use_legacy_tx: bool,
     network_id: u64,
 ) -> SignableExpressRelayContract {
-    let transformer = LegacyTxTransformer { use_legacy_tx };
+    let transformer = LegacyTxTransformer {
+        use_legacy_tx: chain_config.get_fee_strategy() == FeeStrategy::Legacy,
+    };
     let client = Arc::new(TransformerMiddleware::new(
         GasOracleMiddleware::new(
             NonceManagerMiddleware::new(
                 SignerMiddleware::new(provider.clone(), relayer.clone().with_chain_id(network_id)),
                 relayer.address(),
             ),
-            EthProviderOracle::new(provider),
+            get_gas_oracle(provider, chain_config),
         ),
         transformer,
     ));
-    SignableExpressRelayContract::new(address, client)
+    SignableExpressRelayContract::new(chain_config.express_relay_contract, client)
+}
+
+/// Returns true if the chain is tracking too many submitted auctions, which usually means the auction conclusion is stalled.
+async fn is_submitted_auctions_limit_reached(store: &Store, chain_id: &ChainId) -> bool {
+    // Number of the oldest unconcluded auctions to log when the limit is reached
+    const OLDEST_AUCTIONS_TO_LOG: usize = 5;
+
+    let Some(&limit) = store.max_submitted_auctions.get(chain_id) else {
+        return false;
+    };
+    let mut submitted_auctions = store.get_submitted_auctions(chain_id).await;
+    let label = [("chain_id", chain_id.clone())];
+    metrics::gauge!("submitted_auctions", &label).set(submitted_auctions.len() as f64);
+    if submitted_auctions.len() < limit {
+        return false;
+    }
+
+    metrics::counter!("submitted_auctions_limit_reached_total", &label).increment(1);
+    submitted_auctions.sort_by_key(|auction| auction.submission_time);
+    let oldest_auctions: Vec<String> = submitted_auctions
+        .iter()
+        .take(OLDEST_AUCTIONS_TO_LOG)
+        .map(|auction| {
+            format!(
+                "{} (submitted at {:?})",
+                auction.id, auction.submission_time
+            )
+        })
+        .collect();
+    tracing::warn!(
+        "Chain: {chain_id} Submitted auctions limit reached ({count}/{limit}), skipping new submissions. Oldest unconcluded auctions: {oldest_auctions:?}",
+        chain_id = chain_id,
+        count = submitted_auctions.len(),
+        limit = limit,
+        oldest_auctions = oldest_auctions,
+    );
+    true
+}
+
+/// Returns true if no submitted auction of the chain was concluded within the stall timeout, although some auctions
+/// were submitted before the timeout. Submissions are paused while the tracker is stalled, so no fees are spent on
+/// transactions whose settlement can not be confirmed, and resume once an auction is concluded again.
+async fn is_tracker_stalled(store: &Store, chain_id: &ChainId) -> bool {
+    let Some(stall_timeout) = store.tracker_stall_timeout else {
+        return false;
+    };
+    let stalled_since = OffsetDateTime::now_utc() - stall_timeout;
+    let has_old_submissions = store
+        .get_submitted_auctions(chain_id)
+        .await
+        .iter()
+        .filter_map(|auction| auction.submission_time)
+        .any(|submission_time| submission_time.assume_utc() < stalled_since);
+    let has_recent_conclusion = store
+        .last_auction_conclusions
+        .read()
+        .await
+        .get(chain_id)
+        .is_some_and(|conclusion_time| *conclusion_time >= stalled_since);
+    let is_stalled = has_old_submissions && !has_recent_conclusion;
+
+    let label = [("chain_id", chain_id.clone())];
+    metrics::gauge!("submissions_paused", &label).set(if is_stalled { 1.0 } else { 0.0 });
+    let mut stalled_chains = store.stalled_chains.write().await;
+    if is_stalled && stalled_chains.insert(chain_id.clone()) {
+        metrics::counter!("submission_pauses_total", &label).increment(1);
+        tracing::error!(
+            "Chain: {chain_id} No submitted auction was concluded in the last {timeout:?}, pausing submissions",
+            chain_id = chain_id,
+            timeout = stall_timeout,
+        );
+    } else if !is_stalled && stalled_chains.remove(chain_id) {
+        tracing::info!(
+            "Chain: {chain_id} Submitted auctions are concluded again, resuming submissions",
+            chain_id = chain_id,
+        );
+    }
+    is_stalled
+}
+
+/// Returns true if the bid is pending and was placed before the expiry time, or its good till time has passed.
+fn is_bid_expired(
+    core_fields: &SimulatedBidCoreFields,
+    expiry_time: Option<OffsetDateTime>,
+    now: OffsetDateTime,
+) -> bool {
+    core_fields.status == BidStatus::Pending
+        && (expiry_time.is_some_and(|expiry_time| core_fields.initiation_time <= expiry_time)
+            || core_fields.is_past_good_till(now))
+}
+
+/// Expires the pending bids of the chain which were not submitted within the bid expiry time.
+/// Only the auctions with expired bids are locked. The auctions currently being submitted are skipped,
+/// their bids are expired on a later block if still pending.
+async fn expire_pending_bids(store: &Store, chain_id: &ChainId) {
+    let now = OffsetDateTime::now_utc();
+    let expiry_time = store.bid_expiry.map(|bid_expiry| now - bid_expiry);
+    let keys = store
+        .get_auction_keys_with_bid(chain_id, |core_fields| {
+            is_bid_expired(core_fields, expiry_time, now)
+        })
+        .await;
+    if keys.is_empty() {
+        return;
+    }
+
+    let deferred_bid_ids = store.get_deferred_bid_ids().await;
+    for key in keys {
+        let auction_lock = store.get_auction_lock(key.clone()).await;
+        if let Ok(_acquired_lock) = auction_lock.try_lock() {
+            // The bids are checked again, as their status may have changed before the lock was acquired
+            for bid in store.get_bids(&key).await {
+                let core_fields = bid.get_core_fields();
+                if !is_bid_expired(&core_fields, expiry_time, now)
+                    || deferred_bid_ids.contains(&core_fields.id)
+                {
+                    continue;
+                }
+                let result = match bid {
+                    SimulatedBid::Evm(bid) => {
+                        store
+                            .broadcast_bid_status_and_update(bid, BidStatus::Expired, None)
+                            .await
+                    }
+                    SimulatedBid::Svm(bid) => {
+                        store
+                            .broadcast_bid_status_and_update(bid, BidStatus::Expired, None)
+                            .await
+                    }
+                };
+                match result {
+                    Ok(()) => {
+                        metrics::counter!("bids_expired_total", &[("chain_id", chain_id.clone())])
+                            .increment(1)
+                    }
+                    Err(e) => tracing::error!(
+                        "Failed to expire bid: {:?} - bid_id: {:?}",
+                        e,
+                        core_fields.id
+                    ),
+                }
+            }
+        }
+        drop(auction_lock);
+        store.remove_auction_lock(&key).await;
+    }
 }
 
-async fn submit_auctions(store: Arc<Store>, chain_id: String) {
+/// Submits the auctions of the chain which are ready. The round time is set for the round based schedules,
+/// so only the bids placed before the round are collected for it.
+async fn submit_auctions(store: Arc<Store>, chain_id: String, round_time: Option<OffsetDateTime>) {
+    // Expire before checking the submission limit, as the bids linger the most while submissions are blocked
+    expire_pending_bids(&store, &chain_id).await;
+    if is_submitted_auctions_limit_reached(&store, &chain_id).await {
+        return;
+    }
+    if is_tracker_stalled(&store, &chain_id).await {
+        return;
+    }
+
     let permission_keys = store.get_permission_keys_for_auction(&chain_id).await;
 
     tracing::info!(
@@ -570,13 +913,42 @@ async fn submit_auctions(store: Arc<Store>, chain_id: String) {
         auction_len = permission_keys.len()
     );
 
-    for permission_key in permission_keys.iter() {
+    let semaphore = store.submission_semaphores.get(&chain_id).cloned();
+    for (index, (permission_key, _)) in permission_keys.iter().enumerate() {
+        // Permits are taken in the priority order, so the most valuable auctions are submitted first.
+        // The remaining keys are not queued for a permit, they are ranked again with the new bids next time.
+        let permit = match &semaphore {
+            Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(TryAcquireError::NoPermits) => {
+                    let skipped = permission_keys.len() - index;
+                    tracing::info!(
+                        "Chain: {chain_id} Submission limit reached, skipping {skipped} auctions",
+                        chain_id = chain_id,
+                        skipped = skipped,
+                    );
+                    metrics::counter!(
+                        "submissions_skipped_total",
+                        &[("chain_id", chain_id.clone())]
+                    )
+                    .increment(skipped as u64);
+                    return;
+                }
+                Err(err) => {
+                    tracing::error!("Failed to acquire submission permit: {:?}", err);
+                    return;
+                }
+            },
+            None => None,
+        };
         store.task_tracker.spawn({
             let (store, permission_key, chain_id) =
                 (store.clone(), permission_key.clone(), chain_id.clone());
             async move {
+                let _permit = permit;
                 if let Err(err) =
-                    submit_auction(store, permission_key.clone(), chain_id.clone()).await
+                    submit_auction(store, permission_key.clone(), chain_id.clone(), round_time)
+                        .await
                 {
                     tracing::error!(
                         "Failed to submit auction: {:?} - permission_key: {:?} - chain_id: {:?}",
@@ -608,6 +980,11 @@ pub struct BidEvm {
     #[schema(example = "10", value_type = String)]
     #[serde(with = "crate::serde::u256")]
     pub amount:          BidAmount,
+    /// The time until which the bid can be auctioned, formatted in rfc3339.
+    /// The bid expires if it is still pending after this time.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub good_till:       Option<OffsetDateTime>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
@@ -619,6 +996,11 @@ pub struct BidSvm {
     #[schema(example = "SGVsbG8sIFdvcmxkIQ==", value_type = String)]
     #[serde(with = "crate::serde::transaction_svm")]
     pub transaction: VersionedTransaction,
+    /// The time until which the bid can be auctioned, formatted in rfc3339.
+    /// The bid expires if it is still pending after this time.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub good_till:   Option<OffsetDateTime>,
 }
 
 #[derive(Serialize, ToSchema, Debug, Clone)]
@@ -661,7 +1043,7 @@ where
     let (maximum_gas_fee, priority_fee) = oracle
         .estimate_eip1559_fees()
         .await
-        .map_err(|_| RestError::TemporarilyUnavailable)?;
+        .map_err(|_| RestError::TemporarilyUnavailable(UnavailableReason::Chain))?;
 
     // To submit TOTAL_BIDS_PER_AUCTION together, each bid must cover the gas fee for all of the submitted bids.
     // To make sure we cover the estimation errors, we add the priority_fee to the final potential gas fee.
@@ -691,8 +1073,9 @@ async fn verify_bid_under_gas_limit(
     estimated_gas: U256,
     multiplier: U256,
 ) -> Result<(), RestError> {
-    if chain_store.block_gas_limit < estimated_gas * multiplier {
-        let maximum_allowed_gas = chain_store.block_gas_limit / multiplier;
+    let block_gas_limit = *chain_store.block_gas_limit.read().await;
+    if block_gas_limit < estimated_gas * multiplier {
+        let maximum_allowed_gas = block_gas_limit / multiplier;
         tracing::info!(
             estimated_gas = estimated_gas.to_string(),
             maximum_allowed_gas = maximum_allowed_gas.to_string(),
@@ -707,6 +1090,217 @@ async fn verify_bid_under_gas_limit(
     }
 }
 
+/// Waits for a free simulation slot on the chain, so bursts of bids do not overwhelm the RPC node.
+/// Returns None if the simulations are not limited on the chain.
+async fn acquire_simulation_permit<'a>(
+    chain_store: &'a ChainStoreEvm,
+    chain_id: &ChainId,
+) -> Result<Option<SemaphorePermit<'a>>, RestError> {
+    let Some(semaphore) = &chain_store.simulation_semaphore else {
+        return Ok(None);
+    };
+    let label = [("chain_id", chain_id.clone())];
+    let queue_timeout = Duration::from_secs(chain_store.config.simulation_queue_timeout);
+    metrics::gauge!("simulation_queue_depth", &label).increment(1.0);
+    let permit = tokio::time::timeout(queue_timeout, semaphore.acquire()).await;
+    metrics::gauge!("simulation_queue_depth", &label).decrement(1.0);
+    match permit {
+        Ok(Ok(permit)) => Ok(Some(permit)),
+        Ok(Err(e)) => {
+            tracing::error!("Simulation semaphore is closed: {:?}", e);
+            Err(RestError::TemporarilyUnavailable(
+                UnavailableReason::Internal,
+            ))
+        }
+        Err(_) => {
+            tracing::warn!(
+                "No simulation slot became free after {:?} on chain {}",
+                queue_timeout,
+                chain_id
+            );
+            metrics::counter!("simulation_queue_timeouts_total", &label).increment(1);
+            Err(RestError::TemporarilyUnavailable(
+                UnavailableReason::Overloaded,
+            ))
+        }
+    }
+}
+
+async fn verify_target_contract_allowed(
+    chain_store: &ChainStoreEvm,
+    target_contract: Address,
+) -> Result<(), RestError> {
+    let allowlist = chain_store.target_contract_allowlist.read().await;
+    if !allowlist.is_empty() && !allowlist.contains(&target_contract) {
+        return Err(RestError::BadParameters(format!(
+            "Target contract {:?} is not allowed on this chain",
+            target_contract
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that the protocol fee of the bid goes to the fee recipient pinned for the chain, if any.
+fn verify_fee_recipient_evm(
+    chain_store: &ChainStoreEvm,
+    permission_key: &PermissionKey,
+) -> Result<(), RestError> {
+    let Some(fee_recipient) = chain_store.config.fee_recipient else {
+        return Ok(());
+    };
+    match permission_key.get(..Address::len_bytes()) {
+        Some(recipient) if recipient == fee_recipient.as_bytes() => Ok(()),
+        _ => Err(RestError::BadParameters(format!(
+            "Permission key should start with the fee recipient {:?} of this chain",
+            fee_recipient
+        ))),
+    }
+}
+
+/// Checks that the bid pays the fee to the router pinned for the chain, if any.
+/// The router account is the second half of the permission key of svm bids.
+fn verify_fee_recipient_svm(
+    chain_store: &ChainStoreSvm,
+    permission_key: &PermissionKey,
+) -> Result<(), RestError> {
+    let Some(fee_recipient) = chain_store.config.fee_recipient else {
+        return Ok(());
+    };
+    match permission_key.get(PUBKEY_BYTES..) {
+        Some(router) if router == fee_recipient.as_ref() => Ok(()),
+        _ => Err(RestError::BadParameters(format!(
+            "Router account should be the fee recipient {} of this chain",
+            fee_recipient
+        ))),
+    }
+}
+
+/// Checks that the bid amount is at least the minimum increment above the current best pending bid.
+async fn verify_bid_increment(
+    store: &Store,
+    auction_key: &AuctionKey,
+    bid_amount: BidAmount,
+    min_bid_increment: Option<MinBidIncrement>,
+) -> Result<(), RestError> {
+    let Some(min_bid_increment) = min_bid_increment else {
+        return Ok(());
+    };
+    let Some(best_bid_amount) = store
+        .get_bids(auction_key)
+        .await
+        .iter()
+        .map(|bid| bid.get_core_fields())
+        .filter(|core_fields| core_fields.status == BidStatus::Pending)
+        .map(|core_fields| core_fields.bid_amount)
+        .max()
+    else {
+        return Ok(());
+    };
+
+    let increment = match min_bid_increment {
+        MinBidIncrement::Absolute(amount) => BidAmount::from(amount),
+        MinBidIncrement::BasisPoints(basis_points) => {
+            best_bid_amount.saturating_mul(BidAmount::from(basis_points)) / 10_000
+        }
+    };
+    let minimum_bid_amount = best_bid_amount.saturating_add(increment);
+    if bid_amount <= best_bid_amount || bid_amount >= minimum_bid_amount {
+        return Ok(());
+    }
+    Err(RestError::BadParameters(format!(
+        "Insufficient increment over the current best bid. bid amount should be either at most {} or at least {}",
+        best_bid_amount, minimum_bid_amount
+    )))
+}
+
+fn verify_good_till(
+    good_till: Option<OffsetDateTime>,
+    initiation_time: OffsetDateTime,
+) -> result::Result<(), RestError> {
+    match good_till {
+        Some(good_till) if good_till <= initiation_time => Err(RestError::BadParameters(
+            "Good till time of the bid should be in the future".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks if the permission key was only used on other chains before, which is suspicious for a new bid.
+/// The check only rejects bids in the reject mode. In the log mode it runs in the background, so it does not
+/// delay the bid and a failing check does not fail it. Only the uses before the bid are checked, so the bid
+/// itself does not hide a mismatch once it is stored.
+async fn verify_permission_key_chain(
+    store: &Arc<Store>,
+    permission_key: &PermissionKey,
+    chain_id: &ChainId,
+    initiation_time: OffsetDateTime,
+) -> Result<(), RestError> {
+    match store.permission_key_chain_check {
+        PermissionKeyChainCheck::Disabled => Ok(()),
+        PermissionKeyChainCheck::Log => {
+            store.task_tracker.spawn({
+                let (store, permission_key, chain_id) =
+                    (store.clone(), permission_key.clone(), chain_id.clone());
+                async move {
+                    // The DB errors are logged when fetching the chains
+                    let _ = find_permission_key_chain_mismatch(
+                        &store,
+                        &permission_key,
+                        &chain_id,
+                        initiation_time,
+                    )
+                    .await;
+                }
+            });
+            Ok(())
+        }
+        PermissionKeyChainCheck::Reject => {
+            match find_permission_key_chain_mismatch(
+                store,
+                permission_key,
+                chain_id,
+                initiation_time,
+            )
+            .await?
+            {
+                Some(chains) => Err(RestError::BadParameters(format!(
+                    "Permission key was used on other chains: {:?}",
+                    chains
+                ))),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+/// Returns the chains which the permission key was used on, if none of them is the bid chain.
+async fn find_permission_key_chain_mismatch(
+    store: &Store,
+    permission_key: &PermissionKey,
+    chain_id: &ChainId,
+    initiation_time: OffsetDateTime,
+) -> Result<Option<Vec<ChainId>>, RestError> {
+    let chains = store
+        .get_permission_key_chains(permission_key, initiation_time)
+        .await?;
+    if chains.is_empty() || chains.contains(chain_id) {
+        return Ok(None);
+    }
+
+    tracing::warn!(
+        "Bid for permission key {} on chain {} which was only used on chains {:?}",
+        permission_key,
+        chain_id,
+        chains
+    );
+    metrics::counter!(
+        "bid_permission_key_chain_mismatches_total",
+        &[("chain_id", chain_id.clone())]
+    )
+    .increment(1);
+    Ok(Some(chains))
+}
+
 // As we submit bids together for an auction, the bid is limited as follows:
 // 1. The bid amount should cover gas fees for all bids included in the submission.
 // 2. Depending on the maximum number of bids in the auction, the transaction size for the bid is limited.
@@ -718,12 +1312,38 @@ pub async fn handle_bid(
     initiation_time: OffsetDateTime,
     auth: Auth,
 ) -> result::Result<Uuid, RestError> {
+    let simulated_bid = verify_bid(store.clone(), bid, initiation_time, auth).await?;
+    let id = simulated_bid.core_fields.id;
+    store.add_bid(simulated_bid.into(), None).await?;
+    Ok(id)
+}
+
+/// Verifies and simulates the evm bid, returning the bid to add to the auction.
+#[tracing::instrument(skip_all)]
+pub async fn verify_bid(
+    store: Arc<Store>,
+    bid: BidEvm,
+    initiation_time: OffsetDateTime,
+    auth: Auth,
+) -> result::Result<SimulatedBidEvm, RestError> {
     let chain_store = store
         .chains
         .get(&bid.chain_id)
         .ok_or(RestError::InvalidChainId)?;
+    verify_good_till(bid.good_till, initiation_time)?;
+    verify_fee_recipient_evm(chain_store, &bid.permission_key)?;
+    verify_target_contract_allowed(chain_store, bid.target_contract).await?;
+    verify_permission_key_chain(&store, &bid.permission_key, &bid.chain_id, initiation_time)
+        .await?;
+    verify_bid_increment(
+        &store,
+        &(bid.permission_key.clone(), bid.chain_id.clone()),
+        bid.amount,
+        chain_store.config.min_bid_increment,
+    )
+    .await?;
     let call = get_simulation_call(
-        store.relayer.address(),
+        chain_store.relayers.primary().address(),
         chain_store.provider.clone(),
         chain_store.config.clone(),
         bid.permission_key.clone(),
@@ -741,7 +1361,21 @@ pub async fn handle_bid(
         ))],
     );
 
-    match call.clone().await {
+    let simulation_permit = acquire_simulation_permit(chain_store, &bid.chain_id).await?;
+    let simulation_timeout = Duration::from_secs(chain_store.config.simulation_timeout);
+    let simulation_result = tokio::time::timeout(simulation_timeout, call.clone().call())
+        .await
+        .map_err(|_| {
+            tracing::warn!(
+                "Bid simulation timed out after {:?} on chain {}",
+                simulation_timeout,
+                bid.chain_id
+            );
+            let label = [("chain_id", bid.chain_id.clone())];
+            metrics::counter!("bid_simulation_timeouts_total", &label).increment(1);
+            RestError::TemporarilyUnavailable(UnavailableReason::Chain)
+        })?;
+    match simulation_result {
         Ok(results) => {
             if !results[0].external_success {
                 // The call should be reverted because the "revert_on_failure" is set to true.
@@ -769,8 +1403,12 @@ pub async fn handle_bid(
                         reason,
                     )))
                 }
-                ContractError::MiddlewareError { e: _ } => Err(RestError::TemporarilyUnavailable),
-                ContractError::ProviderError { e: _ } => Err(RestError::TemporarilyUnavailable),
+                ContractError::MiddlewareError { e: _ } => {
+                    Err(RestError::TemporarilyUnavailable(UnavailableReason::Chain))
+                }
+                ContractError::ProviderError { e: _ } => {
+                    Err(RestError::TemporarilyUnavailable(UnavailableReason::Chain))
+                }
                 _ => Err(RestError::BadParameters(format!("Error: {}", e))),
             };
         }
@@ -778,12 +1416,13 @@ pub async fn handle_bid(
 
     let estimated_gas = call.estimate_gas().await.map_err(|e| {
         tracing::error!("Error while estimating gas: {:?}", e);
-        RestError::TemporarilyUnavailable
+        RestError::TemporarilyUnavailable(UnavailableReason::Chain)
     })?;
+    drop(simulation_permit);
 
     verify_bid_exceeds_gas_cost(
         estimated_gas,
-        EthProviderOracle::new(chain_store.provider.clone()),
+        get_gas_oracle(chain_store.provider.clone(), &chain_store.config),
         bid.amount,
     )
     .await?;
@@ -800,53 +1439,159 @@ pub async fn handle_bid(
         bid.chain_id,
         bid.permission_key,
         initiation_time,
+        bid.good_till,
         auth,
     );
-    let simulated_bid = SimulatedBidEvm {
-        core_fields:     core_fields.clone(),
+    Ok(SimulatedBidEvm {
+        core_fields,
         target_contract: bid.target_contract,
         target_calldata: bid.target_calldata.clone(),
         // Add a 25% more for estimation errors
-        gas_limit:       estimated_gas * U256::from(125) / U256::from(100),
+        gas_limit: estimated_gas * U256::from(125) / U256::from(100),
+    })
+}
+
+/// Updates the block gas limit of the chain from the latest block, as chains can change it over time.
+async fn refresh_block_gas_limit(chain_store: &ChainStoreEvm, chain_id: &ChainId) {
+    let block = match chain_store.provider.get_block(BlockNumber::Latest).await {
+        Ok(Some(block)) => block,
+        Ok(None) => {
+            tracing::error!("Latest block not found - chain_id: {}", chain_id);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Error while getting latest block. error: {:?}", e);
+            return;
+        }
     };
-    store.add_bid(simulated_bid.into()).await?;
-    Ok(core_fields.id)
+    let mut block_gas_limit = chain_store.block_gas_limit.write().await;
+    if *block_gas_limit != block.gas_limit {
+        tracing::info!(
+            chain_id = chain_id,
+            previous_gas_limit = block_gas_limit.to_string(),
+            gas_limit = block.gas_limit.to_string(),
+            "Block gas limit changed"
+        );
+        *block_gas_limit = block.gas_limit;
+    }
+}
+
+/// Checks the auctions concluded within the reorg check depth of the chain for reorgs which dropped their transaction.
+/// A reorg is confirmed when the receipt of the transaction is gone and its inclusion block is replaced on chain,
+/// so a node which is behind does not revert the auctions.
+async fn check_reorged_auctions(
+    store: &Arc<Store>,
+    chain_store: &ChainStoreEvm,
+    chain_id: &ChainId,
+) -> Result<()> {
+    let latest_block_number = chain_store.provider.get_block_number().await?.as_u64();
+    let auctions = store
+        .get_auctions_concluded_since_block(
+            chain_id,
+            latest_block_number.saturating_sub(chain_store.config.reorg_check_depth),
+        )
+        .await?;
+    for auction in auctions {
+        let (Some(tx_hash), Some(block_number), Some(block_hash)) = (
+            auction.tx_hash.as_deref(),
+            auction.conclusion_block_number,
+            auction.conclusion_block_hash.as_deref(),
+        ) else {
+            continue;
+        };
+        let tx_hash = H256::from_slice(tx_hash);
+        if chain_store
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+        let canonical_block_hash = chain_store
+            .provider
+            .get_block(block_number as u64)
+            .await?
+            .and_then(|block| block.hash);
+        if canonical_block_hash == Some(H256::from_slice(block_hash)) {
+            continue;
+        }
+
+        let is_pending = chain_store
+            .provider
+            .get_transaction(tx_hash)
+            .await?
+            .is_some();
+        tracing::warn!(
+            chain_id = chain_id,
+            "Transaction {:?} of auction {} was dropped from block {} by a reorg, pending: {}",
+            tx_hash,
+            auction.id,
+            block_number,
+            is_pending
+        );
+        let labels = [
+            ("chain_id", chain_id.clone()),
+            (
+                "result",
+                if is_pending { "resubmitted" } else { "lost" }.to_string(),
+            ),
+        ];
+        metrics::counter!("auction_reorgs_total", &labels).increment(1);
+        store.revert_reorged_auction(auction, is_pending).await?;
+    }
+    Ok(())
 }
 
-pub async fn run_tracker_loop(store: Arc<Store>, chain_id: String) -> Result<()> {
+pub async fn run_tracker_loop(
+    store: Arc<Store>,
+    chain_id: String,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
     tracing::info!(chain_id = chain_id, "Starting tracker...");
     let chain_store = store
         .chains
         .get(&chain_id)
         .ok_or(anyhow!("Chain not found: {}", chain_id))?;
 
-    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
-
     // this should be replaced by a subscription to the chain and trigger on new blocks
-    let mut submission_interval = tokio::time::interval(Duration::from_secs(10));
-    while !SHOULD_EXIT.load(Ordering::Acquire) {
+    let mut submission_interval = tokio::time::interval(TRACKER_INTERVAL);
+    let mut block_gas_limit_interval = tokio::time::interval(Duration::from_secs(
+        chain_store.config.block_gas_limit_refresh_interval,
+    ));
+    let mut reorg_check_interval = tokio::time::interval(TRACKER_INTERVAL);
+    loop {
         tokio::select! {
-            _ = submission_interval.tick() => {
-                match chain_store.provider.get_balance(store.relayer.address(), None).await {
-                    Ok(r) => {
-                        // This conversion to u128 is fine as the total balance will never cross the limits
-                        // of u128 practically.
-                        // The f64 conversion is made to be able to serve metrics within the constraints of Prometheus.
-                        // The balance is in wei, so we need to divide by 1e18 to convert it to eth.
-                        let balance = r.as_u128() as f64 / 1e18;
-                        let label = [
-                            ("chain_id", chain_id.clone()),
-                            ("address", format!("{:?}", store.relayer.address())),
-                        ];
-                        metrics::gauge!("relayer_balance", &label).set(balance);
-                    }
-                    Err(e) => {
-                        tracing::error!("Error while getting balance. error: {:?}", e);
-                    }
-                };
+            _ = block_gas_limit_interval.tick() => {
+                refresh_block_gas_limit(chain_store, &chain_id).await;
+            }
+            _ = reorg_check_interval.tick() => {
+                if let Err(e) = check_reorged_auctions(&store, chain_store, &chain_id).await {
+                    tracing::error!(chain_id = chain_id, "Failed to check reorged auctions: {:?}", e);
+                }
             }
-            _ = exit_check_interval.tick() => {
+            _ = submission_interval.tick() => {
+                for relayer in chain_store.relayers.iter() {
+                    match chain_store.provider.get_balance(relayer.address(), None).await {
+                        Ok(r) => {
+                            // This conversion to u128 is fine as the total balance will never cross the limits
+                            // of u128 practically.
+                            // The f64 conversion is made to be able to serve metrics within the constraints of Prometheus.
+                            // The balance is in wei, so we need to divide by 1e18 to convert it to eth.
+                            let balance = r.as_u128() as f64 / 1e18;
+                            let label = [
+                                ("chain_id", chain_id.clone()),
+                                ("address", format!("{:?}", relayer.address())),
+                            ];
+                            metrics::gauge!("relayer_balance", &label).set(balance);
+                        }
+                        Err(e) => {
+                            tracing::error!("Error while getting balance. error: {:?}", e);
+                        }
+                    };
+                }
             }
+            _ = shutdown.recv() => break,
         }
     }
     tracing::info!("Shutting down tracker...");
@@ -877,16 +1622,93 @@ pub fn verify_submit_bid_instruction_svm(
 
     match submit_bid_instructions.len() {
         1 => Ok(submit_bid_instructions[0].clone()),
-        _ => Err(RestError::BadParameters(
-            "Bid has to include exactly one submit_bid instruction to Express Relay program"
-                .to_string(),
+        0 => Err(RestError::BadParameters(
+            "Bid has to include a submit_bid instruction to Express Relay program".to_string(),
         )),
+        count => Err(RestError::BadParameters(format!(
+            "Bid has to include exactly one submit_bid instruction to Express Relay program, found {}",
+            count
+        ))),
     }
 }
 
-fn extract_account_svm(
-    accounts: &[Pubkey],
-    instruction: CompiledInstruction,
+/// Validates the structure of the bid transaction, so a malformed transaction is rejected up front instead of
+/// failing on submission. Returns the submit_bid instruction of the transaction.
+///
+/// The relayer co-signs the whole transaction on submission, so it may only appear as the relayer signer of the
+/// submit_bid instruction. It can not pay the fees, be writable or be passed to any other instruction.
+pub fn validate_svm_bid_transaction(
+    chain_store: &ChainStoreSvm,
+    express_relay_svm: &ExpressRelaySvm,
+    transaction: &VersionedTransaction,
+) -> Result<CompiledInstruction, RestError> {
+    let message = &transaction.message;
+    let accounts = message.static_account_keys();
+    let submit_bid_instruction =
+        verify_submit_bid_instruction_svm(chain_store, transaction.clone())?;
+    for (name, position) in [
+        ("permission", express_relay_svm.permission_account_position),
+        ("router", express_relay_svm.router_account_position),
+        (
+            "relayer signer",
+            express_relay_svm.relayer_signer_account_position,
+        ),
+    ] {
+        let account_index = submit_bid_instruction
+            .accounts
+            .get(position)
+            .ok_or_else(|| {
+                RestError::BadParameters(format!(
+                    "The submit_bid instruction is missing the {} account at position {}",
+                    name, position
+                ))
+            })?;
+        if usize::from(*account_index) >= accounts.len() {
+            return Err(RestError::BadParameters(format!(
+                "The {} account of the submit_bid instruction should be a static account of the transaction, not loaded from an address lookup table",
+                name
+            )));
+        }
+    }
+
+    let relayer = express_relay_svm.relayer.pubkey();
+    let relayer_index =
+        submit_bid_instruction.accounts[express_relay_svm.relayer_signer_account_position];
+    let relayer_signer = accounts[usize::from(relayer_index)];
+    if relayer_signer != relayer {
+        return Err(RestError::BadParameters(format!(
+            "The relayer signer of the submit_bid instruction should be {}, found {}",
+            relayer, relayer_signer
+        )));
+    }
+    if relayer_index == 0 {
+        return Err(RestError::BadParameters(
+            "The relayer can not be the fee payer of the transaction".to_string(),
+        ));
+    }
+    if message.is_maybe_writable(usize::from(relayer_index), None) {
+        return Err(RestError::BadParameters(
+            "The relayer can not be a writable account of the transaction".to_string(),
+        ));
+    }
+    let relayer_uses = message
+        .instructions()
+        .iter()
+        .flat_map(|instruction| instruction.accounts.iter())
+        .filter(|account_index| **account_index == relayer_index)
+        .count();
+    if relayer_uses > 1 {
+        return Err(RestError::BadParameters(
+            "The relayer can only be used as the relayer signer of the submit_bid instruction"
+                .to_string(),
+        ));
+    }
+    Ok(submit_bid_instruction)
+}
+
+fn extract_account_svm(
+    accounts: &[Pubkey],
+    instruction: CompiledInstruction,
     position: usize,
 ) -> Result<Pubkey, RestError> {
     let account_position = instruction.accounts.get(position).ok_or_else(|| {
@@ -937,86 +1759,197 @@ fn extract_bid_data_svm(
     Ok((submit_bid_data.bid_amount, concat.into()))
 }
 
+/// Verifies and simulates the svm bid, returning the bid to add to the auction.
 #[tracing::instrument(skip_all)]
-pub async fn handle_bid_svm(
+pub async fn verify_bid_svm(
     store: Arc<Store>,
     bid: BidSvm,
     initiation_time: OffsetDateTime,
     auth: Auth,
-) -> result::Result<Uuid, RestError> {
+) -> result::Result<SimulatedBidSvm, RestError> {
     let chain_store = store
         .chains_svm
         .get(&bid.chain_id)
         .ok_or(RestError::InvalidChainId)?;
+    verify_good_till(bid.good_till, initiation_time)?;
 
     let submit_bid_instruction =
-        verify_submit_bid_instruction_svm(chain_store, bid.transaction.clone())?;
+        validate_svm_bid_transaction(chain_store, &store.express_relay_svm, &bid.transaction)?;
     let (bid_amount, permission_key) = extract_bid_data_svm(
         store.express_relay_svm.clone(),
         bid.transaction.message.static_account_keys(),
         submit_bid_instruction,
     )?;
+    verify_fee_recipient_svm(chain_store, &permission_key)?;
+    verify_permission_key_chain(&store, &permission_key, &bid.chain_id, initiation_time).await?;
+    verify_bid_increment(
+        &store,
+        &(permission_key.clone(), bid.chain_id.clone()),
+        U256::from(bid_amount),
+        chain_store.config.min_bid_increment,
+    )
+    .await?;
 
     verify_signatures_svm(&bid, &store.express_relay_svm.relayer.pubkey())?;
-    simulate_bid_svm(chain_store, &bid).await?;
+    if chain_store.config.preflight {
+        simulate_bid_svm(chain_store, &bid).await?;
+    }
 
     let core_fields = SimulatedBidCoreFields::new(
         U256::from(bid_amount),
         bid.chain_id,
         permission_key,
         initiation_time,
+        bid.good_till,
         auth,
     );
-    let simulated_bid = SimulatedBidSvm {
-        core_fields: core_fields.clone(),
+    Ok(SimulatedBidSvm {
+        core_fields,
         transaction: bid.transaction,
-    };
-    store.add_bid(simulated_bid.clone().into()).await?;
-    Ok(core_fields.id)
+    })
 }
 
+/// Verifies the signatures of the bid transaction before the relayer co-signs it on submission.
+///
+/// The signers of a transaction are the first `num_required_signatures` static accounts, and the
+/// signature at each position belongs to the account at the same position. The relayer has to be
+/// one of these signers and its signature is left empty by the searcher, as it is added by the server
+/// on submission. Every other signer, such as the searcher, has to provide a valid signature.
 fn verify_signatures_svm(bid: &BidSvm, relayer_pubkey: &Pubkey) -> Result<(), RestError> {
-    let message_bytes = bid.transaction.message.serialize();
-    let all_signatures_valid = bid
+    let message = &bid.transaction.message;
+    let num_required_signatures = message.header().num_required_signatures as usize;
+    let signers = message
+        .static_account_keys()
+        .get(..num_required_signatures)
+        .ok_or_else(|| {
+            RestError::BadParameters(
+                "Transaction has fewer accounts than required signatures".to_string(),
+            )
+        })?;
+    if bid.transaction.signatures.len() != num_required_signatures {
+        return Err(RestError::BadParameters(format!(
+            "Transaction should include {} signatures, including an empty one for the relayer",
+            num_required_signatures
+        )));
+    }
+    if !signers.contains(relayer_pubkey) {
+        return Err(RestError::BadParameters(format!(
+            "Relayer {} should be one of the transaction signers",
+            relayer_pubkey
+        )));
+    }
+
+    let message_bytes = message.serialize();
+    let missing_signers: Vec<String> = bid
         .transaction
         .signatures
         .iter()
-        .zip(bid.transaction.message.static_account_keys().iter())
-        .all(|(signature, pubkey)| {
-            signature.verify(pubkey.as_ref(), &message_bytes) || pubkey.eq(relayer_pubkey)
-        });
-
-    match all_signatures_valid {
+        .zip(signers.iter())
+        .filter(|(signature, pubkey)| {
+            !pubkey.eq(&relayer_pubkey) && !signature.verify(pubkey.as_ref(), &message_bytes)
+        })
+        .map(|(_, pubkey)| pubkey.to_string())
+        .collect();
+    match missing_signers.is_empty() {
         true => Ok(()),
-        false => Err(RestError::BadParameters("Invalid signatures".to_string())),
+        false => Err(RestError::BadParameters(format!(
+            "Missing or invalid signatures for signers: {}",
+            missing_signers.join(", ")
+        ))),
     }
 }
 
+/// Returns the static accounts of the transaction which may be written to.
+/// Accounts loaded from address lookup tables are not resolved, so they are not included.
+fn get_writable_accounts_svm(transaction: &VersionedTransaction) -> HashSet<Pubkey> {
+    let message = &transaction.message;
+    message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| message.is_maybe_writable(*index, None))
+        .map(|(_, key)| *key)
+        .collect()
+}
+
+/// Simulates the bid transaction as it would be submitted by the relayer.
+/// The signatures are not verified, as the relayer signature is only added on submission.
 async fn simulate_bid_svm(chain_store: &ChainStoreSvm, bid: &BidSvm) -> Result<(), RestError> {
     let response = chain_store
         .client
-        .simulate_transaction(&bid.transaction)
+        .simulate_transaction_with_config(
+            &bid.transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: false,
+                ..Default::default()
+            },
+        )
         .await;
     let result = response.map_err(|e| {
         tracing::error!("Error while simulating bid: {:?}", e);
-        RestError::TemporarilyUnavailable
+        RestError::TemporarilyUnavailable(UnavailableReason::Chain)
     })?;
     match result.value.err {
         Some(err) => {
+            let logs = result.value.logs.unwrap_or_default();
             tracing::error!(
-                "Error while simulating bid: {:?}, context: {:?}",
+                "Error while simulating bid: {:?}, context: {:?}, logs: {:?}",
                 err,
-                result.context
+                result.context,
+                logs,
             );
+            let reason = match logs.is_empty() {
+                true => err.to_string(),
+                false => format!("{}, logs: {}", err, logs.join("\n")),
+            };
             Err(RestError::SimulationError {
                 result: Default::default(),
-                reason: err.to_string(),
+                reason,
             })
         }
         None => Ok(()),
     }
 }
 
+/// The bids selected for submission in an auction
+pub struct WinnerBids<T> {
+    /// The bids to submit on chain
+    pub winners:     Vec<T>,
+    /// The bids which can not be submitted together with the winners
+    pub conflicting: Vec<T>,
+}
+
+impl<T> Default for WinnerBids<T> {
+    fn default() -> Self {
+        Self {
+            winners:     vec![],
+            conflicting: vec![],
+        }
+    }
+}
+
+/// The transaction submitted for an auction.
+pub struct AuctionSubmission {
+    pub tx_hash: TxHash,
+    /// Address of the relayer which signed the transaction.
+    pub relayer: Vec<u8>,
+}
+
+/// The block which included the transaction of an auction.
+pub struct InclusionBlock {
+    pub number: u64,
+    pub hash:   H256,
+}
+
+/// The results of the bids of a concluded auction.
+pub struct BidResults {
+    /// The statuses in the same order as the bids.
+    pub statuses:        Vec<BidStatus>,
+    /// The block which included the transaction, to detect reorgs which drop it. Only known on EVM chains.
+    pub inclusion_block: Option<InclusionBlock>,
+}
+
 /// The trait for the chain store to be implemented for each chain type
 /// These functions are chain specific and should be implemented for each chain in order to handle auctions
 pub trait ChainStore {
@@ -1035,6 +1968,8 @@ pub trait ChainStore {
     const CHAIN_TYPE: models::ChainType;
     /// The minimum lifetime for an auction. If any bid for auction is older than this, the auction is ready to be submitted.
     const AUCTION_MINIMUM_LIFETIME: Duration;
+    /// The maximum number of bids which can win an auction together.
+    const MAX_WINNER_BIDS: usize;
 
     /// Get the ws client for the chain
     fn get_ws_client(&self) -> impl Future<Output = Result<Self::WsClient>> + Send;
@@ -1044,26 +1979,37 @@ pub trait ChainStore {
     ) -> impl Future<Output = Result<Self::BlockStream<'a>>>;
     /// Convert the bids to the chain specific simulated bid type and panics if the conversion is not possible
     fn convert_bids(bids: Vec<SimulatedBid>) -> Vec<Self::SimulatedBid>;
+    /// Get the minimum number of bids to collect before running an auction on the chain
+    fn get_min_bids(&self) -> MinBids;
+    /// Get the scheduling of the auctions on the chain
+    fn get_auction_schedule(&self) -> AuctionSchedule;
+    /// Get the interval for polling the confirmation of the submitted auctions, if it is decoupled from the blocks
+    fn get_confirmation_poll_interval(&self) -> Option<Duration>;
+    /// Get the maximum allowed drift between the server clock and the chain time
+    fn get_max_clock_drift(&self) -> Option<Duration>;
+    /// Get the timestamp of the latest block on the chain
+    fn get_latest_block_time(&self) -> impl Future<Output = Result<OffsetDateTime>> + Send;
     /// Get the winner bids for the auction. Sorting bids by bid amount and simulating the bids to determine the winner bids.
+    /// The bids which conflict with the winner bids are returned separately, so they can be marked as lost with a conflict reason.
     fn get_winner_bids(
         &self,
         bids: &[Self::SimulatedBid],
         permission_key: Bytes,
         store: Arc<Store>,
-    ) -> impl Future<Output = Result<Vec<Self::SimulatedBid>>>;
+    ) -> impl Future<Output = Result<WinnerBids<Self::SimulatedBid>>>;
     /// Submit the bids for the auction on the chain
     fn submit_bids(
         &self,
         permission_key: Bytes,
         bids: Vec<Self::SimulatedBid>,
         store: Arc<Store>,
-    ) -> impl Future<Output = Result<Vec<u8>>>;
+    ) -> impl Future<Output = Result<AuctionSubmission>>;
     /// Get the bid results for the bids submitted for the auction after the transaction is concluded. Order of the returned BidStatus is as same as the order of the bids
     fn get_bid_results(
         &self,
         bids: Vec<Self::SimulatedBid>,
-        tx_hash: Vec<u8>,
-    ) -> impl Future<Output = Result<Option<Vec<BidStatus>>>>;
+        tx_hash: TxHash,
+    ) -> impl Future<Output = Result<Option<BidResults>>>;
 }
 
 // While we are submitting bids together, increasing this number will have the following effects:
@@ -1072,6 +2018,111 @@ pub trait ChainStore {
 // 3. Gas consumption limit will decrease for the bid
 const TOTAL_BIDS_PER_AUCTION: usize = 3;
 
+/// Sorts the bids from the best to the worst, breaking ties between equal amounts with the given order.
+fn sort_bids_evm(bids: &mut [SimulatedBidEvm], tiebreak: BidTiebreak) {
+    bids.sort_by(|a, b| {
+        let by_amount = b.core_fields.bid_amount.cmp(&a.core_fields.bid_amount);
+        let by_time = a
+            .core_fields
+            .initiation_time
+            .cmp(&b.core_fields.initiation_time);
+        match tiebreak {
+            BidTiebreak::AmountTime => by_amount.then(by_time),
+            BidTiebreak::AmountGasTime => {
+                by_amount.then(a.gas_limit.cmp(&b.gas_limit)).then(by_time)
+            }
+        }
+    });
+}
+
+/// Tag of the SetComputeUnitPrice instruction of the compute budget program.
+const SET_COMPUTE_UNIT_PRICE_TAG: u8 = 3;
+
+/// Returns the compute unit price set in the transaction, in micro-lamports. Zero if not set.
+fn get_compute_unit_price_svm(transaction: &VersionedTransaction) -> u64 {
+    let message = &transaction.message;
+    message
+        .instructions()
+        .iter()
+        .filter(|instruction| {
+            *instruction.program_id(message.static_account_keys()) == compute_budget::id()
+        })
+        .find_map(|instruction| match instruction.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_PRICE_TAG, price)) => {
+                price.try_into().ok().map(u64::from_le_bytes)
+            }
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+fn sort_bids_svm(bids: &mut [SimulatedBidSvm], tiebreak: BidTiebreakSvm) {
+    bids.sort_by_cached_key(|bid| {
+        let compute_unit_price = match tiebreak {
+            BidTiebreakSvm::AmountTime => 0,
+            BidTiebreakSvm::AmountPriorityFeeTime => get_compute_unit_price_svm(&bid.transaction),
+        };
+        (
+            Reverse(bid.core_fields.bid_amount),
+            Reverse(compute_unit_price),
+            bid.core_fields.initiation_time,
+        )
+    });
+}
+
+/// Picks the best bids which fit in a block with the given gas limit, to be simulated together as a bundle.
+fn select_bundle_evm(
+    mut bids: Vec<SimulatedBidEvm>,
+    tiebreak: BidTiebreak,
+    block_gas_limit: U256,
+) -> Vec<SimulatedBidEvm> {
+    sort_bids_evm(&mut bids, tiebreak);
+    let mut bundle_gas = U256::from(EXTRA_GAS_FOR_SUBMISSION);
+    bids.into_iter()
+        .filter(|bid| {
+            if bundle_gas + bid.gas_limit > block_gas_limit {
+                return false;
+            }
+            bundle_gas += bid.gas_limit;
+            true
+        })
+        .take(TOTAL_BIDS_PER_AUCTION)
+        .collect()
+}
+
+/// Compares the bids picked by the live strategy with the ones picked by the shadow strategy.
+/// Only the decisions are recorded, the live selection is always the one submitted.
+fn record_shadow_selection<T: SimulatedBidTrait>(
+    chain_id: &ChainId,
+    permission_key: &Bytes,
+    live_bids: &[T],
+    shadow_bids: &[T],
+) {
+    let live_ids: Vec<BidId> = live_bids
+        .iter()
+        .map(|bid| bid.get_core_fields().id)
+        .collect();
+    let shadow_ids: Vec<BidId> = shadow_bids
+        .iter()
+        .map(|bid| bid.get_core_fields().id)
+        .collect();
+    let diverged = live_ids != shadow_ids;
+    if diverged {
+        tracing::info!(
+            chain_id = chain_id,
+            permission_key = permission_key.to_string(),
+            live_bids = ?live_ids,
+            shadow_bids = ?shadow_ids,
+            "Shadow bid selection diverged from the live selection"
+        );
+    }
+    let labels = [
+        ("chain_id", chain_id.clone()),
+        ("diverged", diverged.to_string()),
+    ];
+    metrics::counter!("auction_shadow_selections_total", &labels).increment(1);
+}
+
 impl ChainStore for &ChainStoreEvm {
     type Block = Block<H256>;
     type BlockStream<'a> = SubscriptionStream<'a, Ws, Block<H256>>;
@@ -1081,6 +2132,7 @@ impl ChainStore for &ChainStoreEvm {
 
     const CHAIN_TYPE: models::ChainType = models::ChainType::Evm;
     const AUCTION_MINIMUM_LIFETIME: Duration = Duration::from_secs(1);
+    const MAX_WINNER_BIDS: usize = TOTAL_BIDS_PER_AUCTION;
 
     async fn get_ws_client(&self) -> Result<Self::WsClient> {
         let ws = Ws::connect(self.config.geth_ws_addr.clone()).await?;
@@ -1101,24 +2153,67 @@ impl ChainStore for &ChainStoreEvm {
             .collect()
     }
 
+    fn get_min_bids(&self) -> MinBids {
+        self.config.min_bids
+    }
+
+    fn get_auction_schedule(&self) -> AuctionSchedule {
+        self.config.auction_schedule
+    }
+
+    fn get_confirmation_poll_interval(&self) -> Option<Duration> {
+        self.config
+            .confirmation_poll_interval_ms
+            .filter(|interval| *interval > 0)
+            .map(Duration::from_millis)
+    }
+
+    fn get_max_clock_drift(&self) -> Option<Duration> {
+        self.config.max_clock_drift.map(Duration::from_secs)
+    }
+
+    async fn get_latest_block_time(&self) -> Result<OffsetDateTime> {
+        let block = self
+            .provider
+            .get_block(BlockNumber::Latest)
+            .await?
+            .ok_or(anyhow!("Latest block not found"))?;
+        Ok(OffsetDateTime::from_unix_timestamp(
+            block.timestamp.as_u64() as i64,
+        )?)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn get_winner_bids(
         &self,
         bids: &[Self::SimulatedBid],
         permission_key: Bytes,
         store: Arc<Store>,
-    ) -> Result<Vec<Self::SimulatedBid>> {
+    ) -> Result<WinnerBids<Self::SimulatedBid>> {
         // TODO How we want to perform simulation, pruning, and determination
         if bids.is_empty() {
-            return Ok(vec![]);
+            return Ok(WinnerBids::default());
         }
 
-        let mut bids = bids.to_owned();
-        bids.sort_by(|a, b| b.core_fields.bid_amount.cmp(&a.core_fields.bid_amount));
-        let bids: Vec<SimulatedBidEvm> = bids.into_iter().take(TOTAL_BIDS_PER_AUCTION).collect();
+        let block_gas_limit = *self.block_gas_limit.read().await;
+        if let Some(shadow_tiebreak) = self.config.shadow_bid_tiebreak {
+            record_shadow_selection(
+                &bids[0].core_fields.chain_id,
+                &permission_key,
+                &select_bundle_evm(bids.to_owned(), self.config.bid_tiebreak, block_gas_limit),
+                &select_bundle_evm(bids.to_owned(), shadow_tiebreak, block_gas_limit),
+            );
+        }
+        let bids = select_bundle_evm(bids.to_owned(), self.config.bid_tiebreak, block_gas_limit);
+        if bids.is_empty() {
+            return Ok(WinnerBids::default());
+        }
 
+        let _simulation_permit = acquire_simulation_permit(self, &bids[0].core_fields.chain_id)
+            .await
+            .map_err(|e| anyhow!("Failed to acquire simulation permit: {:?}", e))?;
         let simulation_result = get_simulation_call(
-            store.relayer.address(),
+            self.relayers.primary().address(),
             self.provider.clone(),
             self.config.clone(),
             permission_key.clone(),
@@ -1129,13 +2224,28 @@ impl ChainStore for &ChainStoreEvm {
         )
         .await?;
 
-        match simulation_result
+        let Some(first_success) = simulation_result
             .iter()
             .position(|status| status.external_success)
+        else {
+            return Ok(WinnerBids::default());
+        };
+
+        // All the bids passed the simulation on their own before being accepted,
+        // so the ones reverting after a successful bid in the bundle conflict with the bids before them.
+        let mut winner_bids = WinnerBids::default();
+        for (bid, status) in bids
+            .into_iter()
+            .zip(simulation_result.iter())
+            .skip(first_success)
         {
-            Some(index) => Ok(bids.into_iter().skip(index).collect()),
-            None => Ok(vec![]),
+            if status.external_success {
+                winner_bids.winners.push(bid);
+            } else {
+                winner_bids.conflicting.push(bid);
+            }
         }
+        Ok(winner_bids)
     }
 
     #[tracing::instrument(skip_all)]
@@ -1143,10 +2253,21 @@ impl ChainStore for &ChainStoreEvm {
         &self,
         permission_key: Bytes,
         bids: Vec<Self::SimulatedBid>,
-        _store: Arc<Store>,
-    ) -> Result<Vec<u8>> {
+        store: Arc<Store>,
+    ) -> Result<AuctionSubmission> {
+        let mut in_flight = HashMap::<Address, usize>::new();
+        for auction in store
+            .get_submitted_auctions(&bids[0].core_fields.chain_id)
+            .await
+        {
+            if let Some(relayer) = auction.relayer {
+                *in_flight.entry(Address::from_slice(&relayer)).or_default() += 1;
+            }
+        }
+        let relayer = self.relayers.select(&in_flight);
+
         let gas_estimate = bids.iter().fold(U256::zero(), |sum, b| sum + b.gas_limit);
-        let tx_hash = self
+        let tx_hash = relayer
             .express_relay_contract
             .multicall(
                 permission_key,
@@ -1156,37 +2277,55 @@ impl ChainStore for &ChainStoreEvm {
             .send()
             .await?
             .tx_hash();
-        Ok(tx_hash.0.to_vec())
+        Ok(AuctionSubmission {
+            tx_hash: TxHash::Evm(tx_hash),
+            relayer: relayer.address().0.to_vec(),
+        })
     }
 
     async fn get_bid_results(
         &self,
         bids: Vec<Self::SimulatedBid>,
-        tx_hash: Vec<u8>,
-    ) -> Result<Option<Vec<BidStatus>>> {
+        tx_hash: TxHash,
+    ) -> Result<Option<BidResults>> {
+        let TxHash::Evm(hash) = tx_hash else {
+            return Err(anyhow!("Invalid evm transaction hash: {:?}", tx_hash));
+        };
         let reciept = self
             .provider
-            .get_transaction_receipt(H256::from_slice(tx_hash.clone().as_slice()))
+            .get_transaction_receipt(hash)
             .await
             .map_err(|e| anyhow!("Failed to get transaction receipt: {:?}", e))?;
         match reciept {
             Some(receipt) => {
                 let decoded_logs = decode_logs_for_receipt(&receipt);
-                Ok(Some(
-                    bids.iter()
-                        .map(|b| {
-                            match decoded_logs.iter().find(|decoded_log| {
-                                Uuid::from_bytes(decoded_log.bid_id) == b.core_fields.id
-                            }) {
-                                Some(decoded_log) => get_bid_status(decoded_log, &receipt),
-                                None => BidStatus::Lost {
-                                    result: Some(tx_hash.clone()),
-                                    index:  None,
-                                },
-                            }
-                        })
-                        .collect(),
-                ))
+                let statuses = bids
+                    .iter()
+                    .map(|b| {
+                        match decoded_logs.iter().find(|decoded_log| {
+                            Uuid::from_bytes(decoded_log.bid_id) == b.core_fields.id
+                        }) {
+                            Some(decoded_log) => get_bid_status(decoded_log, &receipt),
+                            None => BidStatus::Lost {
+                                result: Some(tx_hash),
+                                index:  None,
+                                reason: None,
+                            },
+                        }
+                    })
+                    .collect();
+                let inclusion_block =
+                    receipt
+                        .block_number
+                        .zip(receipt.block_hash)
+                        .map(|(number, hash)| InclusionBlock {
+                            number: number.as_u64(),
+                            hash,
+                        });
+                Ok(Some(BidResults {
+                    statuses,
+                    inclusion_block,
+                }))
             }
             None => Ok(None),
         }
@@ -1202,6 +2341,7 @@ impl ChainStore for &ChainStoreSvm {
 
     const CHAIN_TYPE: models::ChainType = models::ChainType::Svm;
     const AUCTION_MINIMUM_LIFETIME: Duration = Duration::from_millis(400);
+    const MAX_WINNER_BIDS: usize = 1;
 
     async fn get_ws_client(&self) -> Result<Self::WsClient> {
         PubsubClient::new(&self.config.ws_addr).await.map_err(|e| {
@@ -1219,6 +2359,31 @@ impl ChainStore for &ChainStoreSvm {
             .collect()
     }
 
+    fn get_min_bids(&self) -> MinBids {
+        self.config.min_bids
+    }
+
+    fn get_auction_schedule(&self) -> AuctionSchedule {
+        self.config.auction_schedule
+    }
+
+    fn get_confirmation_poll_interval(&self) -> Option<Duration> {
+        self.config
+            .confirmation_poll_interval_ms
+            .filter(|interval| *interval > 0)
+            .map(Duration::from_millis)
+    }
+
+    fn get_max_clock_drift(&self) -> Option<Duration> {
+        self.config.max_clock_drift.map(Duration::from_secs)
+    }
+
+    async fn get_latest_block_time(&self) -> Result<OffsetDateTime> {
+        let slot = self.client.get_slot().await?;
+        let block_time = self.client.get_block_time(slot).await?;
+        Ok(OffsetDateTime::from_unix_timestamp(block_time)?)
+    }
+
     async fn get_block_stream<'a>(client: &'a Self::WsClient) -> Result<Self::BlockStream<'a>> {
         let (block_subscribe, _) = client
             .block_subscribe(
@@ -1238,17 +2403,29 @@ impl ChainStore for &ChainStoreSvm {
     async fn get_winner_bids(
         &self,
         bids: &[Self::SimulatedBid],
-        _permission_key: Bytes,
+        permission_key: Bytes,
         _store: Arc<Store>,
-    ) -> Result<Vec<Self::SimulatedBid>> {
+    ) -> Result<WinnerBids<Self::SimulatedBid>> {
         let mut bids = bids.to_owned();
-        bids.sort_by(|a, b| b.core_fields.bid_amount.cmp(&a.core_fields.bid_amount));
-        for bid in bids.iter() {
+        sort_bids_svm(&mut bids, self.config.bid_tiebreak);
+        if let (Some(shadow_tiebreak), Some(bid)) = (self.config.shadow_bid_tiebreak, bids.first())
+        {
+            let mut shadow_bids = bids.clone();
+            sort_bids_svm(&mut shadow_bids, shadow_tiebreak);
+            record_shadow_selection(
+                &bid.core_fields.chain_id,
+                &permission_key,
+                &bids,
+                &shadow_bids,
+            );
+        }
+        for (index, bid) in bids.iter().enumerate() {
             match simulate_bid_svm(
                 self,
                 &BidSvm {
                     chain_id:    bid.core_fields.chain_id.clone(),
                     transaction: bid.transaction.clone(),
+                    good_till:   bid.core_fields.good_till,
                 },
             )
             .await
@@ -1258,10 +2435,25 @@ impl ChainStore for &ChainStoreSvm {
                     reason: _,
                 }) => {}
                 // Either simulation was successful or we can't simulate at this moment
-                _ => return Ok(vec![bid.clone()]),
+                _ => {
+                    let writable_accounts = get_writable_accounts_svm(&bid.transaction);
+                    let conflicting = bids
+                        .iter()
+                        .skip(index + 1)
+                        .filter(|other| {
+                            !get_writable_accounts_svm(&other.transaction)
+                                .is_disjoint(&writable_accounts)
+                        })
+                        .cloned()
+                        .collect();
+                    return Ok(WinnerBids {
+                        winners: vec![bid.clone()],
+                        conflicting,
+                    });
+                }
             }
         }
-        Ok(vec![])
+        Ok(WinnerBids::default())
     }
 
     #[tracing::instrument(skip_all)]
@@ -1270,7 +2462,7 @@ impl ChainStore for &ChainStoreSvm {
         _permission_key: Bytes,
         bids: Vec<Self::SimulatedBid>,
         store: Arc<Store>,
-    ) -> Result<Vec<u8>> {
+    ) -> Result<AuctionSubmission> {
         let relayer = store.express_relay_svm.relayer.clone();
         let mut bid = bids[0].clone();
         let serialized_message = bid.transaction.message.serialize();
@@ -1284,7 +2476,10 @@ impl ChainStore for &ChainStoreSvm {
         bid.transaction.signatures[relayer_signature_pos] =
             relayer.sign_message(&serialized_message);
         match self.client.send_transaction(&bid.transaction).await {
-            Ok(response) => Ok(response.as_ref().to_vec()),
+            Ok(response) => Ok(AuctionSubmission {
+                tx_hash: TxHash::Svm(response),
+                relayer: relayer.pubkey().to_bytes().to_vec(),
+            }),
             Err(e) => {
                 tracing::error!("Error while submitting bid: {:?}", e);
                 Err(anyhow!(e))
@@ -1295,32 +2490,35 @@ impl ChainStore for &ChainStoreSvm {
     async fn get_bid_results(
         &self,
         bids: Vec<Self::SimulatedBid>,
-        tx_hash: Vec<u8>,
-    ) -> Result<Option<Vec<BidStatus>>> {
+        tx_hash: TxHash,
+    ) -> Result<Option<BidResults>> {
         if bids.len() != 1 {
             return Err(anyhow!("Invalid number of bids: {}", bids.len()));
         }
+        let TxHash::Svm(signature) = tx_hash else {
+            return Err(anyhow!("Invalid svm transaction signature: {:?}", tx_hash));
+        };
 
         let status = self
             .client
-            .get_signature_status_with_commitment(
-                &SignatureSvm::try_from(tx_hash.clone())
-                    .map_err(|e| anyhow!("Invalid svm signature: {:?}", e))?,
-                CommitmentConfig::confirmed(),
-            )
+            .get_signature_status_with_commitment(&signature, CommitmentConfig::confirmed())
             .await?;
 
         match status {
-            Some(res) => Ok(Some(vec![match res {
-                Ok(()) => BidStatus::Won {
-                    index:  0,
-                    result: tx_hash,
-                },
-                Err(_) => BidStatus::Lost {
-                    index:  Some(0),
-                    result: Some(tx_hash),
-                },
-            }])),
+            Some(res) => Ok(Some(BidResults {
+                statuses:        vec![match res {
+                    Ok(()) => BidStatus::Won {
+                        index:  0,
+                        result: tx_hash,
+                    },
+                    Err(_) => BidStatus::Lost {
+                        index:  Some(0),
+                        result: Some(tx_hash),
+                        reason: None,
+                    },
+                }],
+                inclusion_block: None,
+            })),
             None => {
                 // not yet confirmed
                 Ok(None)
@@ -1329,54 +2527,435 @@ impl ChainStore for &ChainStoreSvm {
     }
 }
 
+const REJECTED_BID_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFERRED_BID_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+const AUCTION_LOCK_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+const TRACKER_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Deletes the persisted rejected bids which are older than the retention period.
+pub async fn run_rejected_bid_cleanup_loop(store: Arc<Store>) -> Result<()> {
+    if !store.persist_rejected_bids {
+        return Ok(());
+    }
+    tracing::info!("Starting rejected bid cleanup loop...");
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    let mut cleanup_interval = tokio::time::interval(REJECTED_BID_CLEANUP_INTERVAL);
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            _ = cleanup_interval.tick() => {
+                let retention_start = OffsetDateTime::now_utc() - store.rejected_bid_retention;
+                match store.delete_rejected_bids_before(retention_start).await {
+                    Ok(deleted) => tracing::info!("Deleted {} rejected bids past the retention period", deleted),
+                    Err(e) => tracing::error!("Failed to delete rejected bids: {:?}", e),
+                }
+            }
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    tracing::info!("Shutting down rejected bid cleanup loop...");
+    Ok(())
+}
+
+/// Logs the auction locks which are held for longer than the auction lock timeout. Such a lock is usually held by a stuck task,
+/// and blocks the submission of all the future auctions of its permission key.
+pub async fn run_auction_lock_sweep_loop(store: Arc<Store>) -> Result<()> {
+    tracing::info!("Starting auction lock sweep loop...");
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    let mut sweep_interval = tokio::time::interval(AUCTION_LOCK_SWEEP_INTERVAL);
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            _ = sweep_interval.tick() => {
+                let stuck_locks = store.get_stuck_auction_locks();
+                let mut stuck_locks_per_chain: HashMap<ChainId, usize> = store
+                    .chains
+                    .keys()
+                    .chain(store.chains_svm.keys())
+                    .map(|chain_id| (chain_id.clone(), 0))
+                    .collect();
+                for ((permission_key, chain_id), held_for) in stuck_locks {
+                    tracing::warn!(
+                        "Auction lock of {} on chain {} has been held for {:?}",
+                        permission_key,
+                        chain_id,
+                        held_for
+                    );
+                    *stuck_locks_per_chain.entry(chain_id).or_default() += 1;
+                }
+                for (chain_id, count) in stuck_locks_per_chain {
+                    metrics::gauge!("auction_locks_stuck", &[("chain_id", chain_id)]).set(count as f64);
+                }
+            }
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    tracing::info!("Shutting down auction lock sweep loop...");
+    Ok(())
+}
+
+/// Reports the number of the bids, opportunities and auction locks kept in memory, to catch the entries which
+/// are never removed. Sampled on the tracker interval to keep the extra lock contention low.
+pub async fn run_store_size_metrics_loop(store: Arc<Store>) -> Result<()> {
+    tracing::info!("Starting store size metrics loop...");
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    let mut sample_interval = tokio::time::interval(TRACKER_INTERVAL);
+    let chain_ids: Vec<ChainId> = store
+        .chains
+        .keys()
+        .chain(store.chains_svm.keys())
+        .cloned()
+        .collect();
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            _ = sample_interval.tick() => {
+                let bid_counts = store.get_bid_counts_by_chain().await;
+                let opportunity_counts = store.opportunity_store.count_by_chain().await;
+                for chain_id in chain_ids.iter() {
+                    let label = [("chain_id", chain_id.clone())];
+                    metrics::gauge!("auction_server_active_bids", &label)
+                        .set(bid_counts.get(chain_id).copied().unwrap_or(0) as f64);
+                    metrics::gauge!("auction_server_active_opportunities", &label)
+                        .set(opportunity_counts.get(chain_id).copied().unwrap_or(0) as f64);
+                }
+                metrics::gauge!("auction_server_auction_locks")
+                    .set(store.get_auction_locks_count().await as f64);
+            }
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    tracing::info!("Shutting down store size metrics loop...");
+    Ok(())
+}
+
+/// Retries persisting the bids which were accepted while the database was unavailable.
+pub async fn run_deferred_bid_persistence_loop(store: Arc<Store>) -> Result<()> {
+    if store.deferred_bids.is_none() {
+        return Ok(());
+    }
+    tracing::info!("Starting deferred bid persistence loop...");
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    let mut retry_interval = tokio::time::interval(DEFERRED_BID_RETRY_INTERVAL);
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            _ = retry_interval.tick() => {
+                match store.persist_deferred_bids().await {
+                    Ok(0) => {}
+                    Ok(persisted) => tracing::info!("Persisted {} deferred bids", persisted),
+                    Err(e) => tracing::error!("Failed to persist deferred bids: {:?}", e),
+                }
+            }
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    let remaining = store.get_deferred_bid_ids().await.len();
+    if remaining > 0 {
+        tracing::error!(
+            "Shutting down with {} deferred bids not persisted",
+            remaining
+        );
+    }
+    tracing::info!("Shutting down deferred bid persistence loop...");
+    Ok(())
+}
+
+/// Compares the server clock with the latest block time of the chain and alerts if they drift apart.
+/// The server clock is used for the auction timings, so a drift can cause subtle issues in the auctions.
+async fn check_clock_drift<T: ChainStore>(
+    chain_store: &T,
+    chain_id: &ChainId,
+    max_drift: Duration,
+) {
+    let block_time = match chain_store.get_latest_block_time().await {
+        Ok(block_time) => block_time,
+        Err(e) => {
+            tracing::error!(
+                chain_id = chain_id,
+                "Error while getting latest block time. error: {:?}",
+                e
+            );
+            return;
+        }
+    };
+    let drift = OffsetDateTime::now_utc() - block_time;
+    let labels = [("chain_id", chain_id.clone())];
+    metrics::gauge!("clock_drift_seconds", &labels).set(drift.as_seconds_f64());
+    if drift.unsigned_abs() > max_drift {
+        tracing::warn!(
+            chain_id = chain_id,
+            drift_seconds = drift.as_seconds_f64(),
+            max_drift_seconds = max_drift.as_secs(),
+            "Server clock drifted from the chain time beyond the threshold"
+        );
+        metrics::counter!("clock_drift_exceeded_total", &labels).increment(1);
+    }
+}
+
+/// Returns the first multiple of the period since the unix epoch after the given time.
+pub fn next_grid_time(time: OffsetDateTime, period: Duration) -> OffsetDateTime {
+    let period_nanos = period.as_nanos().max(1) as i128;
+    let time_nanos = time.unix_timestamp_nanos();
+    let delay_nanos = period_nanos - time_nanos.rem_euclid(period_nanos);
+    time + Duration::from_nanos(delay_nanos as u64)
+}
+
+/// Creates an interval ticking on the multiples of the period since the unix epoch.
+fn grid_interval(period: Duration) -> Interval {
+    let now = OffsetDateTime::now_utc();
+    let delay = Duration::try_from(next_grid_time(now, period) - now).unwrap_or_default();
+    let mut interval = tokio::time::interval_at(Instant::now() + delay, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    interval
+}
+
 async fn run_submission_loop<T: ChainStore>(
     store: Arc<Store>,
     chain_store: T,
     chain_id: String,
+    mut shutdown: ShutdownReceiver,
 ) -> Result<()> {
     tracing::info!(chain_id = chain_id, "Starting transaction submitter...");
-    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
 
     let ws_client = chain_store.get_ws_client().await?;
     let mut stream = T::get_block_stream(&ws_client).await?;
 
-    while !SHOULD_EXIT.load(Ordering::Acquire) {
+    let confirmation_poll_interval = chain_store.get_confirmation_poll_interval();
+    let mut confirmation_interval =
+        tokio::time::interval(confirmation_poll_interval.unwrap_or(EXIT_CHECK_INTERVAL));
+
+    let max_clock_drift = chain_store.get_max_clock_drift();
+    let mut clock_drift_interval = tokio::time::interval(CLOCK_DRIFT_CHECK_INTERVAL);
+
+    let auction_schedule = chain_store.get_auction_schedule();
+    // The grid interval is checked to be positive when loading the config
+    let round_interval = match auction_schedule {
+        AuctionSchedule::Grid { interval_ms } => Some(Duration::from_millis(interval_ms)),
+        _ => None,
+    };
+    let mut round_interval_timer = grid_interval(round_interval.unwrap_or(EXIT_CHECK_INTERVAL));
+
+    loop {
         tokio::select! {
             block = stream.next() => {
                 if block.is_none() {
                     return Err(anyhow!("Block stream ended for chain: {}", chain_id));
                 }
 
-                tracing::debug!("New block received for {} at {}: {:?}", chain_id, OffsetDateTime::now_utc(), block);
+                let block_time = OffsetDateTime::now_utc();
+                tracing::debug!("New block received for {} at {}: {:?}", chain_id, block_time, block);
+                store.record_block_time(&chain_id, block_time).await;
+                match auction_schedule {
+                    AuctionSchedule::PerKey => {
+                        store.task_tracker.spawn(submit_auctions(store.clone(), chain_id.clone(), None));
+                    }
+                    AuctionSchedule::Block => {
+                        store.task_tracker.spawn(submit_auctions(store.clone(), chain_id.clone(), Some(block_time)));
+                    }
+                    AuctionSchedule::Grid { .. } => {}
+                }
+                if confirmation_poll_interval.is_none() {
+                    store.task_tracker.spawn(
+                        conclude_submitted_auctions(store.clone(), chain_id.clone())
+                    );
+                }
+            }
+            _ = confirmation_interval.tick(), if confirmation_poll_interval.is_some() => {
                 store.task_tracker.spawn(
-                    submit_auctions(
-                        store.clone(),
-                        chain_id.clone(),
-                    )
+                    conclude_submitted_auctions(store.clone(), chain_id.clone())
                 );
+            }
+            _ = round_interval_timer.tick(), if round_interval.is_some() => {
                 store.task_tracker.spawn(
-                    conclude_submitted_auctions(store.clone(), chain_id.clone())
+                    submit_auctions(store.clone(), chain_id.clone(), Some(OffsetDateTime::now_utc()))
                 );
             }
-            _ = exit_check_interval.tick() => {}
+            _ = clock_drift_interval.tick(), if max_clock_drift.is_some() => {
+                if let Some(max_drift) = max_clock_drift {
+                    check_clock_drift(&chain_store, &chain_id, max_drift).await;
+                }
+            }
+            _ = shutdown.recv() => break,
         }
     }
     tracing::info!("Shutting down transaction submitter...");
     Ok(())
 }
 
-pub async fn run_submission_loop_evm(store: Arc<Store>, chain_id: String) -> Result<()> {
+pub async fn run_submission_loop_evm(
+    store: Arc<Store>,
+    chain_id: String,
+    shutdown: ShutdownReceiver,
+) -> Result<()> {
     let chain_store = store
         .chains
         .get(&chain_id)
         .ok_or(anyhow!("Chain not found: {}", chain_id))?;
-    run_submission_loop(store.clone(), chain_store, chain_id).await
+    run_submission_loop(store.clone(), chain_store, chain_id, shutdown).await
 }
 
-pub async fn run_submission_loop_svm(store: Arc<Store>, chain_id: String) -> Result<()> {
+pub async fn run_submission_loop_svm(
+    store: Arc<Store>,
+    chain_id: String,
+    shutdown: ShutdownReceiver,
+) -> Result<()> {
     let chain_store = store
         .chains_svm
         .get(&chain_id)
         .ok_or(anyhow!("Chain not found: {}", chain_id))?;
-    run_submission_loop(store.clone(), chain_store, chain_id).await
+    run_submission_loop(store.clone(), chain_store, chain_id, shutdown).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction,
+            message::{
+                Message,
+                VersionedMessage,
+            },
+        },
+    };
+
+    fn bid_core_fields(bid_amount: u64, initiation_secs: u64) -> SimulatedBidCoreFields {
+        SimulatedBidCoreFields {
+            id:              Uuid::new_v4(),
+            bid_amount:      BidAmount::from(bid_amount),
+            permission_key:  PermissionKey::default(),
+            chain_id:        "test".to_string(),
+            status:          BidStatus::Pending,
+            initiation_time: OffsetDateTime::UNIX_EPOCH + Duration::from_secs(initiation_secs),
+            profile_id:      None,
+            good_till:       None,
+        }
+    }
+
+    fn bid_evm(bid_amount: u64, gas_limit: u64, initiation_secs: u64) -> SimulatedBidEvm {
+        SimulatedBidEvm {
+            core_fields:     bid_core_fields(bid_amount, initiation_secs),
+            target_contract: Address::zero(),
+            target_calldata: Bytes::default(),
+            gas_limit:       U256::from(gas_limit),
+        }
+    }
+
+    fn bid_svm(
+        bid_amount: u64,
+        compute_unit_price: Option<u64>,
+        initiation_secs: u64,
+    ) -> SimulatedBidSvm {
+        let instructions: Vec<_> = compute_unit_price
+            .map(ComputeBudgetInstruction::set_compute_unit_price)
+            .into_iter()
+            .collect();
+        SimulatedBidSvm {
+            core_fields: bid_core_fields(bid_amount, initiation_secs),
+            transaction: VersionedTransaction {
+                signatures: vec![],
+                message:    VersionedMessage::Legacy(Message::new(&instructions, None)),
+            },
+        }
+    }
+
+    fn bid_ids<T: SimulatedBidTrait>(bids: &[T]) -> Vec<BidId> {
+        bids.iter().map(|bid| bid.get_core_fields().id).collect()
+    }
+
+    #[test]
+    fn test_sort_bids_evm_amount_time() {
+        let early = bid_evm(10, 300, 1);
+        let late = bid_evm(10, 100, 2);
+        let best = bid_evm(20, 500, 3);
+        let mut bids = vec![late.clone(), early.clone(), best.clone()];
+        sort_bids_evm(&mut bids, BidTiebreak::AmountTime);
+        assert_eq!(bid_ids(&bids), bid_ids(&[best, early, late]));
+    }
+
+    #[test]
+    fn test_sort_bids_evm_amount_gas_time() {
+        let early_heavy = bid_evm(10, 300, 1);
+        let late_light = bid_evm(10, 100, 3);
+        let early_light = bid_evm(10, 100, 2);
+        let best = bid_evm(20, 500, 4);
+        let mut bids = vec![
+            early_heavy.clone(),
+            late_light.clone(),
+            best.clone(),
+            early_light.clone(),
+        ];
+        sort_bids_evm(&mut bids, BidTiebreak::AmountGasTime);
+        assert_eq!(
+            bid_ids(&bids),
+            bid_ids(&[best, early_light, late_light, early_heavy])
+        );
+    }
+
+    #[test]
+    fn test_is_bid_expired() {
+        let now = OffsetDateTime::UNIX_EPOCH + Duration::from_secs(100);
+        let expiry_time = Some(OffsetDateTime::UNIX_EPOCH + Duration::from_secs(50));
+
+        // Bids placed up to the expiry time are expired
+        assert!(is_bid_expired(&bid_core_fields(10, 50), expiry_time, now));
+        assert!(!is_bid_expired(&bid_core_fields(10, 51), expiry_time, now));
+        // Bids never expire by age without an expiry time
+        assert!(!is_bid_expired(&bid_core_fields(10, 0), None, now));
+        // Only the pending bids are expired
+        let submitted = SimulatedBidCoreFields {
+            status: BidStatus::Submitted {
+                result: H256::zero(),
+                index:  0,
+            },
+            ..bid_core_fields(10, 0)
+        };
+        assert!(!is_bid_expired(&submitted, expiry_time, now));
+        // Bids past their good till time are expired regardless of their age
+        let past_good_till = SimulatedBidCoreFields {
+            good_till: Some(now),
+            ..bid_core_fields(10, 99)
+        };
+        assert!(is_bid_expired(&past_good_till, expiry_time, now));
+        assert!(is_bid_expired(&past_good_till, None, now));
+        let before_good_till = SimulatedBidCoreFields {
+            good_till: Some(now + Duration::from_secs(1)),
+            ..bid_core_fields(10, 99)
+        };
+        assert!(!is_bid_expired(&before_good_till, None, now));
+    }
+
+    #[test]
+    fn test_sort_bids_svm_amount_time() {
+        let early = bid_svm(10, None, 1);
+        let late_high_fee = bid_svm(10, Some(1_000), 2);
+        let best = bid_svm(20, None, 3);
+        let mut bids = vec![late_high_fee.clone(), early.clone(), best.clone()];
+        sort_bids_svm(&mut bids, BidTiebreakSvm::AmountTime);
+        assert_eq!(bid_ids(&bids), bid_ids(&[best, early, late_high_fee]));
+    }
+
+    #[test]
+    fn test_sort_bids_svm_amount_priority_fee_time() {
+        let early_no_fee = bid_svm(10, None, 1);
+        let late_high_fee = bid_svm(10, Some(1_000), 4);
+        let late_low_fee = bid_svm(10, Some(100), 3);
+        let early_low_fee = bid_svm(10, Some(100), 2);
+        let best = bid_svm(20, None, 5);
+        let mut bids = vec![
+            early_no_fee.clone(),
+            late_low_fee.clone(),
+            best.clone(),
+            early_low_fee.clone(),
+            late_high_fee.clone(),
+        ];
+        sort_bids_svm(&mut bids, BidTiebreakSvm::AmountPriorityFeeTime);
+        assert_eq!(
+            bid_ids(&bids),
+            bid_ids(&[
+                best,
+                late_high_fee,
+                early_low_fee,
+                late_low_fee,
+                early_no_fee
+            ])
+        );
+    }
 }
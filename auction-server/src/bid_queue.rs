@@ -0,0 +1,160 @@
+use {
+    crate::{
+        api::{
+            Auth,
+            RestError,
+            UnavailableReason,
+        },
+        models::ProfileTier,
+        server::ShutdownReceiver,
+        state::Store,
+    },
+    anyhow::Result,
+    axum_prometheus::metrics,
+    futures::future::join_all,
+    std::{
+        collections::VecDeque,
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        time::Instant,
+    },
+    tokio::sync::{
+        oneshot,
+        Mutex,
+        Notify,
+    },
+};
+
+/// Lanes of the bid queue, in the order they are served.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BidLane {
+    Premium,
+    Standard,
+    Anonymous,
+}
+
+impl BidLane {
+    const ALL: [BidLane; 3] = [BidLane::Premium, BidLane::Standard, BidLane::Anonymous];
+
+    pub fn from_auth(auth: &Auth) -> Self {
+        match auth {
+            Auth::Admin => BidLane::Premium,
+            Auth::Authorized(_, profile) => match profile.tier {
+                ProfileTier::Premium => BidLane::Premium,
+                ProfileTier::Standard => BidLane::Standard,
+            },
+            Auth::Unauthorized => BidLane::Anonymous,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            BidLane::Premium => "premium",
+            BidLane::Standard => "standard",
+            BidLane::Anonymous => "anonymous",
+        }
+    }
+}
+
+type BidJob = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct QueuedBidJob {
+    job:          BidJob,
+    enqueue_time: Instant,
+}
+
+/// Queues the incoming bids by the tier of their searcher and processes them with a bounded pool of workers.
+/// Workers always take the oldest bid of the highest priority lane, so premium bids skip the backlog of the
+/// lower lanes during bursts.
+pub struct BidQueue {
+    lanes:    Mutex<[VecDeque<QueuedBidJob>; 3]>,
+    notify:   Notify,
+    capacity: usize,
+    workers:  usize,
+}
+
+impl BidQueue {
+    pub fn new(capacity: usize, workers: usize) -> Self {
+        Self {
+            lanes: Default::default(),
+            notify: Notify::new(),
+            capacity,
+            workers,
+        }
+    }
+
+    /// Queues the processing of a bid on the lane and waits for its result.
+    /// The bid is rejected right away if the lane is full.
+    pub async fn process<T, F>(&self, lane: BidLane, f: F) -> Result<T, RestError>
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, RestError>> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut lanes = self.lanes.lock().await;
+            let queue = &mut lanes[lane as usize];
+            if queue.len() >= self.capacity {
+                tracing::warn!("Bid queue lane {} is full", lane.as_str());
+                metrics::counter!("bid_queue_rejected_total", "lane" => lane.as_str()).increment(1);
+                return Err(RestError::TemporarilyUnavailable(
+                    UnavailableReason::Overloaded,
+                ));
+            }
+            queue.push_back(QueuedBidJob {
+                job:          Box::pin(async move {
+                    // The receiver is dropped if the request was cancelled
+                    let _ = sender.send(f.await);
+                }),
+                enqueue_time: Instant::now(),
+            });
+            metrics::gauge!("bid_queue_depth", "lane" => lane.as_str()).set(queue.len() as f64);
+        }
+        self.notify.notify_one();
+        receiver.await.map_err(|_| {
+            tracing::error!("Bid queue job was dropped before completion");
+            RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+        })?
+    }
+
+    async fn pop(&self) -> Option<QueuedBidJob> {
+        let mut lanes = self.lanes.lock().await;
+        for lane in BidLane::ALL {
+            let queue = &mut lanes[lane as usize];
+            if let Some(job) = queue.pop_front() {
+                metrics::gauge!("bid_queue_depth", "lane" => lane.as_str()).set(queue.len() as f64);
+                metrics::histogram!("bid_queue_wait_seconds", "lane" => lane.as_str())
+                    .record(job.enqueue_time.elapsed().as_secs_f64());
+                return Some(job);
+            }
+        }
+        None
+    }
+}
+
+async fn run_bid_queue_worker(store: &Store) {
+    loop {
+        match store.bid_queue.pop().await {
+            // Spawning the job lets it finish even if the worker is stopped during the shutdown
+            Some(queued) => {
+                let _ = store.task_tracker.spawn(queued.job).await;
+            }
+            None => store.bid_queue.notify.notified().await,
+        }
+    }
+}
+
+pub async fn run_bid_queue_workers(
+    store: Arc<Store>,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
+    tracing::info!("Starting {} bid queue workers...", store.bid_queue.workers);
+    let workers = join_all((0..store.bid_queue.workers).map(|_| run_bid_queue_worker(&store)));
+    tokio::select! {
+        _ = workers => {}
+        _ = shutdown.recv() => {}
+    }
+    tracing::info!("Shutting down bid queue workers...");
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use {
-    anyhow::Result,
+    anyhow::{
+        anyhow,
+        Result,
+    },
     clap::{
         crate_authors,
         crate_description,
@@ -8,19 +11,38 @@ use {
         Args,
         Parser,
     },
-    ethers::abi::Address,
+    ethers::{
+        abi::Address,
+        types::Bytes,
+        utils::{
+            hex,
+            keccak256,
+        },
+    },
     serde_with::{
         serde_as,
         DisplayFromStr,
     },
     solana_sdk::pubkey::Pubkey,
     std::{
-        collections::HashMap,
+        collections::{
+            BTreeMap,
+            HashMap,
+        },
         fs,
     },
+    utoipa::ToSchema,
 };
 
+mod auction;
+mod bid_queue;
+mod database;
+mod opportunity_publisher;
+mod retry;
 mod server;
+mod telemetry;
+mod usage;
+mod ws;
 
 // `Options` is a structup definition to provide clean command-line args for Hermes.
 #[derive(Parser, Debug)]
@@ -53,6 +75,38 @@ pub struct RunOptions {
     #[command(flatten)]
     pub server: server::Options,
 
+    /// Database Options
+    #[command(flatten)]
+    pub database: database::Options,
+
+    /// WebSocket Options
+    #[command(flatten)]
+    pub ws: ws::Options,
+
+    /// Auction Options
+    #[command(flatten)]
+    pub auction: auction::Options,
+
+    /// Opportunity Publisher Options
+    #[command(flatten)]
+    pub opportunity_publisher: opportunity_publisher::Options,
+
+    /// Usage Options
+    #[command(flatten)]
+    pub usage: usage::Options,
+
+    /// Bid Queue Options
+    #[command(flatten)]
+    pub bid_queue: bid_queue::Options,
+
+    /// Telemetry Options
+    #[command(flatten)]
+    pub telemetry: telemetry::Options,
+
+    /// Retry Options
+    #[command(flatten)]
+    pub retry: retry::Options,
+
     #[command(flatten)]
     pub config: ConfigOptions,
 
@@ -85,9 +139,63 @@ pub struct ConfigOptions {
 
 pub type ChainId = String;
 
+/// Action taken on a bid for a permission key which was only seen on other chains before.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PermissionKeyChainCheck {
+    /// Do not check the chains of the permission key.
+    Disabled,
+    /// Log the bid and accept it.
+    #[default]
+    Log,
+    /// Reject the bid.
+    Reject,
+}
+
+/// Action taken on a bid which arrives while the auction of its permission key is being submitted.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LateBidPolicy {
+    /// Accept the bid for the next auction of the permission key.
+    #[default]
+    Queue,
+    /// Reject the bid, so the searcher can bid again once the auction is submitted.
+    Reject,
+}
+
+impl LateBidPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LateBidPolicy::Queue => "queue",
+            LateBidPolicy::Reject => "reject",
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ConfigMap {
-    pub chains: HashMap<ChainId, Config>,
+    pub chains:         HashMap<ChainId, Config>,
+    /// Overrides of the retry options for the background tasks.
+    #[serde(default)]
+    pub retry_policies: RetryPoliciesConfig,
+}
+
+/// Retry policy overrides for each kind of background task.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct RetryPoliciesConfig {
+    #[serde(default)]
+    pub submission:   RetryPolicyConfig,
+    #[serde(default)]
+    pub tracker:      RetryPolicyConfig,
+    #[serde(default)]
+    pub verification: RetryPolicyConfig,
+}
+
+/// Overrides of the retry options for a kind of background task. Unset values fall back to the retry options.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct RetryPolicyConfig {
+    pub initial_delay_ms: Option<u64>,
+    pub max_delay_ms:     Option<u64>,
+    pub multiplier:       Option<f64>,
+    pub max_attempts:     Option<u32>,
 }
 
 impl ConfigMap {
@@ -96,8 +204,31 @@ impl ConfigMap {
         // TODO: the default serde deserialization doesn't enforce unique keys
         let yaml_content = fs::read_to_string(path)?;
         let config: ConfigMap = serde_yaml::from_str(&yaml_content)?;
+        for (chain_id, chain_config) in config.chains.iter() {
+            chain_config.validate(chain_id)?;
+        }
         Ok(config)
     }
+
+    /// Returns a hash identifying the config. Secrets are removed before hashing, so the
+    /// fingerprint can be shared safely to compare configs across instances.
+    pub fn fingerprint(&self) -> Result<String> {
+        // BTreeMap keeps the chain order stable between calls
+        let sanitized: BTreeMap<&ChainId, Config> = self
+            .chains
+            .iter()
+            .map(|(chain_id, config)| (chain_id, config.sanitized()))
+            .collect();
+        let serialized = serde_json::to_vec(&sanitized)?;
+        Ok(hex::encode(keccak256(serialized)))
+    }
+}
+
+/// Strips credentials, paths and query params from a url, as providers usually put api keys there.
+fn sanitize_url(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .map(|url| url.origin().ascii_serialization())
+        .unwrap_or_default()
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -107,6 +238,74 @@ pub enum Config {
     Svm(ConfigSvm),
 }
 
+impl Config {
+    /// Rejects the values which would make the chain loops fail or panic once the server is running.
+    fn validate(&self, chain_id: &ChainId) -> Result<()> {
+        let auction_schedule = match self {
+            Config::Evm(config) => {
+                if config.block_gas_limit_refresh_interval == 0 {
+                    return Err(anyhow!(
+                        "Block gas limit refresh interval must be positive for chain: {}",
+                        chain_id
+                    ));
+                }
+                if let FeeStrategy::Eip1559 {
+                    priority_fee_percentile,
+                } = config.fee_strategy
+                {
+                    // NaN fails the range check as well
+                    if !(0.0..=100.0).contains(&priority_fee_percentile) {
+                        return Err(anyhow!(
+                            "Priority fee percentile must be between 0 and 100 for chain: {}",
+                            chain_id
+                        ));
+                    }
+                }
+                config.auction_schedule
+            }
+            Config::Svm(config) => config.auction_schedule,
+        };
+        if auction_schedule == (AuctionSchedule::Grid { interval_ms: 0 }) {
+            return Err(anyhow!(
+                "Auction grid interval must be positive for chain: {}",
+                chain_id
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn max_concurrent_submissions(&self) -> Option<usize> {
+        match self {
+            Config::Evm(config) => config.max_concurrent_submissions,
+            Config::Svm(config) => config.max_concurrent_submissions,
+        }
+    }
+
+    pub fn max_submitted_auctions(&self) -> Option<usize> {
+        match self {
+            Config::Evm(config) => config.max_submitted_auctions,
+            Config::Svm(config) => config.max_submitted_auctions,
+        }
+    }
+
+    fn sanitized(&self) -> Config {
+        match self {
+            Config::Evm(config) => Config::Evm(ConfigEvm {
+                geth_rpc_addr: sanitize_url(&config.geth_rpc_addr),
+                geth_ws_addr: sanitize_url(&config.geth_ws_addr),
+                relayer_private_key: None,
+                extra_relayer_private_keys: vec![],
+                ..config.clone()
+            }),
+            Config::Svm(config) => Config::Svm(ConfigSvm {
+                rpc_addr: sanitize_url(&config.rpc_addr),
+                ws_addr: sanitize_url(&config.ws_addr),
+                ..config.clone()
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ConfigEvm {
     /// URL of a Geth RPC endpoint to use for interacting with the blockchain.
@@ -130,9 +329,285 @@ pub struct ConfigEvm {
     /// Subwallets available for relaying bids. Only used in the subwallet sync command.
     pub subwallets: Option<Vec<Address>>,
 
-    /// Use the legacy transaction format (for networks without EIP 1559)
+    /// Use the legacy transaction format (for networks without EIP 1559).
+    /// Deprecated in favor of the legacy fee strategy, which it overrides when set.
     #[serde(default)]
     pub legacy_tx: bool,
+
+    /// How the fees of the submitted transactions are set.
+    #[serde(default)]
+    pub fee_strategy: FeeStrategy,
+
+    /// Maximum fee per gas of the submitted transactions in wei. The estimated fees are capped to it if set.
+    #[serde(default)]
+    pub max_fee_per_gas: Option<u64>,
+
+    /// Timeout for bid simulation calls in seconds.
+    #[serde(default = "default_simulation_timeout")]
+    pub simulation_timeout: u64,
+
+    /// Maximum number of bid and bundle simulations running concurrently. Unlimited if not set.
+    #[serde(default)]
+    pub max_concurrent_simulations: Option<usize>,
+
+    /// Maximum time to wait for a free simulation slot in seconds, when the simulations are limited.
+    #[serde(default = "default_simulation_queue_timeout")]
+    pub simulation_queue_timeout: u64,
+
+    /// Interval for refreshing the block gas limit from the latest block in seconds.
+    #[serde(default = "default_block_gas_limit_refresh_interval")]
+    pub block_gas_limit_refresh_interval: u64,
+
+    /// A 20-byte (40 char) hex encoded Ethereum private key which is used for relaying the bids on this chain.
+    /// Falls back to the subwallet private key of the server if not set.
+    #[serde(default)]
+    pub relayer_private_key: Option<String>,
+
+    /// Private keys of additional relayers for this chain, which should be registered as subwallets of the
+    /// express relay contract. Each auction is submitted by the relayer with the fewest auctions in flight.
+    #[serde(default)]
+    pub extra_relayer_private_keys: Vec<String>,
+
+    /// Target contracts which EVM bids are allowed to call. All contracts are allowed if empty.
+    #[serde(default)]
+    pub target_contract_allowlist: Vec<Address>,
+
+    /// Maximum number of auctions submitted concurrently. Unlimited if not set.
+    /// When limited, the auctions with the most valuable bids are submitted first.
+    #[serde(default)]
+    pub max_concurrent_submissions: Option<usize>,
+
+    /// Maximum number of submitted but not concluded auctions tracked for this chain.
+    /// Falls back to the max submitted auctions option of the server if not set.
+    #[serde(default)]
+    pub max_submitted_auctions: Option<usize>,
+
+    /// Order used to break ties between bids with the same amount.
+    #[serde(default)]
+    pub bid_tiebreak: BidTiebreak,
+
+    /// Tiebreak order evaluated in shadow alongside the live one. The bids it would pick are only
+    /// logged and metered when they differ from the live selection, they are never submitted.
+    #[serde(default)]
+    pub shadow_bid_tiebreak: Option<BidTiebreak>,
+
+    /// Minimum number of bids to collect before running an auction.
+    #[serde(default)]
+    pub min_bids: MinBids,
+
+    /// Scheduling of the auctions, either per permission key or in rounds for all the keys.
+    #[serde(default)]
+    pub auction_schedule: AuctionSchedule,
+
+    /// Interval for polling the confirmation of the submitted auctions in milliseconds.
+    /// The submitted auctions are checked on every new block if not set.
+    #[serde(default)]
+    pub confirmation_poll_interval_ms: Option<u64>,
+
+    /// Minimum amount a new bid should add to the current best bid of the permission key.
+    #[serde(default)]
+    pub min_bid_increment: Option<MinBidIncrement>,
+
+    /// Accept and verify opportunities on this chain. Bids are accepted either way.
+    #[serde(default = "default_opportunities_enabled")]
+    pub opportunities_enabled: bool,
+
+    /// Maximum allowed drift between the server clock and the latest block timestamp in seconds.
+    /// Should be larger than the block time of the chain. The drift is not checked if not set.
+    #[serde(default)]
+    pub max_clock_drift: Option<u64>,
+
+    /// Function selectors which opportunities are allowed to call on each target contract.
+    /// Calls to contracts without an allowlist are not restricted.
+    #[serde(default)]
+    pub opportunity_selector_allowlist: HashMap<Address, Vec<Bytes>>,
+
+    /// Fee recipient which bids on this chain have to pay the protocol fee to.
+    /// The fee recipient of a bid is the address in the first 20 bytes of its permission key.
+    /// Bids to any fee recipient are accepted if not set.
+    #[serde(default)]
+    pub fee_recipient: Option<Address>,
+
+    /// Accept opportunities which both sell and buy WETH, to wrap or unwrap the native token.
+    /// Opportunities with any other token in both their sell and buy tokens are rejected.
+    #[serde(default)]
+    pub allow_weth_token_overlap: bool,
+
+    /// Maximum number of sell tokens and of buy tokens of an opportunity, to bound its size in the database
+    /// and in the adapter calldata.
+    #[serde(default = "default_max_opportunity_tokens")]
+    pub max_opportunity_tokens: usize,
+
+    /// Maximum age of an opportunity in seconds, after which it is removed as expired.
+    /// Opportunities are only removed when they fail the verification if not set.
+    #[serde(default)]
+    pub opportunity_max_age: Option<u64>,
+
+    /// Number of the latest blocks in which the concluded auctions are checked for reorgs which dropped their
+    /// transaction.
+    #[serde(default = "default_reorg_check_depth")]
+    pub reorg_check_depth: u64,
+}
+
+fn default_opportunities_enabled() -> bool {
+    true
+}
+
+fn default_reorg_check_depth() -> u64 {
+    64
+}
+
+fn default_max_opportunity_tokens() -> usize {
+    10
+}
+
+/// Minimum increment of a new bid over the current best bid, to prevent negligible overbids.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MinBidIncrement {
+    /// An absolute amount in the smallest unit of the chain currency.
+    Absolute(u128),
+    /// A ratio of the current best bid in basis points, where 100 basis points is 1%.
+    BasisPoints(u32),
+}
+
+/// Policy for waiting for a minimum number of bids before running an auction.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct MinBids {
+    /// Minimum number of bids for a permission key to run the auction.
+    pub count:    usize,
+    /// Maximum time to wait for the minimum number of bids in seconds, counted from the oldest bid.
+    pub timeout:  u64,
+    /// What to do with the bids if the minimum is not met before the timeout.
+    #[serde(default)]
+    pub fallback: MinBidsFallback,
+}
+
+impl Default for MinBids {
+    fn default() -> Self {
+        Self {
+            count:    1,
+            timeout:  0,
+            fallback: MinBidsFallback::default(),
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MinBidsFallback {
+    /// Run the auction with the collected bids.
+    #[default]
+    Submit,
+    /// Conclude the auction off-chain and mark all the bids as lost.
+    ConcludeOffchain,
+}
+
+/// Scheduling of the auctions of a chain.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema,
+)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum AuctionSchedule {
+    /// Each permission key is auctioned on its own once its oldest bid reaches the minimum auction lifetime.
+    #[default]
+    PerKey,
+    /// The bids of all the permission keys are auctioned together in a round on every new block.
+    Block,
+    /// The bids of all the permission keys are auctioned together in rounds on a fixed time grid.
+    /// The rounds are aligned to the unix epoch, so they happen at the same times across restarts.
+    Grid { interval_ms: u64 },
+}
+
+/// Tiebreak order applied after the bid amount when ranking bids of an auction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BidTiebreak {
+    /// Earlier bids win ties.
+    #[default]
+    AmountTime,
+    /// Bids with a lower gas limit win ties, then earlier bids.
+    AmountGasTime,
+}
+
+impl BidTiebreak {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BidTiebreak::AmountTime => "amount_time",
+            BidTiebreak::AmountGasTime => "amount_gas_time",
+        }
+    }
+}
+
+/// Tiebreak order applied after the bid amount when ranking the bids of an SVM auction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BidTiebreakSvm {
+    /// Earlier bids win ties.
+    #[default]
+    AmountTime,
+    /// Bids with a higher compute unit price win ties, then earlier bids.
+    AmountPriorityFeeTime,
+}
+
+impl BidTiebreakSvm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BidTiebreakSvm::AmountTime => "amount_time",
+            BidTiebreakSvm::AmountPriorityFeeTime => "amount_priority_fee_time",
+        }
+    }
+}
+
+/// Fee strategy of the transactions submitted on an EVM chain.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FeeStrategy {
+    /// Legacy transactions paying the gas price of the provider.
+    Legacy,
+    /// EIP 1559 transactions whose priority fee is estimated from the given percentile of the rewards paid
+    /// in the recent blocks, as returned by `eth_feeHistory`.
+    Eip1559 {
+        #[serde(default = "default_priority_fee_percentile")]
+        priority_fee_percentile: f64,
+    },
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        FeeStrategy::Eip1559 {
+            priority_fee_percentile: default_priority_fee_percentile(),
+        }
+    }
+}
+
+impl ConfigEvm {
+    /// Returns the fee strategy of the chain, honoring the deprecated legacy_tx flag.
+    pub fn get_fee_strategy(&self) -> FeeStrategy {
+        if self.legacy_tx {
+            FeeStrategy::Legacy
+        } else {
+            self.fee_strategy
+        }
+    }
+}
+
+fn default_priority_fee_percentile() -> f64 {
+    5.0
+}
+
+fn default_simulation_timeout() -> u64 {
+    5
+}
+
+fn default_simulation_queue_timeout() -> u64 {
+    5
+}
+
+fn default_block_gas_limit_refresh_interval() -> u64 {
+    60
 }
 
 #[serde_as]
@@ -140,9 +615,52 @@ pub struct ConfigEvm {
 pub struct ConfigSvm {
     /// Id of the express relay program.
     #[serde_as(as = "DisplayFromStr")]
-    pub express_relay_program_id: Pubkey,
+    pub express_relay_program_id:      Pubkey,
     /// RPC endpoint to use for interacting with the blockchain.
-    pub rpc_addr:                 String,
+    pub rpc_addr:                      String,
     /// WS endpoint to use for interacting with the blockchain.
-    pub ws_addr:                  String,
+    pub ws_addr:                       String,
+    /// Maximum number of auctions submitted concurrently. Unlimited if not set.
+    #[serde(default)]
+    pub max_concurrent_submissions:    Option<usize>,
+    /// Maximum number of submitted but not concluded auctions tracked for this chain.
+    /// Falls back to the max submitted auctions option of the server if not set.
+    #[serde(default)]
+    pub max_submitted_auctions:        Option<usize>,
+    /// Simulate the bid transactions on submission and reject the failing ones.
+    /// Disabling it reduces the RPC load and the bid submission latency.
+    #[serde(default = "default_preflight")]
+    pub preflight:                     bool,
+    /// Minimum number of bids to collect before running an auction.
+    #[serde(default)]
+    pub min_bids:                      MinBids,
+    /// Scheduling of the auctions, either per permission key or in rounds for all the keys.
+    #[serde(default)]
+    pub auction_schedule:              AuctionSchedule,
+    /// Interval for polling the confirmation of the submitted auctions in milliseconds.
+    /// The submitted auctions are checked on every new block if not set.
+    #[serde(default)]
+    pub confirmation_poll_interval_ms: Option<u64>,
+    /// Minimum amount a new bid should add to the current best bid of the permission key.
+    #[serde(default)]
+    pub min_bid_increment:             Option<MinBidIncrement>,
+    /// Maximum allowed drift between the server clock and the latest slot time in seconds.
+    /// The drift is not checked if not set.
+    #[serde(default)]
+    pub max_clock_drift:               Option<u64>,
+    /// Order used to break ties between bids with the same amount.
+    #[serde(default)]
+    pub bid_tiebreak:                  BidTiebreakSvm,
+    /// Tiebreak order evaluated in shadow alongside the live one, only to log and meter the divergences.
+    #[serde(default)]
+    pub shadow_bid_tiebreak:           Option<BidTiebreakSvm>,
+    /// Router account which bids on this chain have to pay the fee to.
+    /// Bids to any router are accepted if not set.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub fee_recipient:                 Option<Pubkey>,
+}
+
+fn default_preflight() -> bool {
+    true
 }
@@ -0,0 +1,86 @@
+use {
+    super::{
+        LateBidPolicy,
+        PermissionKeyChainCheck,
+    },
+    clap::Args,
+};
+
+const DEFAULT_MAX_SUBMITTED_AUCTIONS: &str = "100";
+const DEFAULT_AUCTION_LOCK_TIMEOUT: &str = "30";
+const DEFAULT_TRACKER_STALL_TIMEOUT: &str = "600";
+const DEFAULT_BID_STATUS_REORDER_RETRIES: &str = "3";
+const DEFAULT_BID_STATUS_REORDER_DELAY_MS: &str = "500";
+const DEFAULT_REJECTED_BID_RETENTION: &str = "7";
+const DEFAULT_MAX_DEFERRED_BIDS: &str = "10000";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Auction Options")]
+#[group(id = "Auction")]
+pub struct Options {
+    /// Maximum number of submitted but not concluded auctions tracked per chain, unless the chain
+    /// config sets its own limit. No new auction is submitted on a chain while its limit is reached.
+    #[arg(long = "max-submitted-auctions")]
+    #[arg(default_value = DEFAULT_MAX_SUBMITTED_AUCTIONS)]
+    #[arg(env = "MAX_SUBMITTED_AUCTIONS")]
+    pub max_submitted_auctions:      usize,
+    /// Time in seconds after which the submissions of a chain are paused if none of its submitted auctions was
+    /// concluded, although some were submitted before. Submissions resume once an auction is concluded.
+    /// Set to 0 to never pause the submissions.
+    #[arg(long = "tracker-stall-timeout")]
+    #[arg(default_value = DEFAULT_TRACKER_STALL_TIMEOUT)]
+    #[arg(env = "TRACKER_STALL_TIMEOUT")]
+    pub tracker_stall_timeout:       u64,
+    /// Time in seconds after which an auction lock is considered stuck. Submissions give up waiting for
+    /// the lock after this time, and the locks held for longer are reported.
+    #[arg(long = "auction-lock-timeout")]
+    #[arg(default_value = DEFAULT_AUCTION_LOCK_TIMEOUT)]
+    #[arg(env = "AUCTION_LOCK_TIMEOUT")]
+    pub auction_lock_timeout:        u64,
+    /// Action taken on a bid for a permission key which was only seen on other chains before.
+    #[arg(long = "permission-key-chain-check")]
+    #[arg(value_enum, default_value_t = PermissionKeyChainCheck::default())]
+    #[arg(env = "PERMISSION_KEY_CHAIN_CHECK")]
+    pub permission_key_chain_check:  PermissionKeyChainCheck,
+    /// Action taken on a bid which arrives while the auction of its permission key is being submitted,
+    /// i.e. while the auction lock of the key is held. Queued bids are auctioned in the next auction of the key.
+    #[arg(long = "late-bid-policy")]
+    #[arg(value_enum, default_value_t = LateBidPolicy::default())]
+    #[arg(env = "LATE_BID_POLICY")]
+    pub late_bid_policy:             LateBidPolicy,
+    /// Number of times the conclusion of a bid is retried while its submission is not persisted yet.
+    #[arg(long = "bid-status-reorder-retries")]
+    #[arg(default_value = DEFAULT_BID_STATUS_REORDER_RETRIES)]
+    #[arg(env = "BID_STATUS_REORDER_RETRIES")]
+    pub bid_status_reorder_retries:  usize,
+    /// Delay in milliseconds between the retries of the conclusion of a bid.
+    #[arg(long = "bid-status-reorder-delay-ms")]
+    #[arg(default_value = DEFAULT_BID_STATUS_REORDER_DELAY_MS)]
+    #[arg(env = "BID_STATUS_REORDER_DELAY_MS")]
+    pub bid_status_reorder_delay_ms: u64,
+    /// Persist the rejected bids along with the rejection reason, to analyze why bids are failing.
+    #[arg(long = "persist-rejected-bids")]
+    #[arg(env = "PERSIST_REJECTED_BIDS")]
+    pub persist_rejected_bids:       bool,
+    /// Number of days the persisted rejected bids are kept for.
+    #[arg(long = "rejected-bid-retention")]
+    #[arg(default_value = DEFAULT_REJECTED_BID_RETENTION)]
+    #[arg(env = "REJECTED_BID_RETENTION")]
+    pub rejected_bid_retention:      u64,
+    /// Time in seconds after which a pending bid which was never submitted to the chain is expired.
+    /// Bids stay pending until their auction is submitted if not set.
+    #[arg(long = "bid-expiry")]
+    #[arg(env = "BID_EXPIRY")]
+    pub bid_expiry:                  Option<u64>,
+    /// Accept the bids when they can not be written to the database, and retry persisting them in the background.
+    /// The deferred bids are only auctioned once persisted. They are lost if the server stops before that,
+    /// so the searchers may never learn the outcome of these bids.
+    #[arg(long = "defer-bid-persistence")]
+    #[arg(env = "DEFER_BID_PERSISTENCE")]
+    pub defer_bid_persistence:       bool,
+    /// Maximum number of deferred bids kept in memory. New bids are rejected when the limit is reached.
+    #[arg(long = "max-deferred-bids")]
+    #[arg(default_value = DEFAULT_MAX_DEFERRED_BIDS)]
+    #[arg(env = "MAX_DEFERRED_BIDS")]
+    pub max_deferred_bids:           usize,
+}
@@ -0,0 +1,24 @@
+use {
+    clap::Args,
+    std::num::NonZeroUsize,
+};
+
+const DEFAULT_WORKERS: &str = "32";
+const DEFAULT_LANE_CAPACITY: &str = "1000";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Bid Queue Options")]
+#[group(id = "BidQueue")]
+pub struct Options {
+    /// Number of bids processed concurrently. Bids of premium profiles are picked first, then the bids of
+    /// the other profiles and finally the anonymous bids.
+    #[arg(long = "bid-queue-workers")]
+    #[arg(default_value = DEFAULT_WORKERS)]
+    #[arg(env = "BID_QUEUE_WORKERS")]
+    pub workers:       NonZeroUsize,
+    /// Maximum number of bids waiting in each lane of the queue. Bids are rejected once their lane is full.
+    #[arg(long = "bid-queue-lane-capacity")]
+    #[arg(default_value = DEFAULT_LANE_CAPACITY)]
+    #[arg(env = "BID_QUEUE_LANE_CAPACITY")]
+    pub lane_capacity: usize,
+}
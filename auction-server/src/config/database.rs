@@ -0,0 +1,32 @@
+use clap::Args;
+
+const DEFAULT_MAX_CONNECTIONS: &str = "10";
+const DEFAULT_MIN_CONNECTIONS: &str = "0";
+const DEFAULT_ACQUIRE_TIMEOUT: &str = "10";
+const DEFAULT_IDLE_TIMEOUT: &str = "600";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Database Options")]
+#[group(id = "Database")]
+pub struct Options {
+    /// Maximum number of connections of the database pool.
+    #[arg(long = "database-max-connections")]
+    #[arg(default_value = DEFAULT_MAX_CONNECTIONS)]
+    #[arg(env = "DATABASE_MAX_CONNECTIONS")]
+    pub max_connections: u32,
+    /// Number of idle connections the database pool keeps open.
+    #[arg(long = "database-min-connections")]
+    #[arg(default_value = DEFAULT_MIN_CONNECTIONS)]
+    #[arg(env = "DATABASE_MIN_CONNECTIONS")]
+    pub min_connections: u32,
+    /// Maximum time to wait for a free connection of the database pool in seconds, before failing the query.
+    #[arg(long = "database-acquire-timeout")]
+    #[arg(default_value = DEFAULT_ACQUIRE_TIMEOUT)]
+    #[arg(env = "DATABASE_ACQUIRE_TIMEOUT")]
+    pub acquire_timeout: u64,
+    /// Time after which an idle connection above the minimum is closed in seconds. Set to 0 to never close them.
+    #[arg(long = "database-idle-timeout")]
+    #[arg(default_value = DEFAULT_IDLE_TIMEOUT)]
+    #[arg(env = "DATABASE_IDLE_TIMEOUT")]
+    pub idle_timeout:    u64,
+}
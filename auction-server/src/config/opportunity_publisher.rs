@@ -0,0 +1,20 @@
+use clap::Args;
+
+const DEFAULT_BUFFER_SIZE: &str = "1000";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Opportunity Publisher Options")]
+#[group(id = "OpportunityPublisher")]
+pub struct Options {
+    /// URL of an HTTP endpoint which the added and removed opportunities are published to as JSON.
+    /// Publishing is disabled if not set.
+    #[arg(long = "opportunity-publisher-url")]
+    #[arg(env = "OPPORTUNITY_PUBLISHER_URL")]
+    pub url:         Option<String>,
+    /// Maximum number of events buffered while the endpoint is unavailable.
+    /// New events are dropped when the buffer is full.
+    #[arg(long = "opportunity-publisher-buffer-size")]
+    #[arg(default_value = DEFAULT_BUFFER_SIZE)]
+    #[arg(env = "OPPORTUNITY_PUBLISHER_BUFFER_SIZE")]
+    pub buffer_size: usize,
+}
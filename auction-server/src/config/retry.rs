@@ -0,0 +1,32 @@
+use clap::Args;
+
+const DEFAULT_INITIAL_DELAY_MS: &str = "500";
+const DEFAULT_MAX_DELAY_MS: &str = "30000";
+const DEFAULT_MULTIPLIER: &str = "2";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Retry Options")]
+#[group(id = "Retry")]
+pub struct Options {
+    /// Delay in milliseconds before restarting a failed background task for the first time.
+    /// The delay grows exponentially with the consecutive failures of the task.
+    #[arg(long = "retry-initial-delay-ms")]
+    #[arg(default_value = DEFAULT_INITIAL_DELAY_MS)]
+    #[arg(env = "RETRY_INITIAL_DELAY_MS")]
+    pub initial_delay_ms: u64,
+    /// Maximum delay in milliseconds before restarting a failed background task.
+    #[arg(long = "retry-max-delay-ms")]
+    #[arg(default_value = DEFAULT_MAX_DELAY_MS)]
+    #[arg(env = "RETRY_MAX_DELAY_MS")]
+    pub max_delay_ms:     u64,
+    /// Factor the restart delay is multiplied by after each consecutive failure.
+    #[arg(long = "retry-multiplier")]
+    #[arg(default_value = DEFAULT_MULTIPLIER)]
+    #[arg(env = "RETRY_MULTIPLIER")]
+    pub multiplier:       f64,
+    /// Maximum number of consecutive failures of a background task before shutting down the server.
+    /// Failed tasks are restarted forever if not set.
+    #[arg(long = "retry-max-attempts")]
+    #[arg(env = "RETRY_MAX_ATTEMPTS")]
+    pub max_attempts:     Option<u32>,
+}
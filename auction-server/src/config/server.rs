@@ -24,4 +24,12 @@ pub struct Options {
     #[arg(default_value = DEFAULT_METRICS_ADDR)]
     #[arg(env = "METRICS_ADDR")]
     pub metrics_addr: SocketAddr,
+    /// Address and port the gRPC server will bind to. The gRPC server is disabled if not set.
+    #[arg(long = "grpc-addr")]
+    #[arg(env = "GRPC_ADDR")]
+    pub grpc_addr:    Option<SocketAddr>,
+    /// Persist the admin actions in the admin audit log.
+    #[arg(long = "admin-audit")]
+    #[arg(env = "ADMIN_AUDIT")]
+    pub admin_audit:  bool,
 }
@@ -0,0 +1,25 @@
+use clap::Args;
+
+const DEFAULT_OTLP_SERVICE_NAME: &str = "auction-server";
+const DEFAULT_OTLP_EXPORT_TIMEOUT: &str = "3";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Telemetry Options")]
+#[group(id = "Telemetry")]
+pub struct Options {
+    /// Endpoint of the OpenTelemetry collector to export the tracing spans to over OTLP (gRPC).
+    /// The spans are only exported if the endpoint is set.
+    #[arg(long = "otlp-endpoint")]
+    #[arg(env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    pub otlp_endpoint:       Option<String>,
+    /// Service name attached to the exported spans.
+    #[arg(long = "otlp-service-name")]
+    #[arg(default_value = DEFAULT_OTLP_SERVICE_NAME)]
+    #[arg(env = "OTEL_SERVICE_NAME")]
+    pub otlp_service_name:   String,
+    /// Timeout for exporting a batch of spans in seconds.
+    #[arg(long = "otlp-export-timeout")]
+    #[arg(default_value = DEFAULT_OTLP_EXPORT_TIMEOUT)]
+    #[arg(env = "OTLP_EXPORT_TIMEOUT")]
+    pub otlp_export_timeout: u64,
+}
@@ -0,0 +1,19 @@
+use clap::Args;
+
+const DEFAULT_FLUSH_INTERVAL: &str = "60";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "Usage Options")]
+#[group(id = "Usage")]
+pub struct Options {
+    /// Meter the API usage of each profile and enforce the monthly quotas configured for the profiles.
+    #[arg(long = "usage-metering")]
+    #[arg(env = "USAGE_METERING")]
+    pub metering:       bool,
+    /// Interval in seconds for flushing the metered usage to the database and reloading the quotas.
+    /// Quotas can be exceeded by the usage of a single interval.
+    #[arg(long = "usage-flush-interval")]
+    #[arg(default_value = DEFAULT_FLUSH_INTERVAL)]
+    #[arg(env = "USAGE_FLUSH_INTERVAL")]
+    pub flush_interval: u64,
+}
@@ -0,0 +1,28 @@
+use clap::Args;
+
+const DEFAULT_BROADCAST_CHANNEL_CAPACITY: &str = "1000";
+
+#[derive(Args, Clone, Debug)]
+#[command(next_help_heading = "WebSocket Options")]
+#[group(id = "WebSocket")]
+pub struct Options {
+    /// Notify subscribers when an opportunity is removed, along with the reason of the removal.
+    #[arg(long = "ws-emit-opportunity-removals")]
+    #[arg(env = "WS_EMIT_OPPORTUNITY_REMOVALS")]
+    pub emit_opportunity_removals:  bool,
+    /// Skip sending a bid status update to a subscriber if it is the same as the last status sent for the bid.
+    #[arg(long = "ws-dedup-bid-status")]
+    #[arg(env = "WS_DEDUP_BID_STATUS")]
+    pub dedup_bid_status:           bool,
+    /// Include the total amount of the winning bids in the auction conclusion updates.
+    /// The amounts are not revealed by default.
+    #[arg(long = "ws-reveal-winning-amounts")]
+    #[arg(env = "WS_REVEAL_WINNING_AMOUNTS")]
+    pub reveal_winning_amounts:     bool,
+    /// Number of updates buffered for the subscribers. A subscriber which falls behind by more updates misses
+    /// the oldest ones and is asked to resubscribe.
+    #[arg(long = "ws-broadcast-channel-capacity")]
+    #[arg(default_value = DEFAULT_BROADCAST_CHANNEL_CAPACITY)]
+    #[arg(env = "WS_BROADCAST_CHANNEL_CAPACITY")]
+    pub broadcast_channel_capacity: usize,
+}
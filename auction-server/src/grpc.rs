@@ -0,0 +1,243 @@
+use {
+    crate::{
+        api::{
+            bid::process_bid,
+            ws::UpdateEvent,
+            Auth,
+            RestError,
+        },
+        auction::{
+            Bid,
+            BidEvm,
+            BidSvm,
+        },
+        config::RunOptions,
+        server::ShutdownSignal,
+        state::{
+            BidId,
+            BidStatus,
+            BidStatusWithId,
+            Store,
+        },
+    },
+    anyhow::{
+        anyhow,
+        Result,
+    },
+    axum::http::StatusCode,
+    ethers::types::{
+        Address,
+        U256,
+    },
+    futures::Stream,
+    proto::{
+        auction_server::{
+            Auction,
+            AuctionServer,
+        },
+        submit_bid_request,
+        BidStatusUpdate,
+        SubmitBidRequest,
+        SubmitBidResponse,
+        SubscribeBidStatusRequest,
+    },
+    sqlx::types::time::OffsetDateTime,
+    std::{
+        collections::HashSet,
+        pin::Pin,
+        sync::Arc,
+    },
+    tokio::sync::broadcast::error::RecvError,
+    tonic::{
+        metadata::MetadataMap,
+        transport::Server,
+        Code,
+        Request,
+        Response,
+        Status,
+    },
+};
+
+pub mod proto {
+    tonic::include_proto!("express_relay.v1");
+}
+
+impl From<RestError> for Status {
+    fn from(error: RestError) -> Self {
+        let (status, message) = error.to_status_and_message();
+        let code = match status {
+            StatusCode::BAD_REQUEST => Code::InvalidArgument,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+            StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+            _ => Code::Internal,
+        };
+        Status::new(code, message)
+    }
+}
+
+/// Converts the good till unix timestamp of the bid, where zero means the bid does not expire.
+fn get_good_till(good_till: i64) -> Result<Option<OffsetDateTime>, Status> {
+    match good_till {
+        0 => Ok(None),
+        _ => OffsetDateTime::from_unix_timestamp(good_till)
+            .map(Some)
+            .map_err(|e| Status::invalid_argument(format!("Invalid good till: {}", e))),
+    }
+}
+
+impl TryFrom<SubmitBidRequest> for Bid {
+    type Error = Status;
+
+    fn try_from(request: SubmitBidRequest) -> Result<Self, Self::Error> {
+        match request.bid {
+            Some(submit_bid_request::Bid::Evm(bid)) => {
+                if bid.target_contract.len() != Address::len_bytes() {
+                    return Err(Status::invalid_argument(
+                        "Target contract should be a 20 bytes address",
+                    ));
+                }
+                let amount = U256::from_dec_str(&bid.amount)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid amount: {}", e)))?;
+                Ok(Bid::Evm(BidEvm {
+                    permission_key: bid.permission_key.into(),
+                    chain_id: bid.chain_id,
+                    target_contract: Address::from_slice(&bid.target_contract),
+                    target_calldata: bid.target_calldata.into(),
+                    amount,
+                    good_till: get_good_till(bid.good_till)?,
+                }))
+            }
+            Some(submit_bid_request::Bid::Svm(bid)) => {
+                let transaction = bincode::deserialize(&bid.transaction)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid transaction: {}", e)))?;
+                Ok(Bid::Svm(BidSvm {
+                    chain_id: bid.chain_id,
+                    transaction,
+                    good_till: get_good_till(bid.good_till)?,
+                }))
+            }
+            None => Err(Status::invalid_argument("Bid is missing")),
+        }
+    }
+}
+
+impl From<BidStatusWithId> for BidStatusUpdate {
+    fn from(update: BidStatusWithId) -> Self {
+        let (status, result, index) = match update.bid_status {
+            BidStatus::Pending => ("pending", None, None),
+            BidStatus::Submitted { result, index } => {
+                ("submitted", Some(result.to_vec()), Some(index))
+            }
+            BidStatus::Lost { result, index, .. } => {
+                ("lost", result.map(|result| result.to_vec()), index)
+            }
+            BidStatus::Won { result, index } => ("won", Some(result.to_vec()), Some(index)),
+            BidStatus::Expired => ("expired", None, None),
+        };
+        BidStatusUpdate {
+            id: update.id.to_string(),
+            status: status.to_string(),
+            result,
+            index,
+        }
+    }
+}
+
+/// Authenticates the request with the bearer token in the authorization metadata, the same way as the REST API.
+async fn authenticate(store: &Store, metadata: &MetadataMap) -> Result<Auth, Status> {
+    let Some(header) = metadata.get("authorization") else {
+        return Ok(Auth::Unauthorized);
+    };
+    let token = header
+        .to_str()
+        .ok()
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .ok_or(RestError::InvalidToken)?;
+    Ok(Auth::from_token(store, token.to_string()).await?)
+}
+
+pub struct AuctionService {
+    store:    Arc<Store>,
+    shutdown: ShutdownSignal,
+}
+
+#[tonic::async_trait]
+impl Auction for AuctionService {
+    type SubscribeBidStatusStream =
+        Pin<Box<dyn Stream<Item = Result<BidStatusUpdate, Status>> + Send>>;
+
+    async fn submit_bid(
+        &self,
+        request: Request<SubmitBidRequest>,
+    ) -> Result<Response<SubmitBidResponse>, Status> {
+        let auth = authenticate(&self.store, request.metadata()).await?;
+        let bid = Bid::try_from(request.into_inner())?;
+        let bid_result = process_bid(self.store.clone(), bid, auth, None).await?;
+        Ok(Response::new(SubmitBidResponse {
+            id: bid_result.id.to_string(),
+        }))
+    }
+
+    async fn subscribe_bid_status(
+        &self,
+        request: Request<SubscribeBidStatusRequest>,
+    ) -> Result<Response<Self::SubscribeBidStatusStream>, Status> {
+        let bid_ids = request
+            .into_inner()
+            .bid_ids
+            .iter()
+            .map(|id| {
+                BidId::parse_str(id)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid bid id {}: {}", id, e)))
+            })
+            .collect::<Result<HashSet<BidId>, Status>>()?;
+        let mut receiver = self.store.event_sender.subscribe();
+        let mut shutdown = self.shutdown.subscribe();
+        let stream = async_stream::stream! {
+            loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Ok(UpdateEvent::BidStatusUpdate { status: update, .. }) => {
+                            if bid_ids.contains(&update.id) {
+                                yield Ok(update.into());
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped)) => {
+                            yield Err(Status::data_loss(format!("Missed {} status updates", skipped)));
+                            break;
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = shutdown.recv() => break,
+                }
+            }
+        };
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+pub async fn start_grpc(
+    run_options: RunOptions,
+    store: Arc<Store>,
+    shutdown: ShutdownSignal,
+) -> Result<()> {
+    let Some(grpc_addr) = run_options.server.grpc_addr else {
+        return Ok(());
+    };
+    tracing::info!("Starting gRPC server...");
+
+    Server::builder()
+        .add_service(AuctionServer::new(AuctionService {
+            store,
+            shutdown: shutdown.clone(),
+        }))
+        .serve_with_shutdown(grpc_addr, async move {
+            shutdown.subscribe().recv().await;
+            tracing::info!("Shutting down gRPC server...");
+        })
+        .await
+        .map_err(|e| anyhow!("gRPC server failed: {:?}", e))
+}
@@ -29,9 +29,12 @@ use {
 
 mod api;
 mod auction;
+mod bid_queue;
 mod config;
+mod grpc;
 mod models;
 mod opportunity_adapter;
+mod opportunity_publisher;
 mod per_metrics;
 mod serde;
 mod server;
@@ -39,9 +42,14 @@ mod state;
 mod subwallet;
 mod token_spoof;
 mod traced_client;
+mod usage;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Parse the command line arguments with StructOpt, will exit automatically on `--help` or
+    // with invalid arguments.
+    let options = config::Options::parse();
+
     // Initialize a Tracing Subscriber
     let log_layer = tracing_subscriber::fmt::layer()
         .with_file(false)
@@ -51,22 +59,32 @@ async fn main() -> Result<()> {
         .with_ansi(std::io::stderr().is_terminal());
 
 
-    // Will use env variable OTEL_EXPORTER_OTLP_ENDPOINT or defaults to 127.0.0.1:4317
-    let otlp_exporter = opentelemetry_otlp::new_exporter()
-        .tonic()
-        .with_timeout(Duration::from_secs(3));
-    let tracer = opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(otlp_exporter)
-        .with_trace_config(
-            trace::config().with_resource(Resource::new(vec![KeyValue::new(
-                "service.name",
-                "auction-server",
-            )])),
-        )
-        .install_batch(opentelemetry_sdk::runtime::Tokio)
-        .map_err(|e| anyhow::anyhow!("Error initializing open telemetry: {}", e))?;
-    let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
+    // The spans are only exported over OTLP when the server is run with an OTLP endpoint
+    let telemetry_options = match &options {
+        config::Options::Run(opts) => Some(opts.telemetry.clone()),
+        config::Options::SyncSubwallets(_) => None,
+    };
+    let telemetry = match telemetry_options {
+        Some(telemetry_options) => match telemetry_options.otlp_endpoint {
+            Some(otlp_endpoint) => {
+                let otlp_exporter = opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(otlp_endpoint)
+                    .with_timeout(Duration::from_secs(telemetry_options.otlp_export_timeout));
+                let tracer = opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(otlp_exporter)
+                    .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                        KeyValue::new("service.name", telemetry_options.otlp_service_name),
+                    ])))
+                    .install_batch(opentelemetry_sdk::runtime::Tokio)
+                    .map_err(|e| anyhow::anyhow!("Error initializing open telemetry: {}", e))?;
+                Some(tracing_opentelemetry::layer().with_tracer(tracer))
+            }
+            None => None,
+        },
+        None => None,
+    };
 
     let registry = tracing_subscriber::registry()
         .with(MetricsLayer.with_filter(filter::filter_fn(is_metrics)))
@@ -92,9 +110,7 @@ async fn main() -> Result<()> {
             .init();
     }
 
-    // Parse the command line arguments with StructOpt, will exit automatically on `--help` or
-    // with invalid arguments.
-    match config::Options::parse() {
+    match options {
         config::Options::Run(opts) => start_server(opts).await,
         config::Options::SyncSubwallets(opts) => subwallet::sync_subwallets(opts).await,
     }
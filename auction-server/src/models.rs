@@ -21,43 +21,64 @@ use {
         ops::Deref,
         str::FromStr,
     },
+    utoipa::ToSchema,
     uuid::Uuid,
 };
 
 pub type AuctionId = Uuid;
 #[derive(Clone, FromRow, Debug)]
 pub struct Auction {
-    pub id:                  AuctionId,
-    pub creation_time:       PrimitiveDateTime,
-    pub conclusion_time:     Option<PrimitiveDateTime>,
-    pub permission_key:      Vec<u8>,
-    pub chain_id:            String,
-    pub chain_type:          ChainType,
-    pub tx_hash:             Option<Vec<u8>>,
-    pub bid_collection_time: Option<PrimitiveDateTime>,
-    pub submission_time:     Option<PrimitiveDateTime>,
+    pub id:                      AuctionId,
+    pub creation_time:           PrimitiveDateTime,
+    pub conclusion_time:         Option<PrimitiveDateTime>,
+    pub permission_key:          Vec<u8>,
+    pub chain_id:                String,
+    pub chain_type:              ChainType,
+    pub tx_hash:                 Option<Vec<u8>>,
+    pub bid_collection_time:     Option<PrimitiveDateTime>,
+    pub submission_time:         Option<PrimitiveDateTime>,
+    /// Address of the relayer which signed the submitted transaction.
+    pub relayer:                 Option<Vec<u8>>,
+    /// Number of the block which included the transaction, used to detect reorgs. Only recorded on EVM chains.
+    pub conclusion_block_number: Option<i64>,
+    /// Hash of the block which included the transaction.
+    pub conclusion_block_hash:   Option<Vec<u8>>,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, sqlx::Type)]
+/// An auction along with the number of its bids which won on chain.
+#[derive(Clone, FromRow, Debug)]
+pub struct AuctionWithWonBids {
+    #[sqlx(flatten)]
+    pub auction:       Auction,
+    pub won_bid_count: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, sqlx::Type, Serialize, Deserialize, ToSchema)]
 #[sqlx(type_name = "opportunity_removal_reason", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum OpportunityRemovalReason {
     Expired,
     Invalid,
+    Consumed,
 }
 
 #[derive(Clone, FromRow, Debug)]
 pub struct Opportunity {
-    pub id:                Uuid,
-    pub creation_time:     PrimitiveDateTime,
-    pub permission_key:    Vec<u8>,
-    pub chain_id:          String,
-    pub target_contract:   Vec<u8>,
-    pub target_call_value: BigDecimal,
-    pub target_calldata:   Vec<u8>,
-    pub removal_time:      Option<PrimitiveDateTime>,
-    pub sell_tokens:       JsonValue,
-    pub buy_tokens:        JsonValue,
-    pub removal_reason:    Option<OpportunityRemovalReason>,
+    pub id:                    Uuid,
+    pub creation_time:         PrimitiveDateTime,
+    pub permission_key:        Vec<u8>,
+    pub chain_id:              String,
+    pub target_contract:       Vec<u8>,
+    pub target_call_value:     BigDecimal,
+    pub target_calldata:       Vec<u8>,
+    pub removal_time:          Option<PrimitiveDateTime>,
+    pub sell_tokens:           JsonValue,
+    pub buy_tokens:            JsonValue,
+    pub removal_reason:        Option<OpportunityRemovalReason>,
+    /// Sell token amounts which are not filled yet. Null if the opportunity was never filled.
+    pub remaining_sell_tokens: Option<JsonValue>,
+    /// Buy token amounts which are not filled yet. Null if the opportunity was never filled.
+    pub remaining_buy_tokens:  Option<JsonValue>,
 }
 
 
@@ -73,6 +94,18 @@ impl TryFrom<String> for EmailAddress {
     }
 }
 
+/// Tier of a profile, which sets the priority of its bids under load.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, sqlx::Type,
+)]
+#[sqlx(type_name = "profile_tier", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileTier {
+    #[default]
+    Standard,
+    Premium,
+}
+
 pub type ProfileId = Uuid;
 #[derive(Clone, FromRow)]
 pub struct Profile {
@@ -80,14 +113,71 @@ pub struct Profile {
     pub name:  String,
     #[sqlx(try_from = "String")]
     pub email: EmailAddress,
+    pub tier:  ProfileTier,
 
     pub created_at: PrimitiveDateTime,
     pub updated_at: PrimitiveDateTime,
 }
 
+pub type AdminAuditId = Uuid;
+/// An entry of the admin audit log. The profile and access token changes also record their target,
+/// with only the ids of the tokens, never the token values.
+#[derive(Clone, FromRow, Debug)]
+pub struct AdminAudit {
+    pub id:                AdminAuditId,
+    pub creation_time:     PrimitiveDateTime,
+    pub action:            String,
+    pub parameters:        JsonValue,
+    pub admin_identity:    String,
+    /// The profile which took the action, or none if it was taken with the admin secret key.
+    pub actor_profile_id:  Option<ProfileId>,
+    pub target_profile_id: Option<ProfileId>,
+    pub target_token_id:   Option<TokenId>,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct ChainStats {
+    pub auction_count:              i64,
+    pub bid_count:                  i64,
+    pub won_bid_count:              i64,
+    pub concluded_bid_count:        i64,
+    pub total_fees:                 BigDecimal,
+    pub average_settlement_latency: Option<f64>,
+}
+
+/// API usage of a profile, as totals of a period or as the usage not yet flushed to the DB.
+#[derive(Clone, Copy, FromRow, Debug, Default)]
+pub struct Usage {
+    pub requests:          i64,
+    pub bids:              i64,
+    pub bytes_transferred: i64,
+}
+
+/// Monthly usage limits of a profile. A missing limit means the usage is not limited.
+#[derive(Clone, Copy, FromRow, Debug)]
+pub struct UsageQuota {
+    pub profile_id:        ProfileId,
+    pub requests:          Option<i64>,
+    pub bids:              Option<i64>,
+    pub bytes_transferred: Option<i64>,
+}
+
 pub type TokenId = Uuid;
 pub type AccessTokenToken = String;
-#[derive(Clone)]
+/// Capabilities an access token grants on behalf of its profile.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema, sqlx::Type,
+)]
+#[sqlx(type_name = "access_token_scope", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AccessTokenScope {
+    /// Read the bids of the profile.
+    Read,
+    /// Submit bids on behalf of the profile.
+    Submit,
+}
+
+#[derive(Clone, FromRow)]
 pub struct AccessToken {
     pub id: TokenId,
 
@@ -97,6 +187,7 @@ pub struct AccessToken {
 
     pub created_at: PrimitiveDateTime,
     pub updated_at: PrimitiveDateTime,
+    pub scopes:     Vec<AccessTokenScope>,
 }
 
 
@@ -108,6 +199,7 @@ pub enum BidStatus {
     Submitted,
     Lost,
     Won,
+    Expired,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -120,8 +212,9 @@ impl Deref for BundleIndex {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, sqlx::Type)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, sqlx::Type, Serialize, ToSchema)]
 #[sqlx(type_name = "chain_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum ChainType {
     Evm,
     Svm,
@@ -141,13 +234,78 @@ pub struct BidMetadataSvm {
     pub transaction: VersionedTransaction,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(untagged)]
+/// Version of the shape of the stored bid metadata. It is stored along with the metadata and should be bumped
+/// whenever the shape changes, so the bids stored with the older shapes can still be read.
+pub const BID_METADATA_VERSION: u64 = 2;
+
+#[derive(Clone, Debug)]
 pub enum BidMetadata {
     Evm(BidMetadataEvm),
     Svm(BidMetadataSvm),
 }
 
+/// Shape of the bid metadata up to version 2. Version 2 only adds the version to the stored metadata.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BidMetadataV1 {
+    Evm(BidMetadataEvm),
+    Svm(BidMetadataSvm),
+}
+
+impl From<BidMetadataV1> for BidMetadata {
+    fn from(metadata: BidMetadataV1) -> Self {
+        match metadata {
+            BidMetadataV1::Evm(metadata) => BidMetadata::Evm(metadata),
+            BidMetadataV1::Svm(metadata) => BidMetadata::Svm(metadata),
+        }
+    }
+}
+
+impl Serialize for BidMetadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let value = match self {
+            BidMetadata::Evm(metadata) => serde_json::to_value(metadata),
+            BidMetadata::Svm(metadata) => serde_json::to_value(metadata),
+        };
+        let mut value = value.map_err(serde::ser::Error::custom)?;
+        if let JsonValue::Object(object) = &mut value {
+            object.insert("version".to_string(), BID_METADATA_VERSION.into());
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BidMetadata {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut value = JsonValue::deserialize(deserializer)?;
+        // The metadata stored before the versioning has no version
+        let version = match &mut value {
+            JsonValue::Object(object) => match object.remove("version") {
+                Some(version) => version.as_u64().ok_or_else(|| {
+                    serde::de::Error::custom(format!("Invalid bid metadata version: {}", version))
+                })?,
+                None => 1,
+            },
+            _ => 1,
+        };
+        match version {
+            1 | 2 => BidMetadataV1::deserialize(value)
+                .map(BidMetadata::from)
+                .map_err(serde::de::Error::custom),
+            _ => Err(serde::de::Error::custom(format!(
+                "Unsupported bid metadata version: {}",
+                version
+            ))),
+        }
+    }
+}
+
 #[derive(Clone, Debug, FromRow)]
 pub struct Bid {
     pub id:              BidId,
@@ -161,6 +319,7 @@ pub struct Bid {
     pub initiation_time: PrimitiveDateTime,
     pub profile_id:      Option<ProfileId>,
     pub metadata:        Json<BidMetadata>,
+    pub good_till:       Option<PrimitiveDateTime>,
 }
 
 impl Bid {
@@ -10,11 +10,10 @@ use {
             BidEvm,
             MulticallData,
             MulticallReturn,
+            MulticallStatus,
         },
-        server::{
-            EXIT_CHECK_INTERVAL,
-            SHOULD_EXIT,
-        },
+        config::ChainId,
+        server::ShutdownReceiver,
         state::{
             BidAmount,
             ChainStoreEvm,
@@ -72,13 +71,13 @@ use {
     },
     sqlx::types::time::OffsetDateTime,
     std::{
-        collections::HashMap,
+        collections::{
+            HashMap,
+            HashSet,
+        },
         ops::Add,
         result,
-        sync::{
-            atomic::Ordering,
-            Arc,
-        },
+        sync::Arc,
         time::{
             Duration,
             SystemTime,
@@ -161,6 +160,65 @@ fn generate_random_u256() -> U256 {
     U256::from(rng.gen::<[u8; 32]>())
 }
 
+/// Checks that the function selector of the opportunity call is allowed on its target contract
+fn verify_opportunity_selector(
+    opportunity: &OpportunityParamsV1,
+    chain_store: &ChainStoreEvm,
+) -> Result<()> {
+    let Some(allowlist) = chain_store
+        .config
+        .opportunity_selector_allowlist
+        .get(&opportunity.target_contract)
+    else {
+        return Ok(());
+    };
+    if allowlist.is_empty() {
+        return Ok(());
+    }
+    let selector = opportunity.target_calldata.get(..4).unwrap_or_default();
+    if !allowlist.iter().any(|allowed| allowed.as_ref() == selector) {
+        return Err(anyhow!(
+            "Function selector {} is not allowed on target contract {:?}",
+            Bytes::from(selector.to_vec()),
+            opportunity.target_contract
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the opportunity does not list too many tokens on either side and
+/// does not both sell and buy the same token, except WETH if allowed on the chain
+fn verify_opportunity_tokens(
+    opportunity: &OpportunityParamsV1,
+    chain_store: &ChainStoreEvm,
+) -> Result<()> {
+    let max_tokens = chain_store.config.max_opportunity_tokens;
+    if opportunity.sell_tokens.len() > max_tokens || opportunity.buy_tokens.len() > max_tokens {
+        return Err(anyhow!(
+            "Opportunity should have at most {} sell tokens and {} buy tokens",
+            max_tokens,
+            max_tokens
+        ));
+    }
+    let sell_tokens: HashSet<Address> = opportunity
+        .sell_tokens
+        .iter()
+        .map(|token_amount| token_amount.token)
+        .collect();
+    let overlap = opportunity.buy_tokens.iter().find(|token_amount| {
+        sell_tokens.contains(&token_amount.token)
+            && !(chain_store.config.allow_weth_token_overlap
+                && token_amount.token == chain_store.weth)
+    });
+    if let Some(token_amount) = overlap {
+        return Err(anyhow!(
+            "Token {:?} is in both the sell and buy tokens",
+            token_amount.token
+        ));
+    }
+    Ok(())
+}
+
 /// Verify an opportunity by simulating the execution call and checking the result
 /// Simulation is done by spoofing the balances and allowances of a random executor
 /// Returns Ok(VerificationResult) if the simulation is successful or if the tokens cannot be spoofed
@@ -168,9 +226,12 @@ fn generate_random_u256() -> U256 {
 #[tracing::instrument(skip_all)]
 pub async fn verify_opportunity(
     opportunity: OpportunityParamsV1,
+    store: &Store,
     chain_store: &ChainStoreEvm,
     relayer: Address,
 ) -> Result<VerificationResult> {
+    verify_opportunity_selector(&opportunity, chain_store)?;
+    verify_opportunity_tokens(&opportunity, chain_store)?;
     let client = Arc::new(chain_store.provider.clone());
     let fake_wallet = LocalWallet::new(&mut rand::thread_rng());
 
@@ -221,49 +282,60 @@ pub async fn verify_opportunity(
         ))],
     )
     .tx;
+    let Some(state) = spoof_token_balances(
+        store,
+        chain_store,
+        &opportunity.chain_id,
+        fake_wallet.address(),
+        &params_with_signature.params.permit.permitted,
+    )
+    .await?
+    else {
+        return Ok(VerificationResult::UnableToSpoof);
+    };
+    let result = client.call_raw(&call).state(&state).await?;
+
+    let statuses = decode_multicall_statuses(&result)?;
+    if !statuses[0].external_success {
+        tracing::info!("Opportunity simulation failed: {:?}", statuses);
+        return Err(anyhow!("Express Relay Simulation failed: {:?}", statuses));
+    }
+    Ok(VerificationResult::Success)
+}
+
+/// Builds the state overrides which give the owner the permitted token amounts and their permit2 allowances.
+/// Returns None if any of the tokens cannot be spoofed.
+async fn spoof_token_balances(
+    store: &Store,
+    chain_store: &ChainStoreEvm,
+    chain_id: &ChainId,
+    owner: Address,
+    permitted_tokens: &[TokenPermissions],
+) -> Result<Option<spoof::State>> {
     let mut state = spoof::State::default();
-    let token_spoof_info = chain_store.token_spoof_info.read().await.clone();
-    let required_tokens = params_with_signature.params.permit.permitted.clone();
     let mut tokens_map = HashMap::<Address, U256>::new();
-    required_tokens.iter().for_each(|token_amount| {
+    permitted_tokens.iter().for_each(|token_amount| {
         let amount = tokens_map.entry(token_amount.token).or_insert(U256::zero());
         *amount = amount.add(token_amount.amount);
     });
 
     for (token, amount) in tokens_map {
-        let spoof_info = match token_spoof_info.get(&token) {
-            Some(info) => info.clone(),
-            None => {
-                let result = token_spoof::find_spoof_info(token, client.clone())
-                    .await
-                    .unwrap_or_else(|e| {
-                        tracing::error!("Error finding spoof info: {:?}", e);
-                        SpoofInfo::UnableToSpoof
-                    });
-
-                chain_store
-                    .token_spoof_info
-                    .write()
-                    .await
-                    .insert(token, result.clone());
-                result
-            }
-        };
+        let spoof_info = store.get_spoof_info(chain_id, token).await?;
         match spoof_info {
-            SpoofInfo::UnableToSpoof => return Ok(VerificationResult::UnableToSpoof),
+            SpoofInfo::UnableToSpoof => return Ok(None),
             SpoofInfo::Spoofed {
                 balance_slot,
                 allowance_slot,
             } => {
                 let balance_storage_key =
-                    token_spoof::calculate_balance_storage_key(fake_wallet.address(), balance_slot);
+                    token_spoof::calculate_balance_storage_key(owner, balance_slot);
                 let value: [u8; 32] = amount.into();
                 state
                     .account(token)
                     .store(balance_storage_key, value.into());
 
                 let allowance_storage_key = token_spoof::calculate_allowance_storage_key(
-                    fake_wallet.address(),
+                    owner,
                     chain_store.permit2,
                     allowance_slot,
                 );
@@ -274,29 +346,94 @@ pub async fn verify_opportunity(
             }
         }
     }
-    let result = client.call_raw(&call).state(&state).await?;
+    Ok(Some(state))
+}
 
-    match MulticallReturn::decode(&result) {
-        Ok(result) => {
-            if !result.multicall_statuses[0].external_success {
-                tracing::info!(
-                    "Opportunity simulation failed: {:?}",
-                    result.multicall_statuses
-                );
-                return Err(anyhow!(
-                    "Express Relay Simulation failed: {:?}",
-                    result.multicall_statuses
-                ));
-            }
-        }
-        Err(e) => {
-            return Err(anyhow!(format!(
+fn decode_multicall_statuses(result: &Bytes) -> Result<Vec<MulticallStatus>> {
+    MulticallReturn::decode(result)
+        .map(|result| result.multicall_statuses)
+        .map_err(|e| {
+            anyhow!(format!(
                 "Error decoding multicall result: {:?} - result: {:?}",
                 e, result
-            )))
-        }
-    }
-    Ok(VerificationResult::Success)
+            ))
+        })
+}
+
+/// Result of simulating the execution of an opportunity with a signed bid.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct OpportunityBidSimulation {
+    /// Whether the execution of the opportunity succeeded.
+    pub success:          bool,
+    /// The decoded revert reason of the execution, if it failed.
+    #[schema(example = "Opportunity Adapter Contract Revert Error: InsufficientTokenReceived")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason:    Option<String>,
+    /// Whether the executor was given the tokens to sell and their allowances for the simulation.
+    /// The simulation runs against the actual balances of the executor if any of the tokens cannot be spoofed.
+    pub balances_spoofed: bool,
+}
+
+/// Simulates the execution of an opportunity with the signed bid of a searcher, without placing the bid.
+/// The executor is given the tokens the bid permits to be spent, so the execution can be tested before funding it.
+#[tracing::instrument(skip_all)]
+pub async fn simulate_opportunity_execution(
+    opportunity: OpportunityParamsV1,
+    opportunity_bid: OpportunityBid,
+    store: &Store,
+    chain_store: &ChainStoreEvm,
+) -> Result<OpportunityBidSimulation> {
+    let adapter_calldata =
+        make_adapter_calldata(opportunity.clone(), opportunity_bid.clone(), chain_store).await?;
+    let permitted_tokens =
+        make_permitted_tokens(opportunity.clone(), opportunity_bid.clone(), chain_store);
+    let call = get_simulation_call(
+        chain_store.relayers.primary().address(),
+        chain_store.provider.clone(),
+        chain_store.config.clone(),
+        opportunity.permission_key,
+        vec![MulticallData::from((
+            Uuid::new_v4().to_bytes_le(),
+            chain_store.config.adapter_factory_contract,
+            adapter_calldata,
+            opportunity_bid.amount,
+            U256::max_value(),
+            false,
+        ))],
+    )
+    .tx;
+    let state = spoof_token_balances(
+        store,
+        chain_store,
+        &opportunity.chain_id,
+        opportunity_bid.executor,
+        &permitted_tokens,
+    )
+    .await?;
+    let balances_spoofed = state.is_some();
+    let client = chain_store.provider.clone();
+    let result = match &state {
+        Some(state) => client.call_raw(&call).state(state).await?,
+        None => client.call_raw(&call).await?,
+    };
+
+    let status = decode_multicall_statuses(&result)?
+        .into_iter()
+        .next()
+        .ok_or(anyhow!("Multicall result has no status"))?;
+    let revert_reason = (!status.external_success).then(|| {
+        parse_revert_error(&status.external_result).unwrap_or(
+            match status.multicall_revert_reason.is_empty() {
+                true => format!("Execution reverted: {}", status.external_result),
+                false => status.multicall_revert_reason,
+            },
+        )
+    });
+    Ok(OpportunityBidSimulation {
+        success: status.external_success,
+        revert_reason,
+        balances_spoofed,
+    })
 }
 
 impl From<ExecutionParamsWithSignature> for eip712::TypedData {
@@ -527,8 +664,8 @@ async fn verify_with_store(
         .chains
         .get(&params.chain_id)
         .expect("Opportunity Chain not found in store");
-    let relayer = store.relayer.address();
-    match verify_opportunity(params.clone(), chain_store, relayer).await {
+    let relayer = chain_store.relayers.primary().address();
+    match verify_opportunity(params.clone(), store, chain_store, relayer).await {
         Ok(VerificationResult::Success) => None,
         Ok(VerificationResult::UnableToSpoof) => {
             let current_time = SystemTime::now()
@@ -550,19 +687,26 @@ async fn verify_with_store(
 /// # Arguments
 ///
 /// * `store`: server store
-pub async fn run_verification_loop(store: Arc<Store>) -> Result<()> {
+/// * `shutdown`: receiver of the server shutdown signal
+pub async fn run_verification_loop(
+    store: Arc<Store>,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
     tracing::info!("Starting opportunity verifier...");
-    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
 
     // this should be replaced by a subscription to the chain and trigger on new blocks
     let mut submission_interval = tokio::time::interval(Duration::from_secs(5));
-    while !SHOULD_EXIT.load(Ordering::Acquire) {
+    loop {
         tokio::select! {
             _ = submission_interval.tick() => {
-                let all_opportunities = store.opportunity_store.opportunities.read().await.clone();
+                let all_opportunities = store.opportunity_store.get_all_opportunities().await;
                 for (_permission_key,opportunities) in all_opportunities.iter() {
                     // check each of the opportunities for this permission key for validity
                     for opportunity in opportunities.iter() {
+                        let OpportunityParams::V1(params) = &opportunity.params;
+                        if !store.opportunities_enabled(&params.chain_id) {
+                            continue;
+                        }
                         if let Some(reason) =  verify_with_store(opportunity.clone(), &store).await {
                             tracing::info!(
                                 "Removing Opportunity {} for reason {:?}",
@@ -576,14 +720,50 @@ pub async fn run_verification_loop(store: Arc<Store>) -> Result<()> {
                     }
                 }
             }
-            _ = exit_check_interval.tick() => {
-            }
+            _ = shutdown.recv() => break,
         }
     }
     tracing::info!("Shutting down opportunity verifier...");
     Ok(())
 }
 
+const OPPORTUNITY_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Run an infinite loop to remove the opportunities which are older than the maximum opportunity age of their chain
+///
+/// # Arguments
+///
+/// * `store`: server store
+/// * `shutdown`: receiver of the server shutdown signal
+pub async fn run_opportunity_expiry_loop(
+    store: Arc<Store>,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
+    if store
+        .chains
+        .values()
+        .all(|chain_store| chain_store.config.opportunity_max_age.is_none())
+    {
+        return Ok(());
+    }
+    tracing::info!("Starting opportunity expiry loop...");
+    let mut expiry_interval = tokio::time::interval(OPPORTUNITY_EXPIRY_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = expiry_interval.tick() => {
+                match store.remove_expired_opportunities().await {
+                    Ok(0) => {}
+                    Ok(removed) => tracing::info!("Removed {} expired opportunities", removed),
+                    Err(e) => tracing::error!("Failed to remove expired opportunities: {}", e),
+                }
+            }
+            _ = shutdown.recv() => break,
+        }
+    }
+    tracing::info!("Shutting down opportunity expiry loop...");
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Clone)]
 pub struct OpportunityBid {
     /// The opportunity permission key
@@ -621,12 +801,9 @@ pub async fn handle_opportunity_bid(
 ) -> result::Result<Uuid, RestError> {
     let opportunities = store
         .opportunity_store
-        .opportunities
-        .read()
+        .get_opportunities(&opportunity_bid.permission_key)
         .await
-        .get(&opportunity_bid.permission_key)
-        .ok_or(RestError::OpportunityNotFound)?
-        .clone();
+        .ok_or(RestError::OpportunityNotFound)?;
 
     let opportunity = opportunities
         .iter()
@@ -639,6 +816,11 @@ pub async fn handle_opportunity_bid(
         .chains
         .get(&params.chain_id)
         .ok_or(RestError::InvalidChainId)?;
+    if !chain_store.config.opportunities_enabled {
+        return Err(RestError::BadParameters(
+            "Opportunities are disabled on this chain".to_string(),
+        ));
+    }
 
     let adapter_calldata =
         make_adapter_calldata(params.clone(), opportunity_bid.clone(), chain_store)
@@ -657,6 +839,7 @@ pub async fn handle_opportunity_bid(
         target_contract: chain_store.config.adapter_factory_contract,
         target_calldata: adapter_calldata,
         amount:          opportunity_bid.amount,
+        good_till:       None,
     };
     match handle_bid(store.clone(), bid.clone(), initiation_time, auth).await {
         Ok(id) => Ok(id),
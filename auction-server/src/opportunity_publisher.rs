@@ -0,0 +1,134 @@
+use {
+    crate::{
+        api::opportunity::{
+            OpportunityParamsWithMetadata,
+            RemovedOpportunity,
+        },
+        config::RunOptions,
+        server::{
+            EXIT_CHECK_INTERVAL,
+            SHOULD_EXIT,
+        },
+    },
+    anyhow::Result,
+    axum_prometheus::metrics,
+    reqwest::header::CONTENT_TYPE,
+    serde::Serialize,
+    std::{
+        future::Future,
+        sync::{
+            atomic::Ordering,
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::sync::{
+        mpsc,
+        Mutex,
+    },
+};
+
+const MAX_PUBLISH_ATTEMPTS: u32 = 5;
+const PUBLISH_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An event published to the downstream queue when the live opportunities change.
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpportunityEvent {
+    Added {
+        opportunity: OpportunityParamsWithMetadata,
+    },
+    Updated {
+        opportunity: OpportunityParamsWithMetadata,
+    },
+    Removed {
+        opportunity: RemovedOpportunity,
+    },
+}
+
+pub type OpportunityEventReceiver = Arc<Mutex<mpsc::Receiver<OpportunityEvent>>>;
+
+/// A backend which delivers the opportunity events to a downstream queue.
+pub trait OpportunityPublisher: Send + Sync {
+    fn publish(&self, event: &OpportunityEvent) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Publishes the events as JSON to an HTTP endpoint, such as a webhook or the REST proxy of a queue.
+pub struct HttpPublisher {
+    client: reqwest::Client,
+    url:    String,
+}
+
+impl OpportunityPublisher for HttpPublisher {
+    async fn publish(&self, event: &OpportunityEvent) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(event)?)
+            .timeout(PUBLISH_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+async fn publish_with_retry<P: OpportunityPublisher>(publisher: &P, event: &OpportunityEvent) {
+    for attempt in 0..MAX_PUBLISH_ATTEMPTS {
+        match publisher.publish(event).await {
+            Ok(()) => {
+                metrics::counter!("opportunity_events_published_total").increment(1);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to publish opportunity event (attempt {}): {:?}",
+                    attempt + 1,
+                    e
+                );
+                if SHOULD_EXIT.load(Ordering::Acquire) {
+                    break;
+                }
+                tokio::time::sleep(PUBLISH_RETRY_BASE_DELAY * 2u32.pow(attempt)).await;
+            }
+        }
+    }
+    tracing::error!("Dropping opportunity event after failing to publish it");
+    metrics::counter!("opportunity_events_dropped_total").increment(1);
+}
+
+async fn run_publisher<P: OpportunityPublisher>(
+    publisher: P,
+    receiver: OpportunityEventReceiver,
+) -> Result<()> {
+    let mut receiver = receiver.lock().await;
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Some(event) => publish_with_retry(&publisher, &event).await,
+                None => break,
+            },
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    tracing::info!("Shutting down opportunity publisher...");
+    Ok(())
+}
+
+pub async fn start_opportunity_publisher(
+    run_options: RunOptions,
+    receiver: OpportunityEventReceiver,
+) -> Result<()> {
+    let Some(url) = run_options.opportunity_publisher.url else {
+        return Ok(());
+    };
+    tracing::info!("Starting opportunity publisher...");
+
+    let publisher = HttpPublisher {
+        client: reqwest::Client::new(),
+        url,
+    };
+    run_publisher(publisher, receiver).await
+}
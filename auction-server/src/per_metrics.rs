@@ -1,10 +1,7 @@
 use {
     crate::{
         config::RunOptions,
-        server::{
-            EXIT_CHECK_INTERVAL,
-            SHOULD_EXIT,
-        },
+        server::ShutdownReceiver,
         state::Store,
     },
     anyhow::Result,
@@ -18,10 +15,7 @@ use {
     },
     std::{
         fmt::Debug,
-        sync::{
-            atomic::Ordering,
-            Arc,
-        },
+        sync::Arc,
         time::Instant,
     },
     tracing::{
@@ -148,7 +142,11 @@ where
     }
 }
 
-pub async fn start_metrics(run_options: RunOptions, store: Arc<Store>) -> Result<()> {
+pub async fn start_metrics(
+    run_options: RunOptions,
+    store: Arc<Store>,
+    mut shutdown: ShutdownReceiver,
+) -> Result<()> {
     tracing::info!("Starting Metrics Server...");
 
     let (_, metric_handle) = PrometheusMetricLayerBuilder::new()
@@ -159,10 +157,8 @@ pub async fn start_metrics(run_options: RunOptions, store: Arc<Store>) -> Result
 
     let listener = tokio::net::TcpListener::bind(&run_options.server.metrics_addr).await?;
     axum::serve(listener, app)
-        .with_graceful_shutdown(async {
-            while !SHOULD_EXIT.load(Ordering::Acquire) {
-                tokio::time::sleep(EXIT_CHECK_INTERVAL).await;
-            }
+        .with_graceful_shutdown(async move {
+            shutdown.recv().await;
             tracing::info!("Shutting down metrics server...");
         })
         .await?;
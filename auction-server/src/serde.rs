@@ -24,6 +24,40 @@ pub mod u256 {
         U256::from_dec_str(s.as_str()).map_err(|err| D::Error::custom(err.to_string()))
     }
 }
+pub mod nullable_u256 {
+    use {
+        ethers::types::U256,
+        serde::{
+            de::Error,
+            Deserialize,
+            Deserializer,
+            Serializer,
+        },
+    };
+
+    pub fn serialize<S>(b: &Option<U256>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match b {
+            Some(b) => s.serialize_str(b.to_string().as_str()),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Deserialize::deserialize(d)?;
+        match s {
+            Some(s) => U256::from_dec_str(s.as_str())
+                .map(Some)
+                .map_err(|err| D::Error::custom(err.to_string())),
+            None => Ok(None),
+        }
+    }
+}
 pub mod signature {
     use {
         ethers::types::Signature,
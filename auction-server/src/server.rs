@@ -6,37 +6,56 @@ use {
         },
         auction::{
             get_express_relay_contract,
+            run_auction_lock_sweep_loop,
+            run_deferred_bid_persistence_loop,
+            run_rejected_bid_cleanup_loop,
+            run_store_size_metrics_loop,
             run_submission_loop_evm,
             run_submission_loop_svm,
             run_tracker_loop,
         },
+        bid_queue::{
+            run_bid_queue_workers,
+            BidQueue,
+        },
         config::{
             ChainId,
             Config,
             ConfigEvm,
             ConfigMap,
+            RetryPolicyConfig,
             RunOptions,
         },
+        grpc,
         models,
         opportunity_adapter::{
             get_adapter_bytecode_hash,
             get_permit2_address,
             get_weth_address,
+            run_opportunity_expiry_loop,
             run_verification_loop,
         },
+        opportunity_publisher::start_opportunity_publisher,
         per_metrics,
         state::{
             ChainStoreEvm,
             ChainStoreSvm,
             ExpressRelaySvm,
             OpportunityStore,
+            Relayer,
+            RelayerPool,
             Store,
         },
         traced_client::TracedClient,
+        usage::{
+            run_usage_flush_loop,
+            UsageStore,
+        },
     },
     anyhow::anyhow,
     axum_prometheus::{
         metrics_exporter_prometheus::{
+            Matcher,
             PrometheusBuilder,
             PrometheusHandle,
         },
@@ -59,6 +78,7 @@ use {
         future::join_all,
         Future,
     },
+    rand::Rng,
     solana_client::nonblocking::rpc_client::RpcClient,
     solana_sdk::{
         commitment_config::CommitmentConfig,
@@ -79,48 +99,120 @@ use {
             },
             Arc,
         },
-        time::Duration,
+        time::{
+            Duration,
+            Instant,
+        },
     },
     tokio::{
-        sync::RwLock,
+        sync::{
+            broadcast,
+            mpsc,
+            Mutex,
+            RwLock,
+            Semaphore,
+        },
         time::sleep,
     },
     tokio_util::task::TaskTracker,
 };
 
-async fn fault_tolerant_handler<F, Fut>(name: String, f: F)
-where
+/// Backoff policy for restarting the failed background tasks.
+#[derive(Clone, Copy, Debug)]
+struct RetryPolicy {
+    initial_delay: Duration,
+    max_delay:     Duration,
+    multiplier:    f64,
+    max_attempts:  Option<u32>,
+}
+
+impl RetryPolicy {
+    fn new(run_options: &RunOptions, overrides: &RetryPolicyConfig) -> Self {
+        let options = &run_options.retry;
+        Self {
+            initial_delay: Duration::from_millis(
+                overrides
+                    .initial_delay_ms
+                    .unwrap_or(options.initial_delay_ms),
+            ),
+            max_delay:     Duration::from_millis(
+                overrides.max_delay_ms.unwrap_or(options.max_delay_ms),
+            ),
+            multiplier:    overrides.multiplier.unwrap_or(options.multiplier).max(1.0),
+            max_attempts:  overrides.max_attempts.or(options.max_attempts),
+        }
+    }
+
+    /// Returns the delay before the next restart after the given number of consecutive failures.
+    /// Half of the delay is randomized, so the tasks failing together do not restart together.
+    fn get_delay(&self, failures: u32) -> Duration {
+        // Compare as floats, as the exponential growth overflows a duration quickly
+        let delay = (self.initial_delay.as_secs_f64()
+            * self.multiplier.powi(failures.saturating_sub(1) as i32))
+        .min(self.max_delay.as_secs_f64());
+        Duration::from_secs_f64(delay * rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+async fn fault_tolerant_handler<F, Fut>(
+    name: String,
+    shutdown: &ShutdownSignal,
+    retry_policy: RetryPolicy,
+    f: F,
+) where
     F: Fn() -> Fut,
     Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
     Fut::Output: Send + 'static,
 {
+    let mut failures = 0;
     loop {
+        let start_time = Instant::now();
         let res = tokio::spawn(f()).await;
         match res {
             Ok(result) => match result {
                 Ok(_) => break, // This will happen on graceful shutdown
                 Err(err) => {
                     tracing::error!("{} returned error: {:?}", name, err);
-                    sleep(Duration::from_millis(500)).await;
+                    // A task which ran longer than the maximum delay was healthy, so its failures start over
+                    if start_time.elapsed() > retry_policy.max_delay {
+                        failures = 0;
+                    }
+                    failures += 1;
+                    if retry_policy
+                        .max_attempts
+                        .is_some_and(|max_attempts| failures >= max_attempts)
+                    {
+                        tracing::error!(
+                            "{} failed {} times in a row, shutting down the server",
+                            name,
+                            failures
+                        );
+                        shutdown.trigger();
+                        break;
+                    }
+                    if SHOULD_EXIT.load(Ordering::Acquire) {
+                        break;
+                    }
+                    sleep(retry_policy.get_delay(failures)).await;
                 }
             },
             Err(err) => {
                 tracing::error!("{} is panicked or canceled: {:?}", name, err);
-                SHOULD_EXIT.store(true, Ordering::Release);
+                shutdown.trigger();
                 break;
             }
         }
     }
 }
 
-async fn fetch_access_tokens(db: &PgPool) -> HashMap<models::AccessTokenToken, models::Profile> {
-    let access_tokens = sqlx::query_as!(
-        models::AccessToken,
-        "SELECT * FROM access_token WHERE revoked_at IS NULL",
-    )
-    .fetch_all(db)
-    .await
-    .expect("Failed to fetch access tokens from database");
+async fn fetch_access_tokens(
+    db: &PgPool,
+) -> HashMap<models::AccessTokenToken, (models::Profile, Vec<models::AccessTokenScope>)> {
+    let access_tokens: Vec<models::AccessToken> =
+        sqlx::query_as("SELECT * FROM access_token WHERE revoked_at IS NULL")
+            .fetch_all(db)
+            .await
+            .expect("Failed to fetch access tokens from database");
     let profile_ids: Vec<models::ProfileId> =
         access_tokens.iter().map(|token| token.profile_id).collect();
     let profiles: Vec<models::Profile> = sqlx::query_as("SELECT * FROM profile WHERE id = ANY($1)")
@@ -136,15 +228,30 @@ async fn fetch_access_tokens(db: &PgPool) -> HashMap<models::AccessTokenToken, m
                 .iter()
                 .find(|profile| profile.id == token.profile_id)
                 .expect("Profile not found");
-            (token.token, profile.clone())
+            (token.token, (profile.clone(), token.scopes))
         })
         .collect()
 }
 
+/// Buckets for the time bids spend in each status, which spans from a block to several minutes.
+const BID_STATUS_DURATION_BUCKETS: &[f64] = &[
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
 pub fn setup_metrics_recorder() -> anyhow::Result<PrometheusHandle> {
     PrometheusBuilder::new()
         .set_buckets(SECONDS_DURATION_BUCKETS)
         .unwrap()
+        .set_buckets_for_metric(
+            Matcher::Full("bid_pending_duration_seconds".to_string()),
+            BID_STATUS_DURATION_BUCKETS,
+        )
+        .unwrap()
+        .set_buckets_for_metric(
+            Matcher::Full("bid_submitted_duration_seconds".to_string()),
+            BID_STATUS_DURATION_BUCKETS,
+        )
+        .unwrap()
         .install_recorder()
         .map_err(|err| anyhow!("Failed to set up metrics recorder: {:?}", err))
 }
@@ -183,6 +290,36 @@ async fn setup_chain_store(
                     let (chain_id, chain_config, wallet) =
                         (chain_id.clone(), chain_config.clone(), wallet.clone());
                     Some(async move {
+                        let wallet = match &chain_config.relayer_private_key {
+                            Some(private_key) => {
+                                private_key.parse::<LocalWallet>().map_err(|err| {
+                                    anyhow!(
+                                        "Failed to parse relayer private key for chain({chain_id}): {:?}",
+                                        err,
+                                        chain_id = chain_id
+                                    )
+                                })?
+                            }
+                            None => wallet,
+                        };
+                        let mut wallets = vec![wallet];
+                        for private_key in chain_config.extra_relayer_private_keys.iter() {
+                            let wallet = private_key.parse::<LocalWallet>().map_err(|err| {
+                                anyhow!(
+                                    "Failed to parse extra relayer private key for chain({chain_id}): {:?}",
+                                    err,
+                                    chain_id = chain_id
+                                )
+                            })?;
+                            if !wallets.iter().any(|w| w.address() == wallet.address()) {
+                                wallets.push(wallet);
+                            }
+                        }
+                        tracing::info!(
+                            "Using wallet addresses {:?} for chain {}",
+                            wallets.iter().map(|w| w.address()).collect::<Vec<_>>(),
+                            chain_id
+                        );
                         let provider = get_chain_provider(&chain_id, &chain_config)?;
 
                         let id = provider.get_chainid().await?.as_u64();
@@ -191,13 +328,18 @@ async fn setup_chain_store(
                             .await?
                             .expect("Failed to get latest block");
 
-                        let express_relay_contract = get_express_relay_contract(
-                            chain_config.express_relay_contract,
-                            provider.clone(),
-                            wallet.clone(),
-                            chain_config.legacy_tx,
-                            id,
-                        );
+                        let relayers = wallets
+                            .into_iter()
+                            .map(|wallet| Relayer {
+                                express_relay_contract: Arc::new(get_express_relay_contract(
+                                    &chain_config,
+                                    provider.clone(),
+                                    wallet.clone(),
+                                    id,
+                                )),
+                                wallet,
+                            })
+                            .collect();
                         let permit2 = get_permit2_address(
                             chain_config.adapter_factory_contract,
                             provider.clone(),
@@ -225,8 +367,14 @@ async fn setup_chain_store(
                                 permit2,
                                 weth,
                                 adapter_bytecode_hash,
-                                express_relay_contract: Arc::new(express_relay_contract),
-                                block_gas_limit: block.gas_limit,
+                                block_gas_limit: RwLock::new(block.gas_limit),
+                                relayers: RelayerPool::new(relayers),
+                                target_contract_allowlist: RwLock::new(
+                                    chain_config.target_contract_allowlist.iter().cloned().collect(),
+                                ),
+                                simulation_semaphore: chain_config
+                                    .max_concurrent_simulations
+                                    .map(Semaphore::new),
                             },
                         ))
                     })
@@ -238,13 +386,16 @@ async fn setup_chain_store(
     .collect()
 }
 
-const NOTIFICATIONS_CHAN_LEN: usize = 1000;
 pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
-    tokio::spawn(async move {
-        tracing::info!("Registered shutdown signal handler...");
-        tokio::signal::ctrl_c().await.unwrap();
-        tracing::info!("Shut down signal received, waiting for tasks...");
-        SHOULD_EXIT.store(true, Ordering::Release);
+    let shutdown = ShutdownSignal::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            tracing::info!("Registered shutdown signal handler...");
+            tokio::signal::ctrl_c().await.unwrap();
+            tracing::info!("Shut down signal received, waiting for tasks...");
+            shutdown.trigger();
+        }
     });
 
     let config_map = ConfigMap::load(&run_options.config.config).map_err(|err| {
@@ -258,15 +409,69 @@ pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
     let wallet = run_options.subwallet_private_key.parse::<LocalWallet>()?;
     tracing::info!("Using wallet address: {:?}", wallet.address());
 
-    let chains = setup_chain_store(config_map.clone(), wallet.clone()).await?;
+    let chains = setup_chain_store(config_map.clone(), wallet).await?;
+    let submission_semaphores = config_map
+        .chains
+        .iter()
+        .filter_map(|(chain_id, config)| {
+            config
+                .max_concurrent_submissions()
+                .map(|limit| (chain_id.clone(), Arc::new(Semaphore::new(limit))))
+        })
+        .collect();
+    let max_submitted_auctions = config_map
+        .chains
+        .iter()
+        .map(|(chain_id, config)| {
+            (
+                chain_id.clone(),
+                config
+                    .max_submitted_auctions()
+                    .unwrap_or(run_options.auction.max_submitted_auctions),
+            )
+        })
+        .collect();
+
+    let default_retry_policy = RetryPolicy::new(&run_options, &RetryPolicyConfig::default());
+    let submission_retry_policy =
+        RetryPolicy::new(&run_options, &config_map.retry_policies.submission);
+    let tracker_retry_policy = RetryPolicy::new(&run_options, &config_map.retry_policies.tracker);
+    let verification_retry_policy =
+        RetryPolicy::new(&run_options, &config_map.retry_policies.verification);
 
     let (chains_svm, express_relay_svm) = setup_svm(&run_options, config_map)?;
 
+    if run_options.ws.broadcast_channel_capacity == 0 {
+        return Err(anyhow!(
+            "WebSocket broadcast channel capacity should be positive"
+        ));
+    }
     let (broadcast_sender, broadcast_receiver) =
-        tokio::sync::broadcast::channel(NOTIFICATIONS_CHAN_LEN);
+        tokio::sync::broadcast::channel(run_options.ws.broadcast_channel_capacity);
 
+    let database_options = &run_options.database;
+    if database_options.min_connections > database_options.max_connections {
+        return Err(anyhow!(
+            "Minimum database connections ({}) should not exceed the maximum ({})",
+            database_options.min_connections,
+            database_options.max_connections
+        ));
+    }
+    tracing::info!(
+        max_connections = database_options.max_connections,
+        min_connections = database_options.min_connections,
+        acquire_timeout = database_options.acquire_timeout,
+        idle_timeout = database_options.idle_timeout,
+        "Connecting to the database..."
+    );
     let pool = PgPoolOptions::new()
-        .max_connections(10)
+        .max_connections(database_options.max_connections)
+        .min_connections(database_options.min_connections)
+        .acquire_timeout(Duration::from_secs(database_options.acquire_timeout))
+        .idle_timeout(
+            (database_options.idle_timeout > 0)
+                .then(|| Duration::from_secs(database_options.idle_timeout)),
+        )
         .connect(&run_options.server.database_url)
         .await
         .expect("Server should start with a valid database connection.");
@@ -287,6 +492,9 @@ pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
     let task_tracker = TaskTracker::new();
 
     let access_tokens = fetch_access_tokens(&pool).await;
+    let (opportunity_event_sender, opportunity_event_receiver) =
+        mpsc::channel(run_options.opportunity_publisher.buffer_size.max(1));
+    let opportunity_event_receiver = Arc::new(Mutex::new(opportunity_event_receiver));
     let store = Arc::new(Store {
         db: pool,
         bids: Default::default(),
@@ -294,27 +502,76 @@ pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
         chains_svm,
         opportunity_store: OpportunityStore::default(),
         event_sender: broadcast_sender.clone(),
-        relayer: wallet,
         ws: ws::WsState {
             subscriber_counter: AtomicUsize::new(0),
             broadcast_sender,
             broadcast_receiver,
+            emit_opportunity_removals: run_options.ws.emit_opportunity_removals,
+            dedup_bid_status: run_options.ws.dedup_bid_status,
+            reveal_winning_amounts: run_options.ws.reveal_winning_amounts,
         },
         task_tracker: task_tracker.clone(),
         auction_lock: Default::default(),
+        held_auction_locks: Default::default(),
         submitted_auctions: Default::default(),
+        last_auction_conclusions: Default::default(),
+        stalled_chains: Default::default(),
         secret_key: run_options.secret_key.clone(),
         access_tokens: RwLock::new(access_tokens),
         metrics_recorder: setup_metrics_recorder()?,
         express_relay_svm,
+        block_timings: Default::default(),
+        admin_audit: run_options.server.admin_audit,
+        max_submitted_auctions,
+        tracker_stall_timeout: (run_options.auction.tracker_stall_timeout > 0)
+            .then(|| Duration::from_secs(run_options.auction.tracker_stall_timeout)),
+        auction_lock_timeout: Duration::from_secs(run_options.auction.auction_lock_timeout),
+        permission_key_chain_check: run_options.auction.permission_key_chain_check,
+        late_bid_policy: run_options.auction.late_bid_policy,
+        bid_status_reorder_retries: run_options.auction.bid_status_reorder_retries,
+        bid_status_reorder_delay: Duration::from_millis(
+            run_options.auction.bid_status_reorder_delay_ms,
+        ),
+        submission_semaphores,
+        opportunity_publisher: run_options
+            .opportunity_publisher
+            .url
+            .as_ref()
+            .map(|_| opportunity_event_sender),
+        usage: run_options.usage.metering.then(UsageStore::default),
+        bid_queue: BidQueue::new(
+            run_options.bid_queue.lane_capacity,
+            run_options.bid_queue.workers.get(),
+        ),
+        persist_rejected_bids: run_options.auction.persist_rejected_bids,
+        rejected_bid_retention: Duration::from_secs(
+            run_options.auction.rejected_bid_retention * 24 * 60 * 60,
+        ),
+        bid_expiry: run_options.auction.bid_expiry.map(Duration::from_secs),
+        deferred_bids: run_options
+            .auction
+            .defer_bid_persistence
+            .then(|| Mutex::new(HashMap::new())),
+        max_deferred_bids: run_options.auction.max_deferred_bids,
     });
 
+    store.restore_bids_from_db().await?;
+    store.load_token_spoof_info().await?;
+
     tokio::join!(
         async {
             let submission_loops = store.chains.keys().map(|chain_id| {
                 fault_tolerant_handler(
                     format!("submission loop for evm chain {}", chain_id.clone()),
-                    || run_submission_loop_evm(store.clone(), chain_id.clone()),
+                    &shutdown,
+                    submission_retry_policy,
+                    || {
+                        run_submission_loop_evm(
+                            store.clone(),
+                            chain_id.clone(),
+                            shutdown.subscribe(),
+                        )
+                    },
                 )
             });
             join_all(submission_loops).await;
@@ -323,7 +580,15 @@ pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
             let submission_loops = store.chains_svm.keys().map(|chain_id| {
                 fault_tolerant_handler(
                     format!("submission loop for svm chain {}", chain_id.clone()),
-                    || run_submission_loop_svm(store.clone(), chain_id.clone()),
+                    &shutdown,
+                    submission_retry_policy,
+                    || {
+                        run_submission_loop_svm(
+                            store.clone(),
+                            chain_id.clone(),
+                            shutdown.subscribe(),
+                        )
+                    },
                 )
             });
             join_all(submission_loops).await;
@@ -332,22 +597,89 @@ pub async fn start_server(run_options: RunOptions) -> anyhow::Result<()> {
             let tracker_loops = store.chains.keys().map(|chain_id| {
                 fault_tolerant_handler(
                     format!("tracker loop for chain {}", chain_id.clone()),
-                    || run_tracker_loop(store.clone(), chain_id.clone()),
+                    &shutdown,
+                    tracker_retry_policy,
+                    || run_tracker_loop(store.clone(), chain_id.clone(), shutdown.subscribe()),
                 )
             });
             join_all(tracker_loops).await;
         },
-        fault_tolerant_handler("verification loop".to_string(), || run_verification_loop(
-            store.clone()
-        )),
-        fault_tolerant_handler("start api".to_string(), || api::start_api(
-            run_options.clone(),
-            store.clone()
-        )),
-        fault_tolerant_handler("start metrics".to_string(), || per_metrics::start_metrics(
-            run_options.clone(),
-            store.clone()
-        )),
+        fault_tolerant_handler(
+            "verification loop".to_string(),
+            &shutdown,
+            verification_retry_policy,
+            || { run_verification_loop(store.clone(), shutdown.subscribe()) }
+        ),
+        fault_tolerant_handler(
+            "opportunity expiry loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_opportunity_expiry_loop(store.clone(), shutdown.subscribe()) }
+        ),
+        fault_tolerant_handler(
+            "start api".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || api::start_api(run_options.clone(), store.clone(), shutdown.subscribe())
+        ),
+        fault_tolerant_handler(
+            "start metrics".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || {
+                per_metrics::start_metrics(run_options.clone(), store.clone(), shutdown.subscribe())
+            }
+        ),
+        fault_tolerant_handler(
+            "start grpc".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || grpc::start_grpc(run_options.clone(), store.clone(), shutdown.clone())
+        ),
+        fault_tolerant_handler(
+            "start opportunity publisher".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || {
+                start_opportunity_publisher(run_options.clone(), opportunity_event_receiver.clone())
+            }
+        ),
+        fault_tolerant_handler(
+            "rejected bid cleanup loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_rejected_bid_cleanup_loop(store.clone()) }
+        ),
+        fault_tolerant_handler(
+            "auction lock sweep loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_auction_lock_sweep_loop(store.clone()) }
+        ),
+        fault_tolerant_handler(
+            "store size metrics loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_store_size_metrics_loop(store.clone()) }
+        ),
+        fault_tolerant_handler(
+            "deferred bid persistence loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_deferred_bid_persistence_loop(store.clone()) }
+        ),
+        fault_tolerant_handler(
+            "usage flush loop".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_usage_flush_loop(store.clone(), run_options.clone()) }
+        ),
+        fault_tolerant_handler(
+            "bid queue workers".to_string(),
+            &shutdown,
+            default_retry_policy,
+            || { run_bid_queue_workers(store.clone(), shutdown.subscribe()) }
+        ),
     );
 
     // To make sure all the spawned tasks will finish their job before shut down
@@ -375,13 +707,16 @@ fn setup_svm(
             .unwrap_or(Keypair::new().to_base58_string()),
     );
     let express_relay_svm = ExpressRelaySvm {
-        relayer:                     Arc::new(relayer),
-        permission_account_position: env!("SUBMIT_BID_PERMISSION_ACCOUNT_POSITION")
+        relayer:                         Arc::new(relayer),
+        permission_account_position:     env!("SUBMIT_BID_PERMISSION_ACCOUNT_POSITION")
             .parse::<usize>()
             .expect("Failed to parse permission account position"),
-        router_account_position:     env!("SUBMIT_BID_ROUTER_ACCOUNT_POSITION")
+        router_account_position:         env!("SUBMIT_BID_ROUTER_ACCOUNT_POSITION")
             .parse::<usize>()
             .expect("Failed to parse router account position"),
+        relayer_signer_account_position: env!("SUBMIT_BID_RELAYER_SIGNER_ACCOUNT_POSITION")
+            .parse::<usize>()
+            .expect("Failed to parse relayer signer account position"),
     };
     Ok((chains_svm, express_relay_svm))
 }
@@ -413,11 +748,46 @@ pub fn get_chain_provider(
     Ok(provider)
 }
 
-// A static exit flag to indicate to running threads that we're shutting down. This is used to
-// gracefully shutdown the application.
+// A static exit flag to indicate to running threads that we're shutting down.
 //
-// NOTE: A more idiomatic approach would be to use a tokio::sync::broadcast channel, and to send a
-// shutdown signal to all running tasks. However, this is a bit more complicated to implement and
-// we don't rely on global state for anything else.
+// NOTE: This flag is kept for the tasks which still poll it every EXIT_CHECK_INTERVAL. New tasks should
+// subscribe to the ShutdownSignal instead, which also sets this flag when triggered.
 pub(crate) static SHOULD_EXIT: AtomicBool = AtomicBool::new(false);
 pub const EXIT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Broadcasts the shutdown of the server to the running tasks, so they can exit as soon as it is triggered.
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    sender: broadcast::Sender<()>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(1);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> ShutdownReceiver {
+        ShutdownReceiver(self.sender.subscribe())
+    }
+
+    pub fn trigger(&self) {
+        SHOULD_EXIT.store(true, Ordering::Release);
+        // Sending only fails if there is no receiver, which means there is no task left to notify
+        let _ = self.sender.send(());
+    }
+}
+
+pub struct ShutdownReceiver(broadcast::Receiver<()>);
+
+impl ShutdownReceiver {
+    /// Waits for the shutdown signal. Returns immediately if the shutdown was triggered before subscribing,
+    /// which happens when a task is restarted during the shutdown.
+    pub async fn recv(&mut self) {
+        if SHOULD_EXIT.load(Ordering::Acquire) {
+            return;
+        }
+        // Both a closed and a lagged channel mean the shutdown was triggered
+        let _ = self.0.recv().await;
+    }
+}
@@ -1,42 +1,66 @@
 use {
     crate::{
         api::{
-            opportunity::OpportunityParamsWithMetadata,
+            opportunity::{
+                OpportunityParamsWithMetadata,
+                RemovedOpportunity,
+            },
             profile as ApiProfile,
             ws::{
+                BidStatusUpdateTarget,
                 UpdateEvent,
                 WsState,
             },
             Auth,
             RestError,
+            UnavailableReason,
         },
         auction::{
             ChainStore,
+            InclusionBlock,
             SignableExpressRelayContract,
         },
+        bid_queue::BidQueue,
         config::{
             ChainId,
+            Config,
             ConfigEvm,
+            ConfigMap,
             ConfigSvm,
+            LateBidPolicy,
+            PermissionKeyChainCheck,
+            RetryPoliciesConfig,
         },
         models,
+        opportunity_publisher::OpportunityEvent,
+        token_spoof,
         traced_client::TracedClient,
+        usage::UsageStore,
     },
     axum::Json,
-    axum_prometheus::metrics_exporter_prometheus::PrometheusHandle,
+    axum_prometheus::{
+        metrics,
+        metrics_exporter_prometheus::PrometheusHandle,
+    },
     base64::{
         engine::general_purpose::URL_SAFE_NO_PAD,
         Engine,
     },
     ethers::{
         providers::Provider,
-        signers::LocalWallet,
+        signers::{
+            LocalWallet,
+            Signer,
+        },
         types::{
             Address,
             Bytes,
+            H256,
             U256,
         },
+        utils::keccak256,
     },
+    futures::future::join_all,
     rand::Rng,
     serde::{
         Deserialize,
@@ -45,7 +69,10 @@ use {
     serde_json::json,
     solana_client::nonblocking::rpc_client::RpcClient,
     solana_sdk::{
-        signature::Keypair,
+        signature::{
+            Keypair,
+            Signature as SignatureSvm,
+        },
         transaction::VersionedTransaction,
     },
     sqlx::{
@@ -59,23 +86,49 @@ use {
             },
             BigDecimal,
         },
+        Acquire,
         Postgres,
         QueryBuilder,
+        Transaction,
         TypeInfo,
     },
     std::{
+        cmp::Ordering,
         collections::{
-            hash_map::Entry,
+            hash_map::{
+                DefaultHasher,
+                Entry,
+            },
             HashMap,
+            HashSet,
+        },
+        future::Future,
+        hash::{
+            Hash,
+            Hasher,
         },
         str::FromStr,
-        sync::Arc,
+        sync::{
+            atomic::{
+                AtomicUsize,
+                Ordering as AtomicOrdering,
+            },
+            Arc,
+            Mutex as StdMutex,
+        },
+        time::{
+            Duration,
+            Instant,
+        },
     },
     time::UtcOffset,
     tokio::sync::{
         broadcast,
+        mpsc,
         Mutex,
+        OwnedMutexGuard,
         RwLock,
+        Semaphore,
     },
     tokio_util::task::TaskTracker,
     utoipa::{
@@ -114,6 +167,14 @@ pub struct SimulatedBidCoreFields {
     /// The profile id for the bid owner.
     #[schema(example = "", value_type = String)]
     pub profile_id:      Option<models::ProfileId>,
+    /// The time until which the bid can be auctioned formatted in rfc3339, if set by the searcher.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = Option<String>)]
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub good_till:       Option<OffsetDateTime>,
 }
 
 #[derive(Clone, Debug, ToSchema, Serialize, Deserialize)]
@@ -242,15 +303,120 @@ pub enum OpportunityParams {
     V1(OpportunityParamsV1),
 }
 
+impl OpportunityParams {
+    /// Returns a deterministic hash of the content of the opportunity, which does not depend on the id
+    /// or the creation time of the opportunity.
+    pub fn content_hash(&self) -> H256 {
+        H256(keccak256(
+            serde_json::to_vec(self).expect("opportunity params are serializable"),
+        ))
+    }
+}
+
+/// Token amounts of an opportunity, which can be filled in multiple tranches.
+#[derive(Serialize, Deserialize, ToSchema, Clone, PartialEq, Debug)]
+pub struct OpportunityAmounts {
+    pub sell_tokens: Vec<TokenAmount>,
+    pub buy_tokens:  Vec<TokenAmount>,
+}
+
+impl From<&OpportunityParams> for OpportunityAmounts {
+    fn from(params: &OpportunityParams) -> Self {
+        let OpportunityParams::V1(params) = params;
+        OpportunityAmounts {
+            sell_tokens: params.sell_tokens.clone(),
+            buy_tokens:  params.buy_tokens.clone(),
+        }
+    }
+}
+
+impl OpportunityAmounts {
+    /// Returns true if no amount is left for any of the tokens.
+    pub fn is_consumed(&self) -> bool {
+        self.sell_tokens
+            .iter()
+            .chain(self.buy_tokens.iter())
+            .all(|token_amount| token_amount.amount.is_zero())
+    }
+
+    /// Subtracts the filled amount of each token from its remaining amount.
+    /// Fails if a filled token is not part of the opportunity or is filled over its remaining amount.
+    pub fn consume(&self, filled: &OpportunityAmounts) -> Result<Self, String> {
+        fn subtract(
+            remaining: &[TokenAmount],
+            filled: &[TokenAmount],
+        ) -> Result<Vec<TokenAmount>, String> {
+            let mut remaining = remaining.to_vec();
+            for fill in filled {
+                let token_amount = remaining
+                    .iter_mut()
+                    .find(|token_amount| token_amount.token == fill.token)
+                    .ok_or_else(|| {
+                        format!("Token {:?} is not part of the opportunity", fill.token)
+                    })?;
+                token_amount.amount =
+                    token_amount
+                        .amount
+                        .checked_sub(fill.amount)
+                        .ok_or_else(|| {
+                            format!(
+                                "Filled amount {} of token {:?} exceeds the remaining amount {}",
+                                fill.amount, fill.token, token_amount.amount
+                            )
+                        })?;
+            }
+            Ok(remaining)
+        }
+        Ok(OpportunityAmounts {
+            sell_tokens: subtract(&self.sell_tokens, &filled.sell_tokens)?,
+            buy_tokens:  subtract(&self.buy_tokens, &filled.buy_tokens)?,
+        })
+    }
+}
+
 pub type OpportunityId = Uuid;
 pub type AuctionKey = (PermissionKey, ChainId);
 pub type AuctionLock = Arc<Mutex<()>>;
 
+/// Guard of an acquired auction lock. The lock is tracked as held until the guard is dropped,
+/// so the sweep can find the locks which are never released.
+pub struct AuctionLockGuard<'a> {
+    _guard:     OwnedMutexGuard<()>,
+    key:        AuctionKey,
+    held_locks: &'a StdMutex<HashMap<AuctionKey, Instant>>,
+}
+
+impl Drop for AuctionLockGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut held_locks) = self.held_locks.lock() {
+            held_locks.remove(&self.key);
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Opportunity {
     pub id:            OpportunityId,
     pub creation_time: UnixTimestampMicros,
     pub params:        OpportunityParams,
+    /// The token amounts which are not filled yet.
+    pub remaining:     OpportunityAmounts,
+}
+
+impl Opportunity {
+    /// Creates an opportunity which is not filled yet.
+    pub fn new(
+        id: OpportunityId,
+        creation_time: UnixTimestampMicros,
+        params: OpportunityParams,
+    ) -> Self {
+        Self {
+            id,
+            creation_time,
+            remaining: (&params).into(),
+            params,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -263,16 +429,70 @@ pub enum SpoofInfo {
 }
 
 pub struct ChainStoreEvm {
-    pub chain_id_num:           u64,
-    pub provider:               Provider<TracedClient>,
-    pub network_id:             u64,
-    pub config:                 ConfigEvm,
-    pub permit2:                Address,
-    pub adapter_bytecode_hash:  [u8; 32],
-    pub weth:                   Address,
-    pub token_spoof_info:       RwLock<HashMap<Address, SpoofInfo>>,
+    pub chain_id_num:              u64,
+    pub provider:                  Provider<TracedClient>,
+    pub network_id:                u64,
+    pub config:                    ConfigEvm,
+    pub permit2:                   Address,
+    pub adapter_bytecode_hash:     [u8; 32],
+    pub weth:                      Address,
+    pub token_spoof_info:          RwLock<HashMap<Address, SpoofInfo>>,
+    /// Gas limit of the latest block, refreshed periodically by the tracker loop.
+    pub block_gas_limit:           RwLock<U256>,
+    pub relayers:                  RelayerPool,
+    /// Target contracts which bids are allowed to call. All contracts are allowed if empty.
+    pub target_contract_allowlist: RwLock<HashSet<Address>>,
+    /// Limits the concurrent simulations if the chain is configured with a limit.
+    pub simulation_semaphore:      Option<Semaphore>,
+}
+
+pub struct Relayer {
+    pub wallet:                 LocalWallet,
+    /// The express relay contract signing with the wallet of the relayer.
     pub express_relay_contract: Arc<SignableExpressRelayContract>,
-    pub block_gas_limit:        U256,
+}
+
+impl Relayer {
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+}
+
+/// Relayers which can submit the auctions of a chain. Each relayer signs with its own nonces,
+/// so the auctions sent by different relayers do not wait for each other to be mined.
+pub struct RelayerPool {
+    relayers: Vec<Relayer>,
+    next:     AtomicUsize,
+}
+
+impl RelayerPool {
+    /// Creates a pool from the relayers, which should not be empty.
+    pub fn new(relayers: Vec<Relayer>) -> Self {
+        assert!(!relayers.is_empty(), "Relayer pool should not be empty");
+        Self {
+            relayers,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The first relayer of the pool, which is used as the sender of the simulations.
+    pub fn primary(&self) -> &Relayer {
+        &self.relayers[0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Relayer> {
+        self.relayers.iter()
+    }
+
+    /// Picks the relayer with the fewest submitted auctions which are not concluded yet.
+    /// Relayers with the same number of auctions in flight are picked in turns.
+    pub fn select(&self, in_flight: &HashMap<Address, usize>) -> &Relayer {
+        let start = self.next.fetch_add(1, AtomicOrdering::Relaxed);
+        (0..self.relayers.len())
+            .map(|offset| &self.relayers[(start + offset) % self.relayers.len()])
+            .min_by_key(|relayer| in_flight.get(&relayer.address()).copied().unwrap_or(0))
+            .expect("Relayer pool should not be empty")
+    }
 }
 
 pub struct ChainStoreSvm {
@@ -280,28 +500,269 @@ pub struct ChainStoreSvm {
     pub config: ConfigSvm,
 }
 
-#[derive(Default)]
+type OpportunityShard = RwLock<HashMap<PermissionKey, Vec<Opportunity>>>;
+
+const OPPORTUNITY_STORE_SHARDS: usize = 16;
+const DEFAULT_BIDS_PAGE_LIMIT: usize = 20;
+const MAX_BIDS_PAGE_LIMIT: usize = 100;
+
+/// Opportunities are sharded by their permission key so the updates for different permission keys
+/// do not block each other. All the opportunities of a permission key always live in the same shard,
+/// so updates for a single permission key are serialized as before.
 pub struct OpportunityStore {
-    pub opportunities: RwLock<HashMap<PermissionKey, Vec<Opportunity>>>,
+    shards: Vec<OpportunityShard>,
+}
+
+impl Default for OpportunityStore {
+    fn default() -> Self {
+        Self {
+            shards: (0..OPPORTUNITY_STORE_SHARDS)
+                .map(|_| Default::default())
+                .collect(),
+        }
+    }
 }
 
 impl OpportunityStore {
-    pub async fn add_opportunity(&self, opportunity: Opportunity) {
-        let key = match &opportunity.params {
+    fn get_shard(&self, key: &PermissionKey) -> &OpportunityShard {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[(hasher.finish() as usize) % self.shards.len()]
+    }
+
+    fn get_key(opportunity: &Opportunity) -> PermissionKey {
+        match &opportunity.params {
             OpportunityParams::V1(params) => params.permission_key.clone(),
-        };
-        self.opportunities
+        }
+    }
+
+    pub async fn add_opportunity(&self, opportunity: Opportunity) {
+        let key = Self::get_key(&opportunity);
+        self.get_shard(&key)
             .write()
             .await
             .entry(key)
             .or_insert_with(Vec::new)
             .push(opportunity);
     }
+
+    pub async fn remove_opportunity(&self, opportunity: &Opportunity) {
+        let key = Self::get_key(opportunity);
+        let mut write_guard = self.get_shard(&key).write().await;
+        let entry = write_guard.entry(key.clone());
+        if entry
+            .and_modify(|opps| opps.retain(|o| o.id != opportunity.id))
+            .or_default()
+            .is_empty()
+        {
+            write_guard.remove(&key);
+        }
+    }
+
+    /// Removes the opportunities which match the predicate and returns them.
+    pub async fn remove_matching<F>(&self, predicate: F) -> Vec<Opportunity>
+    where
+        F: Fn(&Opportunity) -> bool,
+    {
+        let mut removed = vec![];
+        for shard in self.shards.iter() {
+            let mut write_guard = shard.write().await;
+            write_guard.retain(|_, opps| {
+                opps.retain(|opp| {
+                    if predicate(opp) {
+                        removed.push(opp.clone());
+                        false
+                    } else {
+                        true
+                    }
+                });
+                !opps.is_empty()
+            });
+        }
+        removed
+    }
+
+    /// Refreshes the creation time of the stored opportunity with the same content as the given one, if any.
+    /// Returns the refreshed opportunity.
+    pub async fn refresh_duplicate(&self, opportunity: &Opportunity) -> Option<Opportunity> {
+        let key = Self::get_key(opportunity);
+        let content_hash = opportunity.params.content_hash();
+        let mut write_guard = self.get_shard(&key).write().await;
+        let duplicate = write_guard
+            .get_mut(&key)?
+            .iter_mut()
+            .find(|opp| opp.params.content_hash() == content_hash)?;
+        duplicate.creation_time = opportunity.creation_time;
+        Some(duplicate.clone())
+    }
+
+    /// Returns the permission key of the live opportunity with the id, if any.
+    pub async fn find_key(&self, id: OpportunityId) -> Option<PermissionKey> {
+        for shard in self.shards.iter() {
+            let read_guard = shard.read().await;
+            if let Some(key) = read_guard
+                .iter()
+                .find(|(_, opps)| opps.iter().any(|opp| opp.id == id))
+                .map(|(key, _)| key.clone())
+            {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Subtracts the filled amounts from the remaining amounts of the opportunity and removes it once it is fully
+    /// consumed. The updated opportunity is persisted with the write lock of the permission key held, so concurrent
+    /// fills can not consume the same amounts twice and are persisted in order. The stored opportunity is only
+    /// updated once it is persisted. Returns the updated opportunity, or None if it is not live anymore.
+    pub async fn consume<F, Fut>(
+        &self,
+        key: &PermissionKey,
+        id: OpportunityId,
+        filled: &OpportunityAmounts,
+        persist: F,
+    ) -> Result<Option<Opportunity>, RestError>
+    where
+        F: FnOnce(Opportunity) -> Fut,
+        Fut: Future<Output = Result<(), RestError>>,
+    {
+        let mut write_guard = self.get_shard(key).write().await;
+        let Some(opps) = write_guard.get_mut(key) else {
+            return Ok(None);
+        };
+        let Some(index) = opps.iter().position(|opp| opp.id == id) else {
+            return Ok(None);
+        };
+        let opportunity = Opportunity {
+            remaining: opps[index]
+                .remaining
+                .consume(filled)
+                .map_err(RestError::BadParameters)?,
+            ..opps[index].clone()
+        };
+        persist(opportunity.clone()).await?;
+        if opportunity.remaining.is_consumed() {
+            opps.remove(index);
+        } else {
+            opps[index] = opportunity.clone();
+        }
+        if opps.is_empty() {
+            write_guard.remove(key);
+        }
+        Ok(Some(opportunity))
+    }
+
+    pub async fn get_opportunities(&self, key: &PermissionKey) -> Option<Vec<Opportunity>> {
+        self.get_shard(key).read().await.get(key).cloned()
+    }
+
+    /// Returns a snapshot of all the opportunities grouped by their permission key.
+    ///
+    /// The snapshot is consistent per permission key, but not across different permission keys.
+    pub async fn get_all_opportunities(&self) -> HashMap<PermissionKey, Vec<Opportunity>> {
+        let mut opportunities = HashMap::new();
+        for shard in self.shards.iter() {
+            opportunities.extend(
+                shard
+                    .read()
+                    .await
+                    .iter()
+                    .map(|(key, opps)| (key.clone(), opps.clone())),
+            );
+        }
+        opportunities
+    }
+
+    /// Returns the number of the opportunities of each chain which has any.
+    pub async fn count_by_chain(&self) -> HashMap<ChainId, usize> {
+        let mut counts: HashMap<ChainId, usize> = HashMap::new();
+        for shard in self.shards.iter() {
+            for opportunity in shard.read().await.values().flatten() {
+                let chain_id = match &opportunity.params {
+                    OpportunityParams::V1(params) => &params.chain_id,
+                };
+                *counts.entry(chain_id.clone()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    pub async fn permission_keys_count(&self) -> usize {
+        let mut count = 0;
+        for shard in self.shards.iter() {
+            count += shard.read().await.len();
+        }
+        count
+    }
 }
 
 pub type BidId = Uuid;
 
-// TODO update result type for evm and svm
+/// Hash of the transaction of an auction, which is the transaction hash on EVM chains and the transaction signature
+/// on SVM chains. Serialized as a 0x prefixed hex string on EVM chains and as a base58 string on SVM chains.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TxHash {
+    Evm(H256),
+    Svm(SignatureSvm),
+}
+
+impl TxHash {
+    /// Parses a stored transaction hash, which must have the length of the hashes of the chain type.
+    pub fn from_bytes(chain_type: &models::ChainType, bytes: &[u8]) -> anyhow::Result<Self> {
+        match chain_type {
+            models::ChainType::Evm if bytes.len() == H256::len_bytes() => {
+                Ok(TxHash::Evm(H256::from_slice(bytes)))
+            }
+            models::ChainType::Evm => Err(anyhow::anyhow!(
+                "Invalid evm transaction hash length: {}",
+                bytes.len()
+            )),
+            models::ChainType::Svm => {
+                SignatureSvm::try_from(bytes).map(TxHash::Svm).map_err(|_| {
+                    anyhow::anyhow!("Invalid svm transaction signature length: {}", bytes.len())
+                })
+            }
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            TxHash::Evm(hash) => hash.0.to_vec(),
+            TxHash::Svm(signature) => signature.as_ref().to_vec(),
+        }
+    }
+}
+
+impl Serialize for TxHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            TxHash::Evm(hash) => serializer.serialize_str(&format!("{:?}", hash)),
+            TxHash::Svm(signature) => serializer.serialize_str(&signature.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TxHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if s.starts_with("0x") {
+            H256::from_str(&s)
+                .map(TxHash::Evm)
+                .map_err(|e| serde::de::Error::custom(e.to_string()))
+        } else {
+            SignatureSvm::from_str(&s)
+                .map(TxHash::Svm)
+                .map_err(|e| serde::de::Error::custom(e.to_string()))
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Clone, PartialEq, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BidStatus {
@@ -310,9 +771,8 @@ pub enum BidStatus {
     /// The bid is submitted to the chain, which is placed at the given index of the transaction with the given hash
     /// This state is temporary and will be updated to either lost or won after conclusion of the auction
     Submitted {
-        result: Vec<u8>,
-        // #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = String)]
-        // result: H256,
+        #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = String)]
+        result: TxHash,
         #[schema(example = 1, value_type = u32)]
         index:  u32,
     },
@@ -322,20 +782,58 @@ pub enum BidStatus {
     /// There are cases where the result is not None and the index is None.
     /// It is because other bids were selected for submission to the chain, but not this one.
     Lost {
-        result: Option<Vec<u8>>,
-        // #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = Option<String>)]
-        // result: Option<H256>,
+        #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = Option<String>)]
+        result: Option<TxHash>,
         #[schema(example = 1, value_type = Option<u32>)]
         index:  Option<u32>,
+        /// The reason the bid lost, if it is known. Only reported in the live status updates.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<BidLostReason>,
     },
     /// The bid won the auction, which is concluded with the transaction with the given hash and index
     Won {
-        result: Vec<u8>,
-        // #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = String)]
-        // result: H256,
+        #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = String)]
+        result: TxHash,
         #[schema(example = 1, value_type = u32)]
         index:  u32,
     },
+    /// The bid was never submitted to the chain and expired while pending in the auction
+    Expired,
+}
+
+/// An auction which is concluded, either on-chain by its submitted transaction or off-chain.
+#[derive(Serialize, ToSchema, Clone, Debug)]
+pub struct ConcludedAuction {
+    /// The unique id of the auction.
+    #[schema(example = "obo3ee3e-58cc-4372-a567-0e02b2c3d479", value_type = String)]
+    pub id:              models::AuctionId,
+    /// The chain id of the auction.
+    #[schema(example = "op_sepolia", value_type = String)]
+    pub chain_id:        ChainId,
+    /// The permission key of the auction.
+    #[schema(example = "0xdeadbeef", value_type = String)]
+    pub permission_key:  PermissionKey,
+    /// The transaction which concluded the auction. None if the auction was concluded off-chain.
+    #[schema(example = "0x103d4fbd777a36311b5161f2062490f761f25b67406badb2bace62bb170aa4e3", value_type = Option<String>)]
+    pub result:          Option<TxHash>,
+    /// Total amount of the winning bids in wei. Only included if revealing the winning amounts is enabled.
+    #[schema(example = "10", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winning_amount:  Option<String>,
+    /// The time the auction was concluded formatted in rfc3339.
+    #[schema(example = "2024-05-23T21:26:57.329954Z", value_type = String)]
+    #[serde(with = "time::serde::rfc3339")]
+    pub conclusion_time: OffsetDateTime,
+}
+
+/// The reason a bid lost the auction.
+#[derive(Serialize, Deserialize, ToSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BidLostReason {
+    /// The bid conflicts with the bids selected for submission, so it could not be bundled with them.
+    Conflict,
+    /// The bid won, but its transaction was dropped from the chain by a reorg.
+    Reorg,
 }
 
 impl sqlx::Encode<'_, sqlx::Postgres> for BidStatus {
@@ -349,11 +847,13 @@ impl sqlx::Encode<'_, sqlx::Postgres> for BidStatus {
             BidStatus::Lost {
                 result: _,
                 index: _,
+                reason: _,
             } => "lost",
             BidStatus::Won {
                 result: _,
                 index: _,
             } => "won",
+            BidStatus::Expired => "expired",
         };
         <&str as sqlx::Encode<sqlx::Postgres>>::encode(result, buf)
     }
@@ -376,29 +876,301 @@ pub struct BidStatusWithId {
     pub bid_status: BidStatus,
 }
 
+/// Orders the pending bids of an auction from the best to the worst by their amount.
+/// Earlier bids come first between bids with the same amount.
+fn compare_pending_bids(a: &SimulatedBidCoreFields, b: &SimulatedBidCoreFields) -> Ordering {
+    b.bid_amount
+        .cmp(&a.bid_amount)
+        .then(a.initiation_time.cmp(&b.initiation_time))
+}
+
+/// Returns the best pending bid amount for each permission key of the chain, sorted from the most valuable.
+/// Keys with the same best amount are sorted from the one with the most recent pending bid, so stale keys
+/// do not get ahead of the active ones.
+fn rank_permission_keys(
+    bids: &HashMap<AuctionKey, Vec<SimulatedBid>>,
+    chain_id: &ChainId,
+) -> Vec<(PermissionKey, BidAmount)> {
+    let mut permission_keys: Vec<(PermissionKey, BidAmount, Option<OffsetDateTime>)> = bids
+        .iter()
+        .filter(|((_, c), _)| c == chain_id)
+        .map(|((permission_key, _), bids)| {
+            let pending_bids = bids
+                .iter()
+                .map(|bid| bid.get_core_fields())
+                .filter(|core_fields| core_fields.status == BidStatus::Pending);
+            let best_amount = pending_bids
+                .clone()
+                .map(|core_fields| core_fields.bid_amount)
+                .max()
+                .unwrap_or_default();
+            let latest_time = pending_bids
+                .map(|core_fields| core_fields.initiation_time)
+                .max();
+            (permission_key.clone(), best_amount, latest_time)
+        })
+        .collect();
+    permission_keys.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+    permission_keys
+        .into_iter()
+        .map(|(permission_key, best_amount, _)| (permission_key, best_amount))
+        .collect()
+}
+
+/// The position of a pending bid among the pending bids for the same permission key.
+#[derive(Serialize, Deserialize, Clone, ToSchema, Debug)]
+pub struct BidRank {
+    /// The position of the bid, starting from 1 for the best bid.
+    #[schema(example = 2)]
+    pub position: usize,
+    /// The number of pending bids for the permission key.
+    #[schema(example = 5)]
+    pub total:    usize,
+}
+
 #[derive(Clone)]
 pub struct ExpressRelaySvm {
-    pub relayer:                     Arc<Keypair>,
-    pub permission_account_position: usize,
-    pub router_account_position:     usize,
+    pub relayer:                         Arc<Keypair>,
+    pub permission_account_position:     usize,
+    pub router_account_position:         usize,
+    pub relayer_signer_account_position: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BlockTiming {
+    pub last_block_time: OffsetDateTime,
+    /// Moving average of the time between consecutive blocks.
+    pub block_interval:  Option<time::Duration>,
+}
+
+fn remove_bid_from(
+    bids: &mut HashMap<AuctionKey, Vec<SimulatedBid>>,
+    key: &AuctionKey,
+    bid_id: BidId,
+) {
+    if let Entry::Occupied(mut entry) = bids.entry(key.clone()) {
+        let bids = entry.get_mut();
+        bids.retain(|b| b.get_core_fields().id != bid_id);
+        if bids.is_empty() {
+            entry.remove();
+        }
+    }
+}
+
+fn update_bid_in(bids: &mut HashMap<AuctionKey, Vec<SimulatedBid>>, bid: SimulatedBid) {
+    let key = bid.get_auction_key();
+    let core_fields = bid.get_core_fields();
+    match bids.entry(key.clone()) {
+        Entry::Occupied(mut entry) => {
+            let bids = entry.get_mut();
+            match bids
+                .iter()
+                .position(|b| b.get_core_fields().id == core_fields.id)
+            {
+                Some(index) => bids[index] = bid,
+                None => {
+                    tracing::error!("Update bid failed - bid not found for: {:?}", bid);
+                }
+            }
+        }
+        Entry::Vacant(_) => {
+            tracing::error!("Update bid failed - entry not found for key: {:?}", key);
+        }
+    }
+}
+
+/// Applies the status transition of a bid to the in-memory bids if the transition updated the bid in the db.
+/// Returns whether it was applied, so only one of the concurrent transitions of a bid is broadcast.
+fn apply_bid_status_transition(
+    bids: &mut HashMap<AuctionKey, Vec<SimulatedBid>>,
+    bid: SimulatedBid,
+    rows_affected: u64,
+) -> bool {
+    if rows_affected == 0 {
+        return false;
+    }
+    let core_fields = bid.get_core_fields();
+    match core_fields.status {
+        BidStatus::Submitted { .. } => update_bid_in(bids, bid),
+        _ => remove_bid_from(bids, &bid.get_auction_key(), core_fields.id),
+    }
+    true
+}
+
+/// Serializes a value to be stored as json. The failure is logged with the context of the value.
+fn to_json_value<T: Serialize, C: std::fmt::Debug>(
+    value: &T,
+    context: &C,
+) -> Result<serde_json::Value, RestError> {
+    serde_json::to_value(value).map_err(|e| {
+        tracing::error!(
+            "Failed to serialize json value: {} - context: {:?}",
+            e,
+            context
+        );
+        RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+    })
+}
+
+/// Whether the database error is expected to go away once the database is reachable again.
+fn is_transient_db_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        // Connection exceptions and server shutdowns
+        sqlx::Error::Database(e) => e
+            .code()
+            .is_some_and(|code| code.starts_with("08") || code.starts_with("57P")),
+        _ => false,
+    }
+}
+
+/// Records how long the bid stayed pending once it is submitted, and how long it stayed submitted once it is concluded.
+fn record_bid_status_duration(
+    core_fields: &SimulatedBidCoreFields,
+    updated_status: &BidStatus,
+    auction: Option<&models::Auction>,
+) {
+    let now = OffsetDateTime::now_utc();
+    let submission_time = auction
+        .and_then(|auction| auction.submission_time)
+        .map(PrimitiveDateTime::assume_utc);
+    let conclusion_time = auction
+        .and_then(|auction| auction.conclusion_time)
+        .map(PrimitiveDateTime::assume_utc)
+        .unwrap_or(now);
+    let (name, status, duration) = match updated_status {
+        BidStatus::Submitted { .. } => (
+            "bid_pending_duration_seconds",
+            "submitted",
+            submission_time.unwrap_or(now) - core_fields.initiation_time,
+        ),
+        BidStatus::Won { .. } => (
+            "bid_submitted_duration_seconds",
+            "won",
+            conclusion_time - submission_time.unwrap_or(now),
+        ),
+        // Bids without an index lost the auction and were never submitted
+        BidStatus::Lost { index: Some(_), .. } => (
+            "bid_submitted_duration_seconds",
+            "lost",
+            conclusion_time - submission_time.unwrap_or(now),
+        ),
+        _ => return,
+    };
+    let labels = [
+        ("chain_id", core_fields.chain_id.clone()),
+        ("status", status.to_string()),
+    ];
+    metrics::histogram!(name, &labels).record(duration.as_seconds_f64().max(0.0));
+}
+
+impl BlockTiming {
+    /// Estimates the arrival time of the first block strictly after the given time.
+    pub fn next_block_after(&self, time: OffsetDateTime) -> OffsetDateTime {
+        match self.block_interval {
+            Some(interval) if interval.is_positive() => {
+                let next_block_time = self.last_block_time + interval;
+                if next_block_time > time {
+                    return next_block_time;
+                }
+                let missed_blocks = ((time - next_block_time) / interval).floor() + 1.0;
+                next_block_time + interval * missed_blocks
+            }
+            _ => time,
+        }
+    }
 }
 
 pub struct Store {
-    pub chains:             HashMap<ChainId, ChainStoreEvm>,
-    pub chains_svm:         HashMap<ChainId, ChainStoreSvm>,
-    pub bids:               RwLock<HashMap<AuctionKey, Vec<SimulatedBid>>>,
-    pub event_sender:       broadcast::Sender<UpdateEvent>,
-    pub opportunity_store:  OpportunityStore,
-    pub relayer:            LocalWallet,
-    pub ws:                 WsState,
-    pub db:                 sqlx::PgPool,
-    pub task_tracker:       TaskTracker,
-    pub auction_lock:       Mutex<HashMap<AuctionKey, AuctionLock>>,
-    pub submitted_auctions: RwLock<HashMap<ChainId, Vec<models::Auction>>>,
-    pub secret_key:         String,
-    pub access_tokens:      RwLock<HashMap<models::AccessTokenToken, models::Profile>>,
-    pub metrics_recorder:   PrometheusHandle,
-    pub express_relay_svm:  ExpressRelaySvm,
+    pub chains:                     HashMap<ChainId, ChainStoreEvm>,
+    pub chains_svm:                 HashMap<ChainId, ChainStoreSvm>,
+    pub bids:                       RwLock<HashMap<AuctionKey, Vec<SimulatedBid>>>,
+    pub event_sender:               broadcast::Sender<UpdateEvent>,
+    pub opportunity_store:          OpportunityStore,
+    pub ws:                         WsState,
+    pub db:                         sqlx::PgPool,
+    pub task_tracker:               TaskTracker,
+    pub auction_lock:               Mutex<HashMap<AuctionKey, AuctionLock>>,
+    /// Acquisition times of the auction locks which are currently held.
+    pub held_auction_locks:         StdMutex<HashMap<AuctionKey, Instant>>,
+    pub submitted_auctions:         RwLock<HashMap<ChainId, Vec<models::Auction>>>,
+    /// Time of the latest conclusion of a submitted auction on each chain.
+    pub last_auction_conclusions:   RwLock<HashMap<ChainId, OffsetDateTime>>,
+    /// Chains whose submissions are paused because their submitted auctions are not being concluded.
+    pub stalled_chains:             RwLock<HashSet<ChainId>>,
+    pub secret_key:                 String,
+    /// Profiles of the valid access tokens, along with the scopes each token grants.
+    pub access_tokens:
+        RwLock<HashMap<models::AccessTokenToken, (models::Profile, Vec<models::AccessTokenScope>)>>,
+    pub metrics_recorder:           PrometheusHandle,
+    pub express_relay_svm:          ExpressRelaySvm,
+    pub block_timings:              RwLock<HashMap<ChainId, BlockTiming>>,
+    pub admin_audit:                bool,
+    /// Maximum number of submitted but not concluded auctions tracked for each chain.
+    pub max_submitted_auctions:     HashMap<ChainId, usize>,
+    pub tracker_stall_timeout:      Option<Duration>,
+    pub auction_lock_timeout:       Duration,
+    pub late_bid_policy:            LateBidPolicy,
+    pub bid_status_reorder_retries: usize,
+    pub bid_status_reorder_delay:   Duration,
+    pub permission_key_chain_check: PermissionKeyChainCheck,
+    /// Queues the opportunity events for publishing, if the opportunity publisher is enabled.
+    pub opportunity_publisher:      Option<mpsc::Sender<OpportunityEvent>>,
+    /// Limits the concurrent auction submissions for the chains configured with a limit.
+    pub submission_semaphores:      HashMap<ChainId, Arc<Semaphore>>,
+    /// Meters the API usage of the profiles, if usage metering is enabled.
+    pub usage:                      Option<UsageStore>,
+    pub bid_queue:                  BidQueue,
+    pub persist_rejected_bids:      bool,
+    pub rejected_bid_retention:     Duration,
+    pub bid_expiry:                 Option<Duration>,
+    /// Bids accepted while the database was unavailable, if deferring the bid persistence is enabled.
+    pub deferred_bids:              Option<Mutex<HashMap<BidId, DeferredBid>>>,
+    pub max_deferred_bids:          usize,
+}
+
+/// A bid accepted while the database was unavailable, waiting to be persisted.
+#[derive(Clone, Debug)]
+pub struct DeferredBid {
+    core_fields:     SimulatedBidCoreFields,
+    chain_type:      models::ChainType,
+    metadata:        serde_json::Value,
+    creation_time:   OffsetDateTime,
+    idempotency_key: Option<IdempotencyKey>,
+}
+
+/// Idempotency key of a bid submission, unique among the bids of a profile.
+#[derive(Clone, Debug)]
+pub struct IdempotencyKey {
+    pub key:          String,
+    /// Hash of the submitted bid, to tell the retries of a submission apart from the reuses of its key.
+    pub payload_hash: H256,
+}
+
+impl DeferredBid {
+    fn new(
+        bid: &SimulatedBid,
+        creation_time: OffsetDateTime,
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> Result<Self, RestError> {
+        let (metadata, chain_type): (models::BidMetadata, models::ChainType) =
+            bid.clone().try_into().map_err(|e| {
+                tracing::error!("Failed to convert metadata: {}", e);
+                RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+            })?;
+        let metadata = to_json_value(&metadata, bid)?;
+        Ok(Self {
+            core_fields: bid.get_core_fields(),
+            chain_type,
+            metadata,
+            creation_time,
+            idempotency_key,
+        })
+    }
 }
 
 impl From<SimulatedBid> for SimulatedBidCoreFields {
@@ -416,6 +1188,7 @@ impl SimulatedBidCoreFields {
         chain_id: String,
         permission_key: Bytes,
         initiation_time: OffsetDateTime,
+        good_till: Option<OffsetDateTime>,
         auth: Auth,
     ) -> Self {
         Self {
@@ -429,8 +1202,13 @@ impl SimulatedBidCoreFields {
                 Auth::Authorized(_, profile) => Some(profile.id),
                 _ => None,
             },
+            good_till,
         }
     }
+
+    pub fn is_past_good_till(&self, time: OffsetDateTime) -> bool {
+        self.good_till.is_some_and(|good_till| good_till <= time)
+    }
 }
 
 impl SimulatedBid {
@@ -470,14 +1248,23 @@ impl TryFrom<(models::Bid, Option<models::Auction>)> for BidStatus {
         }
         if bid.status == models::BidStatus::Pending {
             Ok(BidStatus::Pending)
+        } else if bid.status == models::BidStatus::Expired {
+            Ok(BidStatus::Expired)
         } else {
             let result = match auction {
-                Some(auction) => auction.tx_hash,
+                Some(auction) => auction
+                    .tx_hash
+                    .map(|tx_hash| TxHash::from_bytes(&auction.chain_type, &tx_hash))
+                    .transpose()?,
                 None => None,
             };
             let index = bid.metadata.0.get_bundle_index();
             if bid.status == models::BidStatus::Lost {
-                Ok(BidStatus::Lost { result, index })
+                Ok(BidStatus::Lost {
+                    result,
+                    index,
+                    reason: None,
+                })
             } else {
                 if result.is_none() || index.is_none() {
                     return Err(anyhow::anyhow!(
@@ -520,6 +1307,9 @@ impl TryFrom<(models::Bid, Option<models::Auction>)> for SimulatedBid {
             status: bid_with_auction.try_into()?,
             initiation_time: bid.initiation_time.assume_offset(UtcOffset::UTC),
             profile_id: bid.profile_id,
+            good_till: bid
+                .good_till
+                .map(|good_till| good_till.assume_offset(UtcOffset::UTC)),
         };
 
         Ok(match bid.metadata.0 {
@@ -553,7 +1343,7 @@ impl TryFrom<SimulatedBid> for (models::BidMetadata, models::ChainType) {
                         .try_into()
                         .map_err(|e: &str| anyhow::anyhow!(e))?,
                     bundle_index:    models::BundleIndex(match bid.core_fields.status {
-                        BidStatus::Pending => None,
+                        BidStatus::Pending | BidStatus::Expired => None,
                         BidStatus::Lost { index, .. } => index,
                         BidStatus::Submitted { index, .. } => Some(index),
                         BidStatus::Won { index, .. } => Some(index),
@@ -584,22 +1374,88 @@ impl From<SimulatedBidSvm> for SimulatedBid {
 }
 
 impl Store {
-    pub async fn opportunity_exists(&self, opportunity: &Opportunity) -> bool {
-        let key = match &opportunity.params {
-            OpportunityParams::V1(params) => params.permission_key.clone(),
+    /// Returns the effective config of all the chains the server is running with.
+    pub fn get_config_map(&self) -> ConfigMap {
+        let chains_evm = self.chains.iter().map(|(chain_id, chain_store)| {
+            (chain_id.clone(), Config::Evm(chain_store.config.clone()))
+        });
+        let chains_svm = self.chains_svm.iter().map(|(chain_id, chain_store)| {
+            (chain_id.clone(), Config::Svm(chain_store.config.clone()))
+        });
+        ConfigMap {
+            chains:         chains_evm.chain(chains_svm).collect(),
+            retry_policies: RetryPoliciesConfig::default(),
+        }
+    }
+
+    /// Returns the ids of all the EVM and SVM chains the server is running with along with their chain type,
+    /// sorted by the chain id.
+    pub fn supported_chains(&self) -> Vec<(ChainId, models::ChainType)> {
+        let mut chains: Vec<(ChainId, models::ChainType)> = self
+            .chains
+            .keys()
+            .map(|chain_id| (chain_id.clone(), models::ChainType::Evm))
+            .chain(
+                self.chains_svm
+                    .keys()
+                    .map(|chain_id| (chain_id.clone(), models::ChainType::Svm)),
+            )
+            .collect();
+        chains.sort_by(|a, b| a.0.cmp(&b.0));
+        chains
+    }
+
+    /// Rejects the submissions for a chain which is not configured on the server, listing the supported chains.
+    pub fn verify_chain_supported(&self, chain_id: &ChainId) -> Result<(), RestError> {
+        if self.chains.contains_key(chain_id) || self.chains_svm.contains_key(chain_id) {
+            return Ok(());
+        }
+        Err(RestError::UnsupportedChain {
+            chain_id:         chain_id.clone(),
+            supported_chains: self
+                .supported_chains()
+                .into_iter()
+                .map(|(chain_id, _)| chain_id)
+                .collect(),
+        })
+    }
+
+    /// Returns true if the chain accepts opportunities.
+    pub fn opportunities_enabled(&self, chain_id: &ChainId) -> bool {
+        self.chains.get(chain_id).map_or(false, |chain_store| {
+            chain_store.config.opportunities_enabled
+        })
+    }
+
+    /// Refreshes the creation time of the opportunity with the same content as the given one instead of adding
+    /// a duplicate. Returns the refreshed opportunity, or None if there is no duplicate.
+    pub async fn refresh_duplicate_opportunity(
+        &self,
+        opportunity: &Opportunity,
+    ) -> Result<Option<Opportunity>, RestError> {
+        let Some(duplicate) = self.opportunity_store.refresh_duplicate(opportunity).await else {
+            return Ok(None);
         };
-        self.opportunity_store
-            .opportunities
-            .read()
+        let odt = OffsetDateTime::from_unix_timestamp_nanos(duplicate.creation_time * 1000)
+            .expect("creation_time is valid");
+        sqlx::query("UPDATE opportunity SET creation_time = $1 WHERE id = $2")
+            .bind(PrimitiveDateTime::new(odt.date(), odt.time()))
+            .bind(duplicate.id)
+            .execute(&self.db)
             .await
-            .get(&key)
-            .map_or(false, |opps| opps.contains(opportunity))
+            .map_err(|e| {
+                tracing::error!("DB: Failed to refresh opportunity: {}", e);
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })?;
+        Ok(Some(duplicate))
     }
 
     pub async fn add_opportunity(&self, opportunity: Opportunity) -> Result<(), RestError> {
         let odt = OffsetDateTime::from_unix_timestamp_nanos(opportunity.creation_time * 1000)
             .expect("creation_time is valid");
         let OpportunityParams::V1(params) = &opportunity.params;
+        let sell_tokens = to_json_value(&params.sell_tokens, &opportunity)?;
+        let buy_tokens = to_json_value(&params.buy_tokens, &opportunity)?;
         sqlx::query!("INSERT INTO opportunity (id,
                                                         creation_time,
                                                         permission_key,
@@ -616,55 +1472,229 @@ impl Store {
         &params.target_contract.to_fixed_bytes(),
         BigDecimal::from_str(&params.target_call_value.to_string()).unwrap(),
         params.target_calldata.to_vec(),
-        serde_json::to_value(&params.sell_tokens).unwrap(),
-        serde_json::to_value(&params.buy_tokens).unwrap())
+        sell_tokens,
+        buy_tokens)
             .execute(&self.db)
             .await
             .map_err(|e| {
                 tracing::error!("DB: Failed to insert opportunity: {}", e);
-                RestError::TemporarilyUnavailable
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })?;
+        self.opportunity_store
+            .add_opportunity(opportunity.clone())
+            .await;
+        self.publish_opportunity_event(OpportunityEvent::Added {
+            opportunity: opportunity.clone().into(),
+        });
+        self.ws
+            .broadcast_sender
+            .send(UpdateEvent::NewOpportunity(opportunity.clone().into()))
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to send update: {} - opportunity: {:?}",
+                    e,
+                    opportunity
+                );
+                RestError::TemporarilyUnavailable(UnavailableReason::Internal)
             })?;
-        self.opportunity_store.add_opportunity(opportunity).await;
         Ok(())
     }
 
+    /// Queues the event without waiting, so an unavailable publisher never blocks the caller.
+    fn publish_opportunity_event(&self, event: OpportunityEvent) {
+        if let Some(sender) = &self.opportunity_publisher {
+            if let Err(e) = sender.try_send(event) {
+                tracing::warn!("Failed to queue opportunity event: {}", e);
+                metrics::counter!("opportunity_events_dropped_total").increment(1);
+            }
+        }
+    }
+
     pub async fn remove_opportunity(
         &self,
         opportunity: &Opportunity,
         reason: models::OpportunityRemovalReason,
     ) -> anyhow::Result<()> {
-        let key = match &opportunity.params {
-            OpportunityParams::V1(params) => params.permission_key.clone(),
-        };
-        let mut write_guard = self.opportunity_store.opportunities.write().await;
-        let entry = write_guard.entry(key.clone());
-        if entry
-            .and_modify(|opps| opps.retain(|o| o != opportunity))
-            .or_default()
-            .is_empty()
-        {
-            write_guard.remove(&key);
-        }
-        drop(write_guard);
+        self.opportunity_store.remove_opportunity(opportunity).await;
         let now = OffsetDateTime::now_utc();
         sqlx::query!(
             "UPDATE opportunity SET removal_time = $1, removal_reason = $2 WHERE id = $3 AND removal_time IS NULL",
             PrimitiveDateTime::new(now.date(), now.time()),
-            reason as _,
+            reason.clone() as _,
             opportunity.id
         )
             .execute(&self.db)
             .await?;
+        self.broadcast_opportunity_removal(opportunity, reason);
         Ok(())
     }
 
+    /// Records a fill of the opportunity by subtracting the filled amounts from its remaining amounts.
+    /// The opportunity is removed once it is fully consumed, otherwise an update with the remaining amounts is
+    /// broadcast. Returns the updated opportunity.
+    pub async fn consume_opportunity(
+        &self,
+        id: OpportunityId,
+        filled_amounts: &OpportunityAmounts,
+    ) -> Result<Opportunity, RestError> {
+        let key = self
+            .opportunity_store
+            .find_key(id)
+            .await
+            .ok_or(RestError::OpportunityNotFound)?;
+        let opportunity = self
+            .opportunity_store
+            .consume(&key, id, filled_amounts, |opportunity| async move {
+                self.update_opportunity_remaining(&opportunity).await
+            })
+            .await?
+            .ok_or(RestError::OpportunityNotFound)?;
+        if opportunity.remaining.is_consumed() {
+            self.broadcast_opportunity_removal(
+                &opportunity,
+                models::OpportunityRemovalReason::Consumed,
+            );
+        } else {
+            self.broadcast_opportunity_update(&opportunity);
+        }
+        Ok(opportunity)
+    }
+
+    /// Persists the remaining amounts of the opportunity, and its removal if it is fully consumed.
+    async fn update_opportunity_remaining(
+        &self,
+        opportunity: &Opportunity,
+    ) -> Result<(), RestError> {
+        let serialize_tokens = |tokens: &Vec<TokenAmount>| {
+            serde_json::to_value(tokens).map_err(|e| {
+                tracing::error!(
+                    "Failed to serialize tokens: {} - opportunity: {:?}",
+                    e,
+                    opportunity
+                );
+                RestError::TemporarilyUnavailable(UnavailableReason::Internal)
+            })
+        };
+        let remaining_sell_tokens = serialize_tokens(&opportunity.remaining.sell_tokens)?;
+        let remaining_buy_tokens = serialize_tokens(&opportunity.remaining.buy_tokens)?;
+        let is_consumed = opportunity.remaining.is_consumed();
+        let now = OffsetDateTime::now_utc();
+        let mut query = QueryBuilder::new("UPDATE opportunity SET remaining_sell_tokens = ");
+        query.push_bind(remaining_sell_tokens);
+        query.push(", remaining_buy_tokens = ");
+        query.push_bind(remaining_buy_tokens);
+        if is_consumed {
+            query.push(", removal_time = ");
+            query.push_bind(PrimitiveDateTime::new(now.date(), now.time()));
+            query.push(", removal_reason = ");
+            query.push_bind(models::OpportunityRemovalReason::Consumed);
+        }
+        query.push(" WHERE id = ");
+        query.push_bind(opportunity.id);
+        query.build().execute(&self.db).await.map_err(|e| {
+            tracing::error!(
+                "DB: Failed to update opportunity remaining amounts: {} - opportunity_id: {}",
+                e,
+                opportunity.id
+            );
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })?;
+        Ok(())
+    }
+
+    fn broadcast_opportunity_update(&self, opportunity: &Opportunity) {
+        self.publish_opportunity_event(OpportunityEvent::Updated {
+            opportunity: opportunity.clone().into(),
+        });
+        if let Err(e) = self
+            .ws
+            .broadcast_sender
+            .send(UpdateEvent::UpdateOpportunity(opportunity.clone().into()))
+        {
+            tracing::error!("Failed to send opportunity update: {}", e);
+        }
+    }
+
+    /// Returns true if the opportunity is older than the maximum opportunity age of its chain.
+    pub fn is_opportunity_expired(
+        &self,
+        opportunity: &Opportunity,
+        now: UnixTimestampMicros,
+    ) -> bool {
+        let OpportunityParams::V1(params) = &opportunity.params;
+        self.chains
+            .get(&params.chain_id)
+            .and_then(|chain_store| chain_store.config.opportunity_max_age)
+            .is_some_and(|max_age| {
+                now - opportunity.creation_time > max_age as UnixTimestampMicros * 1_000_000
+            })
+    }
+
+    /// Removes the opportunities which are older than the maximum opportunity age of their chain.
+    /// The removals are persisted with a single update. Returns the number of the removed opportunities.
+    pub async fn remove_expired_opportunities(&self) -> anyhow::Result<usize> {
+        let now = OffsetDateTime::now_utc();
+        let now_micros = now.unix_timestamp_nanos() / 1000;
+        let expired = self
+            .opportunity_store
+            .remove_matching(|opportunity| self.is_opportunity_expired(opportunity, now_micros))
+            .await;
+        if expired.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<OpportunityId> = expired.iter().map(|opportunity| opportunity.id).collect();
+        sqlx::query(
+            "UPDATE opportunity SET removal_time = $1, removal_reason = $2 WHERE id = ANY($3) AND removal_time IS NULL",
+        )
+        .bind(PrimitiveDateTime::new(now.date(), now.time()))
+        .bind(models::OpportunityRemovalReason::Expired)
+        .bind(&ids)
+        .execute(&self.db)
+        .await?;
+        for opportunity in expired.iter() {
+            self.broadcast_opportunity_removal(
+                opportunity,
+                models::OpportunityRemovalReason::Expired,
+            );
+        }
+        Ok(expired.len())
+    }
+
+    fn broadcast_opportunity_removal(
+        &self,
+        opportunity: &Opportunity,
+        reason: models::OpportunityRemovalReason,
+    ) {
+        let OpportunityParams::V1(params) = &opportunity.params;
+        let removed_opportunity = RemovedOpportunity {
+            opportunity_id: opportunity.id,
+            chain_id: params.chain_id.clone(),
+            permission_key: params.permission_key.clone(),
+            reason,
+        };
+        self.publish_opportunity_event(OpportunityEvent::Removed {
+            opportunity: removed_opportunity.clone(),
+        });
+        if self.ws.emit_opportunity_removals {
+            if let Err(e) = self
+                .ws
+                .broadcast_sender
+                .send(UpdateEvent::RemoveOpportunity(removed_opportunity))
+            {
+                tracing::error!("Failed to send opportunity removal update: {}", e);
+            }
+        }
+    }
+
     #[tracing::instrument(skip_all)]
+    /// Creates the auction of a bid collection. Returns None if the auction of the bid collection was already
+    /// initialized, in which case its bids are handled by the other initialization and must not be submitted again.
     pub async fn init_auction<T: ChainStore>(
         &self,
         permission_key: PermissionKey,
         chain_id: ChainId,
         bid_collection_time: OffsetDateTime,
-    ) -> anyhow::Result<models::Auction> {
+    ) -> anyhow::Result<Option<models::Auction>> {
         let now = OffsetDateTime::now_utc();
         let auction = models::Auction {
             id: Uuid::new_v4(),
@@ -679,34 +1709,50 @@ impl Store {
                 bid_collection_time.time(),
             )),
             submission_time: None,
+            relayer: None,
+            conclusion_block_number: None,
+            conclusion_block_hash: None,
         };
-        sqlx::query!(
-            "INSERT INTO auction (id, creation_time, permission_key, chain_id, chain_type, bid_collection_time) VALUES ($1, $2, $3, $4, $5, $6)",
-            auction.id,
-            auction.creation_time,
-            auction.permission_key,
-            auction.chain_id,
-            auction.chain_type as _,
-            auction.bid_collection_time,
+        // The auction is unique per bid collection, so only the first of concurrent initializations inserts it
+        let result = sqlx::query(
+            "INSERT INTO auction (id, creation_time, permission_key, chain_id, chain_type, bid_collection_time) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (permission_key, chain_id, bid_collection_time) DO NOTHING",
         )
+        .bind(auction.id)
+        .bind(auction.creation_time)
+        .bind(auction.permission_key.clone())
+        .bind(auction.chain_id.clone())
+        .bind(auction.chain_type.clone())
+        .bind(auction.bid_collection_time)
         .execute(&self.db)
         .await?;
-        Ok(auction)
+        if result.rows_affected() == 0 {
+            tracing::warn!(
+                "Auction is already initialized for the bid collection - permission_key: {:?}, chain_id: {}",
+                permission_key,
+                auction.chain_id
+            );
+            return Ok(None);
+        }
+        Ok(Some(auction))
     }
 
     #[tracing::instrument(skip_all)]
     pub async fn submit_auction(
         &self,
         mut auction: models::Auction,
-        transaction_hash: Vec<u8>,
+        transaction_hash: TxHash,
+        relayer: Vec<u8>,
     ) -> anyhow::Result<models::Auction> {
-        auction.tx_hash = Some(transaction_hash);
+        auction.tx_hash = Some(transaction_hash.to_vec());
+        auction.relayer = Some(relayer);
         let now = OffsetDateTime::now_utc();
         auction.submission_time = Some(PrimitiveDateTime::new(now.date(), now.time()));
-        sqlx::query!("UPDATE auction SET submission_time = $1, tx_hash = $2 WHERE id = $3 AND submission_time IS NULL",
-            auction.submission_time,
-            auction.tx_hash,
-            auction.id)
+        sqlx::query("UPDATE auction SET submission_time = $1, tx_hash = $2, relayer = $3 WHERE id = $4 AND submission_time IS NULL")
+            .bind(auction.submission_time)
+            .bind(&auction.tx_hash)
+            .bind(&auction.relayer)
+            .bind(auction.id)
             .execute(&self.db)
             .await?;
 
@@ -723,34 +1769,185 @@ impl Store {
     pub async fn conclude_auction(
         &self,
         mut auction: models::Auction,
+        inclusion_block: Option<&InclusionBlock>,
     ) -> anyhow::Result<models::Auction> {
         let now = OffsetDateTime::now_utc();
         auction.conclusion_time = Some(PrimitiveDateTime::new(now.date(), now.time()));
-        sqlx::query!(
-            "UPDATE auction SET conclusion_time = $1 WHERE id = $2 AND conclusion_time IS NULL",
-            auction.conclusion_time,
-            auction.id
+        auction.conclusion_block_number = inclusion_block.map(|block| block.number as i64);
+        auction.conclusion_block_hash = inclusion_block.map(|block| block.hash.as_bytes().to_vec());
+        sqlx::query(
+            "UPDATE auction SET conclusion_time = $1, conclusion_block_number = $2, conclusion_block_hash = $3 WHERE id = $4 AND conclusion_time IS NULL",
         )
+        .bind(auction.conclusion_time)
+        .bind(auction.conclusion_block_number)
+        .bind(&auction.conclusion_block_hash)
+        .bind(auction.id)
         .execute(&self.db)
         .await?;
         Ok(auction)
     }
 
+    /// Returns the auctions of the chain whose transaction was included in the block or any later block.
+    pub async fn get_auctions_concluded_since_block(
+        &self,
+        chain_id: &ChainId,
+        block_number: u64,
+    ) -> anyhow::Result<Vec<models::Auction>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM auction WHERE chain_id = $1 AND conclusion_block_number >= $2",
+        )
+        .bind(chain_id)
+        .bind(block_number as i64)
+        .fetch_all(&self.db)
+        .await?)
+    }
+
+    /// Reverts the conclusion of an auction whose transaction was dropped from the chain by a reorg.
+    /// If the transaction is still pending, the won bids go back to submitted and the auction is tracked again until
+    /// the transaction lands. Otherwise the won bids are lost.
+    pub async fn revert_reorged_auction(
+        &self,
+        mut auction: models::Auction,
+        is_pending: bool,
+    ) -> anyhow::Result<()> {
+        auction.conclusion_block_number = None;
+        auction.conclusion_block_hash = None;
+        if is_pending {
+            auction.conclusion_time = None;
+        }
+        let mut tx = self.db.begin().await?;
+        let won_bids: Vec<models::Bid> =
+            sqlx::query_as("SELECT * FROM bid WHERE auction_id = $1 AND status = 'won'")
+                .bind(auction.id)
+                .fetch_all(&mut *tx)
+                .await?;
+        sqlx::query(
+            "UPDATE auction SET conclusion_time = $1, conclusion_block_number = NULL, conclusion_block_hash = NULL WHERE id = $2",
+        )
+        .bind(auction.conclusion_time)
+        .bind(auction.id)
+        .execute(&mut *tx)
+        .await?;
+        let mut reverted_bids = vec![];
+        for bid in won_bids {
+            let bid: SimulatedBid = (bid, Some(auction.clone())).try_into()?;
+            let BidStatus::Won { result, index } = bid.get_core_fields().status else {
+                continue;
+            };
+            let updated_status = if is_pending {
+                BidStatus::Submitted { result, index }
+            } else {
+                BidStatus::Lost {
+                    result: Some(result),
+                    index:  Some(index),
+                    reason: Some(BidLostReason::Reorg),
+                }
+            };
+            sqlx::query("UPDATE bid SET status = $1 WHERE id = $2 AND status = 'won'")
+                .bind(updated_status.clone())
+                .bind(bid.get_core_fields().id)
+                .execute(&mut *tx)
+                .await?;
+            reverted_bids.push(bid.update_status(updated_status));
+        }
+        tx.commit().await?;
+
+        if is_pending {
+            let mut write_guard = self.bids.write().await;
+            for bid in reverted_bids.iter() {
+                write_guard
+                    .entry(bid.get_auction_key())
+                    .or_default()
+                    .push(bid.clone());
+            }
+            drop(write_guard);
+            self.submitted_auctions
+                .write()
+                .await
+                .entry(auction.chain_id.clone())
+                .or_insert_with(Vec::new)
+                .push(auction);
+        }
+        for bid in reverted_bids {
+            let core_fields = bid.get_core_fields();
+            self.broadcast_status_update(&core_fields, core_fields.status.clone());
+        }
+        Ok(())
+    }
+
+    pub async fn record_block_time(&self, chain_id: &ChainId, block_time: OffsetDateTime) {
+        // The weight of the latest block interval in the moving average
+        const BLOCK_INTERVAL_WEIGHT: f64 = 0.2;
+        let mut block_timings = self.block_timings.write().await;
+        let block_interval = block_timings.get(chain_id).map(|timing| {
+            let interval = block_time - timing.last_block_time;
+            match timing.block_interval {
+                Some(average) => {
+                    average * (1.0 - BLOCK_INTERVAL_WEIGHT) + interval * BLOCK_INTERVAL_WEIGHT
+                }
+                None => interval,
+            }
+        });
+        block_timings.insert(
+            chain_id.clone(),
+            BlockTiming {
+                last_block_time: block_time,
+                block_interval,
+            },
+        );
+    }
+
+    /// Estimates when the next auction for the permission key will run.
+    ///
+    /// The auction runs on the first block received after the oldest pending bid has lived for the minimum lifetime.
+    /// Returns None if there is no pending bid for the permission key.
+    pub async fn estimate_next_auction_time(
+        &self,
+        key: &AuctionKey,
+        auction_minimum_lifetime: Duration,
+    ) -> Option<OffsetDateTime> {
+        let oldest_initiation_time = self
+            .get_bids(key)
+            .await
+            .into_iter()
+            .map(SimulatedBidCoreFields::from)
+            .filter(|core_fields| core_fields.status == BidStatus::Pending)
+            .map(|core_fields| core_fields.initiation_time)
+            .min()?;
+        let ready_time =
+            (oldest_initiation_time + auction_minimum_lifetime).max(OffsetDateTime::now_utc());
+        Some(match self.block_timings.read().await.get(&key.1) {
+            Some(timing) => timing.next_block_after(ready_time),
+            None => ready_time,
+        })
+    }
+
     pub async fn get_bids(&self, key: &AuctionKey) -> Vec<SimulatedBid> {
         self.bids.read().await.get(key).cloned().unwrap_or_default()
     }
 
-    pub async fn get_permission_keys_for_auction(&self, chain_id: &ChainId) -> Vec<PermissionKey> {
+    /// Returns the best pending bid amount for each permission key of the chain, sorted from the most valuable.
+    pub async fn get_permission_keys_for_auction(
+        &self,
+        chain_id: &ChainId,
+    ) -> Vec<(PermissionKey, BidAmount)> {
+        rank_permission_keys(&*self.bids.read().await, chain_id)
+    }
+
+    /// Returns the auction keys of the chain which have at least one bid matching the predicate.
+    pub async fn get_auction_keys_with_bid(
+        &self,
+        chain_id: &ChainId,
+        predicate: impl Fn(&SimulatedBidCoreFields) -> bool,
+    ) -> Vec<AuctionKey> {
         self.bids
             .read()
             .await
-            .keys()
-            .filter_map(|(p, c)| {
-                if c != chain_id {
-                    return None;
-                }
-                Some(p.clone())
+            .iter()
+            .filter(|((_, c), bids)| {
+                c == chain_id && bids.iter().any(|bid| predicate(&bid.get_core_fields()))
             })
+            .map(|(key, _)| key.clone())
             .collect()
     }
 
@@ -764,115 +1961,624 @@ impl Store {
     }
 
     #[tracing::instrument(skip_all)]
-    pub async fn add_bid(&self, bid: SimulatedBid) -> Result<(), RestError> {
-        let core_fields = bid.get_core_fields();
+    /// Adds a verified bid and returns its id. If the bid has an idempotency key which was already used by the
+    /// same profile for the same bid, the bid is not added and the id of the original bid is returned instead.
+    pub async fn add_bid(
+        &self,
+        bid: SimulatedBid,
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> Result<BidId, RestError> {
+        let profile_id = bid.get_core_fields().profile_id;
+        let result = self
+            .add_bids_with_idempotency_keys(vec![(bid, idempotency_key.clone())])
+            .await
+            .remove(0);
+        match (result, profile_id, idempotency_key) {
+            // A concurrent submission with the same idempotency key can be inserted first
+            (Err(e), Some(profile_id), Some(idempotency_key)) => self
+                .get_idempotent_bid(profile_id, &idempotency_key)
+                .await?
+                .ok_or(e),
+            (result, _, _) => result,
+        }
+    }
+
+    /// Adds the verified bids, inserting them in a single transaction, and returns the result of each bid in order.
+    /// A bid failing to insert is rejected on its own. If the whole insert fails, every bid is either deferred or
+    /// rejected, depending on the deferred bid queue.
+    pub async fn add_bids(&self, bids: Vec<SimulatedBid>) -> Vec<Result<BidId, RestError>> {
+        self.add_bids_with_idempotency_keys(bids.into_iter().map(|bid| (bid, None)).collect())
+            .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn add_bids_with_idempotency_keys(
+        &self,
+        bids: Vec<(SimulatedBid, Option<IdempotencyKey>)>,
+    ) -> Vec<Result<BidId, RestError>> {
         let now = OffsetDateTime::now_utc();
+        let (bids, idempotency_keys): (Vec<SimulatedBid>, Vec<Option<IdempotencyKey>>) =
+            bids.into_iter().unzip();
+        let mut prepared_bids: Vec<Result<DeferredBid, RestError>> = bids
+            .iter()
+            .zip(idempotency_keys)
+            .map(|(bid, idempotency_key)| DeferredBid::new(bid, now, idempotency_key))
+            .collect();
+        for (bid, prepared_bid) in bids.iter().zip(prepared_bids.iter_mut()) {
+            if prepared_bid.is_ok() && self.is_auction_in_progress(&bid.get_auction_key()).await {
+                let labels = [
+                    ("chain_id", bid.get_core_fields().chain_id.clone()),
+                    ("policy", self.late_bid_policy.as_str().to_string()),
+                ];
+                metrics::counter!("bids_during_auction_submission_total", &labels).increment(1);
+                if self.late_bid_policy == LateBidPolicy::Reject {
+                    *prepared_bid = Err(RestError::BadParameters(
+                        "Auction of the permission key is in progress, bid again for the next auction"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+        let bids_to_insert: Vec<&DeferredBid> = prepared_bids
+            .iter()
+            .filter_map(|bid| bid.as_ref().ok())
+            .collect();
+        let (insert_error, insert_results) = match bids_to_insert.is_empty() {
+            true => (None, vec![]),
+            false => match self.insert_bids(&bids_to_insert).await {
+                Ok(insert_results) => (None, insert_results),
+                Err(e) => (Some(e), vec![]),
+            },
+        };
+        let mut insert_results = insert_results.into_iter();
+        // Only the bids failing on a transient error are deferred, the others would never be persisted
+        let mut deferred_bids = match (&insert_error, &self.deferred_bids) {
+            (Some(e), Some(deferred_bids)) if is_transient_db_error(e) => {
+                tracing::error!("DB: Failed to insert bids: {}", e);
+                Some(deferred_bids.lock().await)
+            }
+            (Some(e), _) => {
+                tracing::error!("DB: Failed to insert bids: {}", e);
+                None
+            }
+            (None, _) => None,
+        };
 
-        let (metadata, chain_type): (models::BidMetadata, models::ChainType) =
-            bid.clone().try_into().map_err(|e| {
-                tracing::error!("Failed to convert metadata: {}", e);
-                RestError::TemporarilyUnavailable
-            })?;
+        let mut results = Vec::with_capacity(bids.len());
+        let mut added_bids = Vec::new();
+        for (bid, prepared_bid) in bids.into_iter().zip(prepared_bids) {
+            let id = bid.get_core_fields().id;
+            let result = match (prepared_bid, &insert_error) {
+                (Err(e), _) => Err(e),
+                (Ok(_), None) => match insert_results.next() {
+                    Some(Ok(())) => Ok(id),
+                    // The idempotency key was used by a concurrent submission, retrying the insert would fail again
+                    Some(Err(sqlx::Error::Database(e))) if e.is_unique_violation() => Err(
+                        RestError::BadParameters("Idempotency key was already used".to_string()),
+                    ),
+                    result => {
+                        tracing::error!(
+                            "DB: Failed to insert bid: {:?} - bid_id: {:?}",
+                            result,
+                            id
+                        );
+                        Err(RestError::TemporarilyUnavailable(UnavailableReason::Db))
+                    }
+                },
+                (Ok(deferred_bid), Some(_)) => match deferred_bids.as_mut() {
+                    Some(deferred_bids) if deferred_bids.len() < self.max_deferred_bids => {
+                        tracing::warn!("Deferring the persistence of bid: {:?}", id);
+                        deferred_bids.insert(id, deferred_bid);
+                        Ok(id)
+                    }
+                    Some(_) => {
+                        tracing::error!("Deferred bid queue is full, rejecting bid: {:?}", id);
+                        Err(RestError::TemporarilyUnavailable(
+                            UnavailableReason::Overloaded,
+                        ))
+                    }
+                    None => Err(RestError::TemporarilyUnavailable(UnavailableReason::Db)),
+                },
+            };
+            if result.is_ok() {
+                added_bids.push(bid);
+            }
+            results.push(result);
+        }
+        if let Some(deferred_bids) = deferred_bids {
+            metrics::gauge!("deferred_bids").set(deferred_bids.len() as f64);
+        }
 
-        sqlx::query!("INSERT INTO bid (id, creation_time, permission_key, chain_id, chain_type, bid_amount, status, initiation_time, profile_id, metadata) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
-        core_fields.id,
-        PrimitiveDateTime::new(now.date(), now.time()),
-        core_fields.permission_key.to_vec(),
-        core_fields.chain_id,
-        chain_type as _,
-        BigDecimal::from_str(&core_fields.bid_amount.to_string()).unwrap(),
-        core_fields.status as _,
-        PrimitiveDateTime::new(core_fields.initiation_time.date(), core_fields.initiation_time.time()),
-        core_fields.profile_id,
-        serde_json::to_value(metadata).expect("Failed to serialize metadata"))
-            .execute(&self.db)
-            .await.map_err(|e| {
-            tracing::error!("DB: Failed to insert bid: {}", e);
-            RestError::TemporarilyUnavailable
-        })?;
+        {
+            let mut write_guard = self.bids.write().await;
+            for bid in added_bids.iter() {
+                write_guard
+                    .entry(bid.get_auction_key())
+                    .or_insert_with(Vec::new)
+                    .push(bid.clone());
+            }
+        }
 
-        self.bids
-            .write()
-            .await
-            .entry(bid.get_auction_key())
-            .or_insert_with(Vec::new)
-            .push(bid.clone());
+        if let Some(usage) = &self.usage {
+            let mut bids_by_profile: HashMap<models::ProfileId, i64> = HashMap::new();
+            for profile_id in added_bids
+                .iter()
+                .filter_map(|bid| bid.get_core_fields().profile_id)
+            {
+                *bids_by_profile.entry(profile_id).or_default() += 1;
+            }
+            for (profile_id, bids) in bids_by_profile {
+                usage
+                    .record(
+                        profile_id,
+                        models::Usage {
+                            bids,
+                            ..Default::default()
+                        },
+                    )
+                    .await;
+            }
+        }
+
+        for bid in added_bids {
+            let core_fields = bid.get_core_fields();
+            self.broadcast_status_update(&core_fields, core_fields.status.clone());
+        }
+        results
+    }
+
+    async fn insert_bid(&self, bid: &DeferredBid) -> Result<(), sqlx::Error> {
+        self.insert_bids(&[bid]).await?.remove(0)
+    }
 
-        self.broadcast_status_update(BidStatusWithId {
-            id:         core_fields.id,
-            bid_status: core_fields.status.clone(),
+    /// Inserts the bids in a single transaction and returns the result of each bid in order.
+    /// Every bid is inserted under its own savepoint, so a bid failing to insert does not fail the others.
+    /// The whole insert fails on a transient error, as the remaining bids would fail the same way.
+    async fn insert_bids(
+        &self,
+        bids: &[&DeferredBid],
+    ) -> Result<Vec<Result<(), sqlx::Error>>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let mut results = Vec::with_capacity(bids.len());
+        for bid in bids {
+            let mut savepoint = tx.begin().await?;
+            match Self::build_insert_bid_query(bid)
+                .build()
+                .execute(&mut *savepoint)
+                .await
+            {
+                Ok(_) => {
+                    savepoint.commit().await?;
+                    results.push(Ok(()));
+                }
+                Err(e) if is_transient_db_error(&e) => return Err(e),
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    results.push(Err(e));
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(results)
+    }
+
+    fn build_insert_bid_query(bid: &DeferredBid) -> QueryBuilder<'static, Postgres> {
+        let mut query_builder = QueryBuilder::new(
+            "INSERT INTO bid (id, creation_time, permission_key, chain_id, chain_type, bid_amount, status, initiation_time, profile_id, metadata, good_till, idempotency_key, idempotency_payload_hash) ",
+        );
+        query_builder.push_values([bid], |mut row, bid| {
+            let core_fields = &bid.core_fields;
+            row.push_bind(core_fields.id)
+                .push_bind(PrimitiveDateTime::new(
+                    bid.creation_time.date(),
+                    bid.creation_time.time(),
+                ))
+                .push_bind(core_fields.permission_key.to_vec())
+                .push_bind(core_fields.chain_id.clone())
+                .push_bind(bid.chain_type.clone())
+                .push_bind(BigDecimal::from_str(&core_fields.bid_amount.to_string()).unwrap())
+                .push_bind(core_fields.status.clone())
+                .push_bind(PrimitiveDateTime::new(
+                    core_fields.initiation_time.date(),
+                    core_fields.initiation_time.time(),
+                ))
+                .push_bind(core_fields.profile_id)
+                .push_bind(bid.metadata.clone())
+                .push_bind(core_fields.good_till.map(|good_till| {
+                    let good_till = good_till.to_offset(UtcOffset::UTC);
+                    PrimitiveDateTime::new(good_till.date(), good_till.time())
+                }))
+                .push_bind(bid.idempotency_key.as_ref().map(|key| key.key.clone()))
+                .push_bind(
+                    bid.idempotency_key
+                        .as_ref()
+                        .map(|key| key.payload_hash.as_bytes().to_vec()),
+                );
         });
-        Ok(())
+        query_builder
     }
 
-    pub async fn get_bid_status(&self, bid_id: BidId) -> Result<Json<BidStatus>, RestError> {
-        let bid: models::Bid = sqlx::query_as("SELECT * FROM bid WHERE id = $1")
-            .bind(bid_id)
-            .fetch_one(&self.db)
+    /// Returns the id of the bid the profile submitted with the idempotency key, if any.
+    /// Reusing the key for a different bid is rejected.
+    pub async fn get_idempotent_bid(
+        &self,
+        profile_id: models::ProfileId,
+        idempotency_key: &IdempotencyKey,
+    ) -> Result<Option<BidId>, RestError> {
+        let deferred_bid = match &self.deferred_bids {
+            Some(deferred_bids) => deferred_bids.lock().await.values().find_map(|bid| {
+                let key = bid.idempotency_key.as_ref()?;
+                (bid.core_fields.profile_id == Some(profile_id) && key.key == idempotency_key.key)
+                    .then(|| (bid.core_fields.id, key.payload_hash.as_bytes().to_vec()))
+            }),
+            None => None,
+        };
+        let bid = match deferred_bid {
+            Some(bid) => Some(bid),
+            None => sqlx::query_as::<_, (BidId, Vec<u8>)>(
+                "SELECT id, idempotency_payload_hash FROM bid WHERE profile_id = $1 AND idempotency_key = $2",
+            )
+            .bind(profile_id)
+            .bind(&idempotency_key.key)
+            .fetch_optional(&self.db)
             .await
             .map_err(|e| {
-                tracing::warn!("DB: Failed to get bid: {} - bid_id: {}", e, bid_id);
-                RestError::BidNotFound
-            })?;
+                tracing::error!(
+                    "DB: Failed to get bid by idempotency key: {} - profile_id: {}",
+                    e,
+                    profile_id
+                );
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })?,
+        };
+        match bid {
+            Some((id, payload_hash)) if payload_hash == idempotency_key.payload_hash.as_bytes() => {
+                Ok(Some(id))
+            }
+            Some(_) => Err(RestError::BadParameters(
+                "Idempotency key was already used for a different bid".to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
 
-        if bid.status == models::BidStatus::Pending {
-            Ok(BidStatus::Pending.into())
-        } else {
-            let result = match bid.auction_id {
-                Some(auction_id) => {
-                    let auction: models::Auction =
-                        sqlx::query_as("SELECT * FROM auction WHERE id = $1")
-                            .bind(auction_id)
-                            .fetch_one(&self.db)
-                            .await
-                            .map_err(|e| {
-                                tracing::warn!(
-                                    "DB: Failed to get auction: {} - auction_id: {}",
-                                    e,
-                                    auction_id
-                                );
-                                RestError::TemporarilyUnavailable
-                            })?;
-                    auction.tx_hash
-                }
-                None => None,
-            };
+    /// Returns the ids of the bids which are accepted but not persisted yet.
+    pub async fn get_deferred_bid_ids(&self) -> HashSet<BidId> {
+        match &self.deferred_bids {
+            Some(deferred_bids) => deferred_bids.lock().await.keys().copied().collect(),
+            None => HashSet::new(),
+        }
+    }
 
-            let index = bid.metadata.0.get_bundle_index();
-            if bid.status == models::BidStatus::Lost {
-                Ok(BidStatus::Lost { result, index }.into())
-            } else {
-                if result.is_none() || index.is_none() {
-                    tracing::error!("Invalid bid status - Won or submitted bid must have a transaction hash and index - bid_id: {}", bid_id);
-                    return Err(RestError::BadParameters(
-                        "Won or submitted bid must have a transaction hash and index".to_string(),
-                    ));
+    /// Retries persisting the deferred bids, in the order they were accepted.
+    /// Stops at the first transient failure, as the database is most likely still unavailable.
+    /// The bids failing permanently are dropped, so they do not hold back the others.
+    pub async fn persist_deferred_bids(&self) -> anyhow::Result<usize> {
+        let Some(deferred_bids) = &self.deferred_bids else {
+            return Ok(0);
+        };
+        let mut bids: Vec<DeferredBid> = deferred_bids.lock().await.values().cloned().collect();
+        bids.sort_by_key(|bid| bid.creation_time);
+        let mut persisted = 0;
+        for bid in bids.iter() {
+            match self.insert_bid(bid).await {
+                Ok(()) => {}
+                // The bid was already inserted, e.g. the previous insert succeeded but its response was lost
+                Err(sqlx::Error::Database(e)) if e.constraint() == Some("bid_pkey") => {}
+                Err(e) if is_transient_db_error(&e) => {
+                    metrics::gauge!("deferred_bids").set(deferred_bids.lock().await.len() as f64);
+                    return Err(e.into());
                 }
-                let result = result.unwrap();
-                let index = index.unwrap();
-                if bid.status == models::BidStatus::Won {
-                    Ok(BidStatus::Won { result, index }.into())
-                } else if bid.status == models::BidStatus::Submitted {
-                    Ok(BidStatus::Submitted { result, index }.into())
-                } else {
-                    tracing::error!("Invalid bid status - bid_id: {}", bid_id);
-                    return Err(RestError::BadParameters("Invalid bid status".to_string()));
+                Err(e) => {
+                    tracing::error!(
+                        "DB: Failed to persist deferred bid, dropping it: {} - bid_id: {:?}",
+                        e,
+                        bid.core_fields.id
+                    );
+                    metrics::counter!("deferred_bids_dropped_total").increment(1);
+                    deferred_bids.lock().await.remove(&bid.core_fields.id);
+                    self.remove_bid_by_id(
+                        &(
+                            bid.core_fields.permission_key.clone(),
+                            bid.core_fields.chain_id.clone(),
+                        ),
+                        bid.core_fields.id,
+                    )
+                    .await;
+                    continue;
                 }
             }
+            deferred_bids.lock().await.remove(&bid.core_fields.id);
+            persisted += 1;
         }
+        metrics::gauge!("deferred_bids").set(deferred_bids.lock().await.len() as f64);
+        Ok(persisted)
     }
 
-    async fn remove_bid<T: SimulatedBidTrait>(&self, bid: T) {
-        let mut write_guard = self.bids.write().await;
-        let key = bid.get_auction_key();
-        let core_fields = bid.get_core_fields();
-        if let Entry::Occupied(mut entry) = write_guard.entry(key.clone()) {
-            let bids = entry.get_mut();
-            bids.retain(|b| b.get_core_fields().id != core_fields.id);
-            if bids.is_empty() {
-                entry.remove();
+    /// Persists a rejected bid along with the rejection reason.
+    pub async fn add_rejected_bid<T: Serialize>(
+        &self,
+        bid: &T,
+        chain_id: &ChainId,
+        profile_id: Option<models::ProfileId>,
+        error: &RestError,
+    ) {
+        let bid = match serde_json::to_value(bid) {
+            Ok(bid) => bid,
+            Err(e) => {
+                tracing::error!("Failed to serialize rejected bid: {}", e);
+                return;
+            }
+        };
+        let now = OffsetDateTime::now_utc();
+        if let Err(e) = sqlx::query(
+            "INSERT INTO rejected_bid (id, creation_time, chain_id, profile_id, bid, reason) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(PrimitiveDateTime::new(now.date(), now.time()))
+        .bind(chain_id)
+        .bind(profile_id)
+        .bind(bid)
+        .bind(error.to_status_and_message().1)
+        .execute(&self.db)
+        .await
+        {
+            tracing::error!("DB: Failed to insert rejected bid: {} - chain_id: {}", e, chain_id);
+        }
+    }
+
+    /// Deletes the rejected bids persisted before the given time and returns the number of deleted bids.
+    pub async fn delete_rejected_bids_before(&self, time: OffsetDateTime) -> anyhow::Result<u64> {
+        let result = sqlx::query("DELETE FROM rejected_bid WHERE creation_time < $1")
+            .bind(PrimitiveDateTime::new(time.date(), time.time()))
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Ranks a pending bid among the pending bids for the same permission key.
+    pub async fn get_bid_rank(&self, bid_id: BidId) -> Option<BidRank> {
+        let bids = self.bids.read().await;
+        let competing_bids = bids
+            .values()
+            .find(|bids| bids.iter().any(|bid| bid.get_core_fields().id == bid_id))?;
+        let mut pending_bids: Vec<SimulatedBidCoreFields> = competing_bids
+            .iter()
+            .map(|bid| bid.get_core_fields())
+            .filter(|core_fields| core_fields.status == BidStatus::Pending)
+            .collect();
+        pending_bids.sort_by(compare_pending_bids);
+        let position = pending_bids
+            .iter()
+            .position(|core_fields| core_fields.id == bid_id)?;
+        Some(BidRank {
+            position: position + 1,
+            total:    pending_bids.len(),
+        })
+    }
+
+    /// Returns the bids of the profile which are currently the best pending bid of their auction.
+    pub async fn get_leading_bids(&self, profile_id: models::ProfileId) -> Vec<SimulatedBid> {
+        self.bids
+            .read()
+            .await
+            .values()
+            .filter_map(|bids| {
+                bids.iter()
+                    .filter(|bid| bid.get_core_fields().status == BidStatus::Pending)
+                    .min_by(|a, b| compare_pending_bids(&a.get_core_fields(), &b.get_core_fields()))
+            })
+            .filter(|bid| bid.get_core_fields().profile_id == Some(profile_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the bids of the profile on the chains which are still in memory, i.e. their auctions are not concluded yet.
+    pub async fn get_live_bids(
+        &self,
+        profile_id: models::ProfileId,
+        chain_ids: &[ChainId],
+    ) -> Vec<SimulatedBid> {
+        self.bids
+            .read()
+            .await
+            .iter()
+            .filter(|((_, chain_id), _)| chain_ids.contains(chain_id))
+            .flat_map(|(_, bids)| bids.iter())
+            .filter(|bid| bid.get_core_fields().profile_id == Some(profile_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the spoof info of the token, finding it on chain if it is not known yet.
+    /// The found spoof info is stored along with the bytecode hash of the token, so it is kept across restarts.
+    pub async fn get_spoof_info(
+        &self,
+        chain_id: &ChainId,
+        token: Address,
+    ) -> anyhow::Result<SpoofInfo> {
+        let chain_store = self
+            .chains
+            .get(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain not found: {}", chain_id))?;
+        if let Some(spoof_info) = chain_store.token_spoof_info.read().await.get(&token) {
+            return Ok(spoof_info.clone());
+        }
+
+        let client = Arc::new(chain_store.provider.clone());
+        let spoof_info = token_spoof::find_spoof_info(token, client.clone())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Error finding spoof info: {:?}", e);
+                SpoofInfo::UnableToSpoof
+            });
+        chain_store
+            .token_spoof_info
+            .write()
+            .await
+            .insert(token, spoof_info.clone());
+
+        match token_spoof::get_bytecode_hash(token, client).await {
+            Ok(bytecode_hash) => {
+                let (balance_slot, allowance_slot) = match &spoof_info {
+                    SpoofInfo::Spoofed {
+                        balance_slot,
+                        allowance_slot,
+                    } => (
+                        Some(BigDecimal::from_str(&balance_slot.to_string()).unwrap()),
+                        Some(BigDecimal::from_str(&allowance_slot.to_string()).unwrap()),
+                    ),
+                    SpoofInfo::UnableToSpoof => (None, None),
+                };
+                if let Err(e) = sqlx::query(
+                    "INSERT INTO token_spoof_info (chain_id, token, bytecode_hash, balance_slot, allowance_slot) \
+                    VALUES ($1, $2, $3, $4, $5) ON CONFLICT (chain_id, token) DO UPDATE SET \
+                    bytecode_hash = EXCLUDED.bytecode_hash, balance_slot = EXCLUDED.balance_slot, \
+                    allowance_slot = EXCLUDED.allowance_slot, creation_time = NOW()",
+                )
+                .bind(chain_id)
+                .bind(token.as_bytes())
+                .bind(bytecode_hash.as_slice())
+                .bind(balance_slot)
+                .bind(allowance_slot)
+                .execute(&self.db)
+                .await
+                {
+                    tracing::error!(
+                        "DB: Failed to insert token spoof info: {} - chain_id: {} - token: {:?}",
+                        e,
+                        chain_id,
+                        token
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to get bytecode hash of token {:?}: {:?}", token, e);
             }
         }
+        Ok(spoof_info)
+    }
+
+    /// Loads the stored spoof info of the tokens on the configured chains.
+    /// The entries of the tokens whose bytecode has changed since they were stored are removed.
+    pub async fn load_token_spoof_info(&self) -> anyhow::Result<()> {
+        let chain_ids: Vec<ChainId> = self.chains.keys().cloned().collect();
+        let rows: Vec<(
+            ChainId,
+            Vec<u8>,
+            Vec<u8>,
+            Option<BigDecimal>,
+            Option<BigDecimal>,
+        )> = sqlx::query_as(
+            "SELECT chain_id, token, bytecode_hash, balance_slot, allowance_slot FROM token_spoof_info WHERE chain_id = ANY($1)",
+        )
+        .bind(&chain_ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        let results = join_all(rows.into_iter().map(
+            |(chain_id, token, bytecode_hash, balance_slot, allowance_slot)| async move {
+                let chain_store = self.chains.get(&chain_id)?;
+                let token = Address::from_slice(&token);
+                let client = Arc::new(chain_store.provider.clone());
+                match token_spoof::get_bytecode_hash(token, client).await {
+                    Ok(current_hash) if current_hash.as_slice() == bytecode_hash.as_slice() => {}
+                    Ok(_) => {
+                        if let Err(e) = sqlx::query(
+                            "DELETE FROM token_spoof_info WHERE chain_id = $1 AND token = $2",
+                        )
+                        .bind(&chain_id)
+                        .bind(token.as_bytes())
+                        .execute(&self.db)
+                        .await
+                        {
+                            tracing::error!(
+                                "DB: Failed to delete stale token spoof info: {} - chain_id: {} - token: {:?}",
+                                e,
+                                chain_id,
+                                token
+                            );
+                        }
+                        return Some(false);
+                    }
+                    // The entry is kept to be validated on the next restart
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to get bytecode hash of token {:?}: {:?}",
+                            token,
+                            e
+                        );
+                        return None;
+                    }
+                }
+                let spoof_info = match (balance_slot, allowance_slot) {
+                    (Some(balance_slot), Some(allowance_slot)) => SpoofInfo::Spoofed {
+                        balance_slot:   U256::from_dec_str(&balance_slot.to_string()).ok()?,
+                        allowance_slot: U256::from_dec_str(&allowance_slot.to_string()).ok()?,
+                    },
+                    _ => SpoofInfo::UnableToSpoof,
+                };
+                chain_store
+                    .token_spoof_info
+                    .write()
+                    .await
+                    .insert(token, spoof_info);
+                Some(true)
+            },
+        ))
+        .await;
+
+        tracing::info!(
+            "Loaded {} token spoof infos and removed {} stale ones",
+            results.iter().filter(|r| **r == Some(true)).count(),
+            results.iter().filter(|r| **r == Some(false)).count()
+        );
+        Ok(())
+    }
+
+    pub async fn get_bid_status(&self, bid_id: BidId) -> Result<Json<BidStatus>, RestError> {
+        self.get_bid_statuses(&[bid_id])
+            .await?
+            .remove(&bid_id)
+            .ok_or(RestError::BidNotFound)?
+            .map(Json)
+    }
+
+    /// Returns the statuses of the bids found in the database, fetching the bids and their auctions in two queries.
+    pub async fn get_bid_statuses(
+        &self,
+        bid_ids: &[BidId],
+    ) -> Result<HashMap<BidId, Result<BidStatus, RestError>>, RestError> {
+        let bids: Vec<models::Bid> = sqlx::query_as("SELECT * FROM bid WHERE id = ANY($1)")
+            .bind(bid_ids)
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("DB: Failed to get bids: {} - bid_ids: {:?}", e, bid_ids);
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })?;
+        Ok(self
+            .get_bids_with_auctions(bids)
+            .await?
+            .into_iter()
+            .map(|(bid, auction)| {
+                let bid_id = bid.id;
+                let status = BidStatus::try_from((bid, auction)).map_err(|e| {
+                    tracing::error!("Invalid bid status: {} - bid_id: {}", e, bid_id);
+                    RestError::BadParameters(e.to_string())
+                });
+                (bid_id, status)
+            })
+            .collect())
+    }
+
+    async fn remove_bid<T: SimulatedBidTrait>(&self, bid: T) {
+        self.remove_bid_by_id(&bid.get_auction_key(), bid.get_core_fields().id)
+            .await
+    }
+
+    async fn remove_bid_by_id(&self, key: &AuctionKey, bid_id: BidId) {
+        remove_bid_from(&mut *self.bids.write().await, key, bid_id);
     }
 
     pub async fn bids_for_submitted_auction(&self, auction: models::Auction) -> Vec<SimulatedBid> {
@@ -886,7 +2592,7 @@ impl Store {
             Some(tx_hash) => bids
                 .into_iter()
                 .filter(|bid| match bid.get_core_fields().status {
-                    BidStatus::Submitted { result, .. } => result == tx_hash,
+                    BidStatus::Submitted { result, .. } => result.to_vec() == tx_hash,
                     _ => false,
                 })
                 .collect(),
@@ -905,38 +2611,22 @@ impl Store {
 
         let mut write_guard = self.submitted_auctions.write().await;
         let key: String = auction.chain_id;
-        if let Entry::Occupied(mut entry) = write_guard.entry(key) {
+        if let Entry::Occupied(mut entry) = write_guard.entry(key.clone()) {
             let auctions = entry.get_mut();
+            let count = auctions.len();
             auctions.retain(|a| a.id != auction.id);
+            if auctions.len() < count {
+                self.last_auction_conclusions
+                    .write()
+                    .await
+                    .insert(key, OffsetDateTime::now_utc());
+            }
             if auctions.is_empty() {
                 entry.remove();
             }
         }
     }
 
-    async fn update_bid<T: SimulatedBidTrait>(&self, bid: T) {
-        let mut write_guard = self.bids.write().await;
-        let key = bid.get_auction_key();
-        let core_fields = bid.clone().get_core_fields();
-        match write_guard.entry(key.clone()) {
-            Entry::Occupied(mut entry) => {
-                let bids = entry.get_mut();
-                match bids
-                    .iter()
-                    .position(|b| b.get_core_fields().id == core_fields.id)
-                {
-                    Some(index) => bids[index] = bid.into(),
-                    None => {
-                        tracing::error!("Update bid failed - bid not found for: {:?}", bid);
-                    }
-                }
-            }
-            Entry::Vacant(_) => {
-                tracing::error!("Update bid failed - entry not found for key: {:?}", key);
-            }
-        }
-    }
-
     pub async fn broadcast_bid_status_and_update<T: SimulatedBidTrait>(
         &self,
         bid: T,
@@ -962,28 +2652,28 @@ impl Store {
                     )
                     .execute(&self.db)
                     .await?;
-
-                    let updated_bid = bid.update_status(updated_status.clone());
-                    self.update_bid(updated_bid).await;
                 } else {
                     return Err(anyhow::anyhow!(
                         "Cannot broadcast submitted bid status without auction."
                     ));
                 }
             }
-            BidStatus::Lost { result: _, index } => {
+            BidStatus::Lost { index, .. } => {
                 if let Some(auction) = auction {
                     match index {
                         Some(index) => {
-                            query_result = sqlx::query!(
-                                "UPDATE bid SET status = $1, metadata = jsonb_set(metadata, '{bundle_index}', $2), auction_id = $3 WHERE id = $4 AND status = 'submitted'",
-                                updated_status as _,
-                                json!(index),
-                                auction.id,
-                                core_fields.id
-                            )
-                            .execute(&self.db)
-                            .await?;
+                            query_result = match self
+                                .conclude_submitted_bid(
+                                    core_fields.id,
+                                    &updated_status,
+                                    index,
+                                    Some(auction.id),
+                                )
+                                .await?
+                            {
+                                Some(query_result) => query_result,
+                                None => return self.drop_bid_conclusion(bid).await,
+                            };
                         }
                         None => {
                             query_result = sqlx::query!(
@@ -1002,38 +2692,163 @@ impl Store {
                         updated_status as _,
                         core_fields.id
                     )
-                    .execute(&self.db)
-                    .await?;
+                    .execute(&self.db)
+                    .await?;
+                }
+            }
+            BidStatus::Won { result: _, index } => {
+                query_result = match self
+                    .conclude_submitted_bid(core_fields.id, &updated_status, index, None)
+                    .await?
+                {
+                    Some(query_result) => query_result,
+                    None => return self.drop_bid_conclusion(bid).await,
+                };
+            }
+            BidStatus::Expired => {
+                query_result =
+                    sqlx::query("UPDATE bid SET status = $1 WHERE id = $2 AND status = 'pending'")
+                        .bind(updated_status.clone())
+                        .bind(core_fields.id)
+                        .execute(&self.db)
+                        .await?;
+            }
+        }
+
+        // It is possible to call this function multiple times from different threads if receipts are delayed
+        // Or the new block is mined faster than the bid status is updated.
+        // Only the call which transitioned the bid in the db applies the update in memory and broadcasts it,
+        // so a failed or stale update never leaves the in-memory bid ahead of the db.
+        let is_applied = apply_bid_status_transition(
+            &mut *self.bids.write().await,
+            bid.update_status(updated_status.clone()).into(),
+            query_result.rows_affected(),
+        );
+        if is_applied {
+            record_bid_status_duration(&core_fields, &updated_status, auction);
+            self.broadcast_status_update(&core_fields, updated_status);
+        }
+        Ok(())
+    }
+
+    /// Removes a concluded bid whose submission was never persisted, so its conclusion could not be applied in the db.
+    /// The bid is concluded on chain anyway, and keeping it would hold its submitted auction in memory forever.
+    async fn drop_bid_conclusion<T: SimulatedBidTrait>(&self, bid: T) -> anyhow::Result<()> {
+        self.remove_bid(bid).await;
+        Ok(())
+    }
+
+    /// Updates a submitted bid to its concluded status. The conclusion can be seen before the submission of the
+    /// bid is persisted, so the update is retried for a while as long as the bid is still pending.
+    /// Returns None if the bid is still pending after the retries and the conclusion is dropped.
+    async fn conclude_submitted_bid(
+        &self,
+        bid_id: BidId,
+        updated_status: &BidStatus,
+        index: u32,
+        auction_id: Option<models::AuctionId>,
+    ) -> anyhow::Result<Option<PgQueryResult>> {
+        let status_label = (
+            "status",
+            match updated_status {
+                BidStatus::Won { .. } => "won",
+                _ => "lost",
+            }
+            .to_string(),
+        );
+        let mut retries = 0;
+        loop {
+            let query_result = sqlx::query(
+                "UPDATE bid SET status = $1, metadata = jsonb_set(metadata, '{bundle_index}', $2), auction_id = COALESCE($3, auction_id) WHERE id = $4 AND status = 'submitted'",
+            )
+            .bind(updated_status.clone())
+            .bind(json!(index))
+            .bind(auction_id)
+            .bind(bid_id)
+            .execute(&self.db)
+            .await?;
+            let is_pending: Option<bool> = if query_result.rows_affected() > 0 {
+                None
+            } else {
+                sqlx::query_scalar("SELECT status = 'pending' FROM bid WHERE id = $1")
+                    .bind(bid_id)
+                    .fetch_optional(&self.db)
+                    .await?
+            };
+            if is_pending != Some(true) {
+                if retries > 0 {
+                    metrics::counter!(
+                        "bid_status_reorders_total",
+                        &[status_label, ("result", "applied".to_string())]
+                    )
+                    .increment(1);
                 }
-                self.remove_bid(bid.clone()).await;
+                return Ok(Some(query_result));
             }
-            BidStatus::Won { result: _, index } => {
-                query_result = sqlx::query!(
-                    "UPDATE bid SET status = $1, metadata = jsonb_set(metadata, '{bundle_index}', $2) WHERE id = $3 AND status = 'submitted'",
-                    updated_status as _,
-                    json!(index),
-                    core_fields.id
+            if retries >= self.bid_status_reorder_retries {
+                tracing::warn!(
+                    "Bid {} is still pending after {} retries, dropping the {} status",
+                    bid_id,
+                    retries,
+                    status_label.1
+                );
+                metrics::counter!(
+                    "bid_status_reorders_total",
+                    &[status_label, ("result", "failed".to_string())]
                 )
-                .execute(&self.db)
-                .await?;
-                self.remove_bid(bid.clone()).await;
+                .increment(1);
+                return Ok(None);
             }
+            retries += 1;
+            tokio::time::sleep(self.bid_status_reorder_delay).await;
         }
+    }
 
-        // It is possible to call this function multiple times from different threads if receipts are delayed
-        // Or the new block is mined faster than the bid status is updated.
-        // To ensure we do not broadcast the update more than once, we need to check the below "if"
-        if query_result.rows_affected() > 0 {
-            self.broadcast_status_update(BidStatusWithId {
-                id:         core_fields.id,
-                bid_status: updated_status,
-            });
+    pub fn broadcast_auction_conclusion(
+        &self,
+        auction: &models::Auction,
+        winning_amount: BidAmount,
+    ) {
+        let concluded_auction = ConcludedAuction {
+            id:              auction.id,
+            chain_id:        auction.chain_id.clone(),
+            permission_key:  Bytes::from(auction.permission_key.clone()),
+            // The hash is validated when the auction is concluded
+            result:          auction
+                .tx_hash
+                .as_deref()
+                .and_then(|tx_hash| TxHash::from_bytes(&auction.chain_type, tx_hash).ok()),
+            winning_amount:  self
+                .ws
+                .reveal_winning_amounts
+                .then(|| winning_amount.to_string()),
+            conclusion_time: auction
+                .conclusion_time
+                .map(|time| time.assume_utc())
+                .unwrap_or_else(OffsetDateTime::now_utc),
+        };
+        if let Err(e) = self
+            .event_sender
+            .send(UpdateEvent::AuctionConcluded(concluded_auction))
+        {
+            tracing::error!("Failed to send auction conclusion update: {}", e);
         }
-        Ok(())
     }
 
-    fn broadcast_status_update(&self, update: BidStatusWithId) {
-        match self.event_sender.send(UpdateEvent::BidStatusUpdate(update)) {
+    fn broadcast_status_update(&self, core_fields: &SimulatedBidCoreFields, bid_status: BidStatus) {
+        let status = BidStatusWithId {
+            id: core_fields.id,
+            bid_status,
+        };
+        let target = BidStatusUpdateTarget {
+            chain_id:       core_fields.chain_id.clone(),
+            permission_key: core_fields.permission_key.clone(),
+            profile_id:     core_fields.profile_id,
+        };
+        match self
+            .event_sender
+            .send(UpdateEvent::BidStatusUpdate { status, target })
+        {
             Ok(_) => (),
             Err(e) => tracing::error!("Failed to send bid status update: {}", e),
         };
@@ -1048,6 +2863,71 @@ impl Store {
             .clone()
     }
 
+    /// Acquires the lock of the auction, giving up after the auction lock timeout instead of waiting forever
+    /// on a lock which is never released.
+    pub async fn try_lock_auction(&self, key: AuctionKey) -> anyhow::Result<AuctionLockGuard<'_>> {
+        let auction_lock = self.get_auction_lock(key.clone()).await;
+        match tokio::time::timeout(self.auction_lock_timeout, auction_lock.lock_owned()).await {
+            Ok(guard) => {
+                if let Ok(mut held_locks) = self.held_auction_locks.lock() {
+                    held_locks.insert(key.clone(), Instant::now());
+                }
+                Ok(AuctionLockGuard {
+                    _guard: guard,
+                    key,
+                    held_locks: &self.held_auction_locks,
+                })
+            }
+            Err(_) => {
+                metrics::counter!(
+                    "auction_lock_timeouts_total",
+                    &[("chain_id", key.1.clone())]
+                )
+                .increment(1);
+                Err(anyhow::anyhow!(
+                    "Timed out waiting for the auction lock of {} on chain {}",
+                    key.0,
+                    key.1
+                ))
+            }
+        }
+    }
+
+    /// Returns the auction locks which have been held for longer than the auction lock timeout, with their
+    /// hold time. The submissions waiting for these locks time out as well.
+    pub fn get_stuck_auction_locks(&self) -> Vec<(AuctionKey, Duration)> {
+        match self.held_auction_locks.lock() {
+            Ok(held_locks) => held_locks
+                .iter()
+                .map(|(key, acquired_at)| (key.clone(), acquired_at.elapsed()))
+                .filter(|(_, held_for)| *held_for > self.auction_lock_timeout)
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns the number of the in-memory bids of each chain which has any.
+    pub async fn get_bid_counts_by_chain(&self) -> HashMap<ChainId, usize> {
+        let mut counts: HashMap<ChainId, usize> = HashMap::new();
+        for ((_, chain_id), bids) in self.bids.read().await.iter() {
+            *counts.entry(chain_id.clone()).or_default() += bids.len();
+        }
+        counts
+    }
+
+    pub async fn get_auction_locks_count(&self) -> usize {
+        self.auction_lock.lock().await.len()
+    }
+
+    /// Returns true if the auction of the key is being submitted, i.e. its lock is held.
+    async fn is_auction_in_progress(&self, key: &AuctionKey) -> bool {
+        self.auction_lock
+            .lock()
+            .await
+            .get(key)
+            .is_some_and(|auction_lock| auction_lock.try_lock().is_err())
+    }
+
     pub async fn remove_auction_lock(&self, key: &AuctionKey) {
         let mut mutex_gaurd = self.auction_lock.lock().await;
         let auction_lock = mutex_gaurd.get(key);
@@ -1061,24 +2941,242 @@ impl Store {
 
     pub async fn create_profile(
         &self,
+        actor: &Auth,
         create_profile: ApiProfile::CreateProfile,
     ) -> Result<models::Profile, RestError> {
         let id = Uuid::new_v4();
+        let mut tx = self.begin_transaction().await?;
         let profile: models::Profile = sqlx::query_as(
-            "INSERT INTO profile (id, name, email) VALUES ($1, $2, $3) RETURNING id, name, email, created_at, updated_at",
+            "INSERT INTO profile (id, name, email, tier) VALUES ($1, $2, $3, $4) RETURNING id, name, email, tier, created_at, updated_at",
         ).bind(id)
         .bind(create_profile.name.clone())
-        .bind(create_profile.email.to_string()).fetch_one(&self.db).await
+        .bind(create_profile.email.to_string())
+        .bind(create_profile.tier).fetch_one(&mut *tx).await
         .map_err(|e| {
             if let Some(true) = e.as_database_error().map(|e| e.is_unique_violation()) {
                 return RestError::BadParameters("Profile with this email already exists".to_string());
             }
             tracing::error!("DB: Failed to insert profile: {} - profile_data: {:?}", e, create_profile);
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
         })?;
+        Self::add_admin_audit_in_transaction(
+            &mut tx,
+            actor,
+            "create_profile",
+            &create_profile,
+            profile.id,
+            None,
+        )
+        .await?;
+        Self::commit_transaction(tx).await?;
         Ok(profile)
     }
 
+    async fn begin_transaction(&self) -> Result<Transaction<'static, Postgres>, RestError> {
+        self.db.begin().await.map_err(|e| {
+            tracing::error!("DB: Failed to begin transaction: {}", e);
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })
+    }
+
+    async fn commit_transaction(tx: Transaction<'static, Postgres>) -> Result<(), RestError> {
+        tx.commit().await.map_err(|e| {
+            tracing::error!("DB: Failed to commit transaction: {}", e);
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })
+    }
+
+    /// Records a change of a profile or of its access tokens in the admin audit log, as part of the transaction
+    /// making the change so the log cannot drift from it. The changes are persisted even if the admin audit
+    /// is disabled.
+    async fn add_admin_audit_in_transaction<T: Serialize>(
+        tx: &mut Transaction<'static, Postgres>,
+        actor: &Auth,
+        action: &str,
+        parameters: &T,
+        target_profile_id: models::ProfileId,
+        target_token_id: Option<models::TokenId>,
+    ) -> Result<(), RestError> {
+        let (admin_identity, actor_profile_id) = Self::get_admin_identity(actor);
+        let parameters = Self::trace_admin_action(action, &admin_identity, parameters);
+        let now = OffsetDateTime::now_utc();
+        sqlx::query(
+            "INSERT INTO admin_audit (id, creation_time, action, parameters, admin_identity, actor_profile_id, target_profile_id, target_token_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(PrimitiveDateTime::new(now.date(), now.time()))
+        .bind(action)
+        .bind(parameters)
+        .bind(admin_identity)
+        .bind(actor_profile_id)
+        .bind(target_profile_id)
+        .bind(target_token_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "DB: Failed to insert admin audit: {} - action: {}, target_profile_id: {}",
+                e,
+                action,
+                target_profile_id
+            );
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })?;
+        Ok(())
+    }
+
+    /// Returns the identity recorded in the admin audit log for the actor, along with its profile id if any.
+    fn get_admin_identity(auth: &Auth) -> (String, Option<models::ProfileId>) {
+        match auth {
+            Auth::Admin => ("secret_key".to_string(), None),
+            Auth::Authorized(_, profile) => (format!("profile:{}", profile.id), Some(profile.id)),
+            Auth::Unauthorized => ("unauthorized".to_string(), None),
+        }
+    }
+
+    /// Traces an admin action and returns its parameters serialized for the admin audit log.
+    fn trace_admin_action<T: Serialize>(
+        action: &str,
+        admin_identity: &str,
+        parameters: &T,
+    ) -> serde_json::Value {
+        let parameters = match serde_json::to_value(parameters) {
+            Ok(parameters) => parameters,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to serialize admin action parameters: {} - action: {}",
+                    e,
+                    action
+                );
+                serde_json::Value::Null
+            }
+        };
+        tracing::info!(
+            action = action,
+            admin_identity = admin_identity,
+            parameters = %parameters,
+            "Admin action"
+        );
+        parameters
+    }
+
+    /// Records an action taken by an admin.
+    ///
+    /// The action is always traced and is persisted in the admin audit log if enabled.
+    pub async fn add_admin_audit<T: Serialize>(&self, auth: &Auth, action: &str, parameters: &T) {
+        let (admin_identity, actor_profile_id) = Self::get_admin_identity(auth);
+        let parameters = Self::trace_admin_action(action, &admin_identity, parameters);
+        if !self.admin_audit {
+            return;
+        }
+
+        let now = OffsetDateTime::now_utc();
+        if let Err(e) = sqlx::query(
+            "INSERT INTO admin_audit (id, creation_time, action, parameters, admin_identity, actor_profile_id) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(PrimitiveDateTime::new(now.date(), now.time()))
+        .bind(action)
+        .bind(parameters)
+        .bind(admin_identity)
+        .bind(actor_profile_id)
+        .execute(&self.db)
+        .await
+        {
+            tracing::error!("DB: Failed to insert admin audit: {} - action: {}", e, action);
+        }
+    }
+
+    /// Returns at most 20 admin audit entries which were created after the given time.
+    /// If a profile is given, only the entries of the actions taken by or on the profile are returned.
+    pub async fn get_admin_audits_by_time(
+        &self,
+        profile_id: Option<models::ProfileId>,
+        from_time: Option<OffsetDateTime>,
+    ) -> Result<Vec<models::AdminAudit>, RestError> {
+        let mut query = QueryBuilder::new("SELECT * from admin_audit WHERE TRUE");
+        if let Some(profile_id) = profile_id {
+            query.push(" AND (target_profile_id = ");
+            query.push_bind(profile_id);
+            query.push(" OR actor_profile_id = ");
+            query.push_bind(profile_id);
+            query.push(")");
+        }
+        if let Some(from_time) = from_time {
+            query.push(" AND creation_time >= ");
+            query.push_bind(PrimitiveDateTime::new(from_time.date(), from_time.time()));
+        }
+        query.push(" ORDER BY creation_time ASC LIMIT 20");
+        query
+            .build_query_as()
+            .fetch_all(&self.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("DB: Failed to fetch admin audits: {}", e);
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })
+    }
+
+    /// Returns the chains which the permission key was used on before the given time, by the bids or the opportunities.
+    pub async fn get_permission_key_chains(
+        &self,
+        permission_key: &PermissionKey,
+        before: OffsetDateTime,
+    ) -> Result<Vec<ChainId>, RestError> {
+        sqlx::query_scalar(
+            "SELECT chain_id FROM bid WHERE permission_key = $1 AND initiation_time < $2 UNION SELECT chain_id FROM opportunity WHERE permission_key = $1 AND creation_time < $2",
+        )
+        .bind(permission_key.to_vec())
+        .bind(before)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "DB: Failed to fetch permission key chains: {} - permission_key: {}",
+                e,
+                permission_key
+            );
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })
+    }
+
+    /// Aggregates the auctions created on the chain in the given time range, along with their bids.
+    pub async fn get_chain_stats(
+        &self,
+        chain_id: &ChainId,
+        from_time: OffsetDateTime,
+        to_time: OffsetDateTime,
+    ) -> Result<models::ChainStats, RestError> {
+        sqlx::query_as(
+            "WITH chain_auction AS (
+                SELECT id, submission_time, conclusion_time FROM auction
+                WHERE chain_id = $1 AND creation_time >= $2 AND creation_time < $3
+            ), chain_bid AS (
+                SELECT bid.status, bid.bid_amount FROM bid JOIN chain_auction ON bid.auction_id = chain_auction.id
+            )
+            SELECT
+                (SELECT COUNT(*) FROM chain_auction) AS auction_count,
+                (SELECT COUNT(*) FROM chain_bid) AS bid_count,
+                (SELECT COUNT(*) FROM chain_bid WHERE status = 'won') AS won_bid_count,
+                (SELECT COUNT(*) FROM chain_bid WHERE status IN ('won', 'lost')) AS concluded_bid_count,
+                (SELECT COALESCE(SUM(bid_amount), 0) FROM chain_bid WHERE status = 'won') AS total_fees,
+                (SELECT AVG(EXTRACT(EPOCH FROM conclusion_time - submission_time))::FLOAT8 FROM chain_auction) AS average_settlement_latency",
+        )
+        .bind(chain_id)
+        .bind(PrimitiveDateTime::new(from_time.date(), from_time.time()))
+        .bind(PrimitiveDateTime::new(to_time.date(), to_time.time()))
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "DB: Failed to fetch chain stats: {} - chain_id: {}",
+                e,
+                chain_id
+            );
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
+        })
+    }
+
     fn generate_url_safe_token(&self) -> anyhow::Result<String> {
         let mut rng = rand::thread_rng();
         let bytes: [u8; 32] = rng.gen();
@@ -1095,37 +3193,50 @@ impl Store {
             .await
             .map_err(|e| {
                 tracing::error!("DB: Failed to fetch profile: {} - id: {}", e, id);
-                RestError::TemporarilyUnavailable
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
             })
     }
 
+    /// Returns the valid access token of the profile with exactly the given scopes, creating it if there is none.
     pub async fn get_or_create_access_token(
         &self,
+        actor: &Auth,
         profile_id: models::ProfileId,
+        scopes: Vec<models::AccessTokenScope>,
     ) -> Result<GetOrCreate<models::AccessToken>, RestError> {
+        let mut scopes = scopes;
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(RestError::BadParameters(
+                "Access token should have at least one scope".to_string(),
+            ));
+        }
         let generated_token = self.generate_url_safe_token().map_err(|e| {
             tracing::error!(
                 "Failed to generate access token: {} - profile_id: {}",
                 e,
                 profile_id
             );
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Internal)
         })?;
 
         let id = Uuid::new_v4();
-        let result = sqlx::query!(
-            "INSERT INTO access_token (id, profile_id, token)
-        SELECT $1, $2, $3
+        let mut tx = self.begin_transaction().await?;
+        let result = sqlx::query(
+            "INSERT INTO access_token (id, profile_id, token, scopes)
+        SELECT $1, $2, $3, $4
         WHERE NOT EXISTS (
             SELECT id
             FROM access_token
-            WHERE profile_id = $2 AND revoked_at is NULL
+            WHERE profile_id = $2 AND scopes = $4 AND revoked_at is NULL
         );",
-            id,
-            profile_id,
-            generated_token
         )
-        .execute(&self.db)
+        .bind(id)
+        .bind(profile_id)
+        .bind(generated_token)
+        .bind(&scopes)
+        .execute(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!(
@@ -1133,16 +3244,16 @@ impl Store {
                 e,
                 profile_id
             );
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
         })?;
 
-        let token = sqlx::query_as!(
-            models::AccessToken,
+        let token: models::AccessToken = sqlx::query_as(
             "SELECT * FROM access_token
-        WHERE profile_id = $1 AND revoked_at is NULL;",
-            profile_id,
+        WHERE profile_id = $1 AND scopes = $2 AND revoked_at is NULL;",
         )
-        .fetch_one(&self.db)
+        .bind(profile_id)
+        .bind(&scopes)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!(
@@ -1150,33 +3261,61 @@ impl Store {
                 e,
                 profile_id
             );
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
         })?;
+        let created = result.rows_affected() > 0;
+        if created {
+            Self::add_admin_audit_in_transaction(
+                &mut tx,
+                actor,
+                "create_access_token",
+                &json!({ "profile_id": profile_id, "scopes": scopes }),
+                profile_id,
+                Some(token.id),
+            )
+            .await?;
+        }
+        Self::commit_transaction(tx).await?;
 
         let profile = self.get_profile_by_id(profile_id).await?;
         self.access_tokens
             .write()
             .await
-            .insert(token.token.clone(), profile);
-        Ok((token, result.rows_affected() > 0))
+            .insert(token.token.clone(), (profile, token.scopes.clone()));
+        Ok((token, created))
     }
 
     pub async fn revoke_access_token(
         &self,
+        actor: &Auth,
         token: &models::AccessTokenToken,
     ) -> Result<(), RestError> {
-        sqlx::query!(
+        let mut tx = self.begin_transaction().await?;
+        let revoked: Option<(models::TokenId, models::ProfileId)> = sqlx::query_as(
             "UPDATE access_token
         SET revoked_at = now()
-        WHERE token = $1 AND revoked_at is NULL;",
-            token
+        WHERE token = $1 AND revoked_at is NULL
+        RETURNING id, profile_id;",
         )
-        .execute(&self.db)
+        .bind(token)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!("DB: Failed to revoke access token: {}", e);
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Db)
         })?;
+        if let Some((token_id, profile_id)) = revoked {
+            Self::add_admin_audit_in_transaction(
+                &mut tx,
+                actor,
+                "revoke_access_token",
+                &(),
+                profile_id,
+                Some(token_id),
+            )
+            .await?;
+        }
+        Self::commit_transaction(tx).await?;
 
         self.access_tokens.write().await.remove(token);
         Ok(())
@@ -1190,29 +3329,107 @@ impl Store {
             .read()
             .await
             .get(token)
-            .cloned()
+            .map(|(profile, _)| profile.clone())
             .ok_or(RestError::InvalidToken)
     }
 
+    /// Returns the scopes of the access token, which are empty if the token is not valid.
+    pub async fn get_access_token_scopes(
+        &self,
+        token: &models::AccessTokenToken,
+    ) -> Vec<models::AccessTokenScope> {
+        self.access_tokens
+            .read()
+            .await
+            .get(token)
+            .map(|(_, scopes)| scopes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns a page of the bids of the profile ordered by the initiation time and id, along with the cursor
+    /// of the next page. The cursor is the id of the last bid of the page and None if there is no next page.
     async fn get_bids_by_time(
         &self,
         profile_id: models::ProfileId,
         from_time: Option<OffsetDateTime>,
-    ) -> Result<Vec<models::Bid>, RestError> {
+        to_time: Option<OffsetDateTime>,
+        after_id: Option<BidId>,
+        limit: Option<usize>,
+    ) -> Result<(Vec<models::Bid>, Option<BidId>), RestError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_BIDS_PAGE_LIMIT)
+            .clamp(1, MAX_BIDS_PAGE_LIMIT);
         let mut query = QueryBuilder::new("SELECT * from bid where profile_id = ");
         query.push_bind(profile_id);
         if let Some(from_time) = from_time {
             query.push(" AND initiation_time >= ");
             query.push_bind(from_time);
         }
-        query.push(" ORDER BY initiation_time ASC LIMIT 20");
+        if let Some(to_time) = to_time {
+            query.push(" AND initiation_time <= ");
+            query.push_bind(to_time);
+        }
+        if let Some(after_id) = after_id {
+            query.push(
+                " AND (initiation_time, id) > (SELECT initiation_time, id FROM bid WHERE id = ",
+            );
+            query.push_bind(after_id);
+            query.push(")");
+        }
+        // Fetch one more bid than the limit to know whether there is a next page
+        query.push(" ORDER BY initiation_time ASC, id ASC LIMIT ");
+        query.push_bind(limit as i64 + 1);
+        let mut bids: Vec<models::Bid> =
+            query
+                .build_query_as()
+                .fetch_all(&self.db)
+                .await
+                .map_err(|e| {
+                    tracing::error!("DB: Failed to fetch bids: {}", e);
+                    RestError::TemporarilyUnavailable(UnavailableReason::Db)
+                })?;
+        let next_cursor = if bids.len() > limit {
+            bids.truncate(limit);
+            bids.last().map(|bid| bid.id)
+        } else {
+            None
+        };
+        Ok((bids, next_cursor))
+    }
+
+    /// Returns the auctions of the chain created since the time, ordered by their creation time.
+    pub async fn get_auctions_by_time(
+        &self,
+        chain_id: &ChainId,
+        from_time: Option<OffsetDateTime>,
+        limit: Option<usize>,
+    ) -> Result<Vec<models::AuctionWithWonBids>, RestError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_BIDS_PAGE_LIMIT)
+            .clamp(1, MAX_BIDS_PAGE_LIMIT);
+        let mut query = QueryBuilder::new(
+            "SELECT auction.*, (SELECT COUNT(*) FROM bid WHERE bid.auction_id = auction.id AND bid.status = 'won') AS won_bid_count
+            FROM auction WHERE chain_id = ",
+        );
+        query.push_bind(chain_id);
+        if let Some(from_time) = from_time {
+            query.push(" AND creation_time >= ");
+            query.push_bind(from_time);
+        }
+        query.push(" ORDER BY creation_time ASC LIMIT ");
+        query.push_bind(limit as i64);
         query
             .build_query_as()
             .fetch_all(&self.db)
             .await
             .map_err(|e| {
-                tracing::error!("DB: Failed to fetch bids: {}", e);
-                RestError::TemporarilyUnavailable
+                tracing::error!(
+                    "DB: Failed to fetch auctions: {} - chain_id: {} - from_time: {:?}",
+                    e,
+                    chain_id,
+                    from_time,
+                );
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
             })
     }
 
@@ -1221,6 +3438,9 @@ impl Store {
         chain_id: ChainId,
         permission_key: Option<PermissionKey>,
         from_time: Option<OffsetDateTime>,
+        to_time: Option<OffsetDateTime>,
+        min_target_call_value: Option<U256>,
+        include_removed: bool,
     ) -> Result<Vec<OpportunityParamsWithMetadata>, RestError> {
         let mut query = QueryBuilder::new("SELECT * from opportunity where chain_id = ");
         query.push_bind(chain_id.clone());
@@ -1232,6 +3452,18 @@ impl Store {
             query.push(" AND creation_time >= ");
             query.push_bind(from_time);
         }
+        if let Some(to_time) = to_time {
+            query.push(" AND creation_time <= ");
+            query.push_bind(to_time);
+        }
+        if let Some(min_target_call_value) = min_target_call_value {
+            // Compared as numeric, the U256 value is converted through its exact decimal representation
+            query.push(" AND target_call_value >= ");
+            query.push_bind(
+                BigDecimal::from_str(&min_target_call_value.to_string())
+                    .expect("U256 is a valid decimal"),
+            );
+        }
         query.push(" ORDER BY creation_time ASC LIMIT 20");
         let opps: Vec<models::Opportunity> = query
             .build_query_as()
@@ -1239,13 +3471,14 @@ impl Store {
             .await
             .map_err(|e| {
                 tracing::error!(
-                    "DB: Failed to fetch opportunities: {} - chain_id: {:?} - permission_key: {:?} - from_time: {:?}",
+                    "DB: Failed to fetch opportunities: {} - chain_id: {:?} - permission_key: {:?} - from_time: {:?} - to_time: {:?}",
                     e,
                     chain_id,
                     permission_key,
                     from_time,
+                    to_time,
                 );
-                RestError::TemporarilyUnavailable
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
             })?;
         let parsed_opps: anyhow::Result<Vec<OpportunityParamsWithMetadata>> = opps
             .into_iter()
@@ -1261,23 +3494,39 @@ impl Store {
                     sell_tokens:       serde_json::from_value(opp.sell_tokens)?,
                     buy_tokens:        serde_json::from_value(opp.buy_tokens)?,
                 });
-                let opp = Opportunity {
-                    id: opp.id,
-                    creation_time: opp.creation_time.assume_utc().unix_timestamp_nanos(),
+                let mut opportunity = Opportunity::new(
+                    opp.id,
+                    opp.creation_time.assume_utc().unix_timestamp_nanos(),
                     params,
-                };
-                Ok(opp.into())
+                );
+                if let (Some(sell_tokens), Some(buy_tokens)) =
+                    (opp.remaining_sell_tokens, opp.remaining_buy_tokens)
+                {
+                    opportunity.remaining = OpportunityAmounts {
+                        sell_tokens: serde_json::from_value(sell_tokens)?,
+                        buy_tokens:  serde_json::from_value(buy_tokens)?,
+                    };
+                }
+                let opportunity: OpportunityParamsWithMetadata = opportunity.into();
+                Ok(match include_removed {
+                    true => opportunity.with_removal(
+                        opp.removal_time.map(|time| time.assume_utc()),
+                        opp.removal_reason,
+                    ),
+                    false => opportunity,
+                })
             })
             .collect();
         parsed_opps.map_err(|e| {
             tracing::error!(
-                "Failed to convert opportunity to OpportunityParamsWithMetadata: {} - chain_id: {:?} - permission_key: {:?} - from_time: {:?}",
+                "Failed to convert opportunity to OpportunityParamsWithMetadata: {} - chain_id: {:?} - permission_key: {:?} - from_time: {:?} - to_time: {:?}",
                 e,
                 chain_id,
                 permission_key,
                 from_time,
+                to_time,
             );
-            RestError::TemporarilyUnavailable
+            RestError::TemporarilyUnavailable(UnavailableReason::Internal)
         })
     }
 
@@ -1285,33 +3534,123 @@ impl Store {
         &self,
         bids: &[models::Bid],
     ) -> Result<Vec<models::Auction>, RestError> {
-        let auction_ids: Vec<models::AuctionId> =
+        let mut auction_ids: Vec<models::AuctionId> =
             bids.iter().filter_map(|bid| bid.auction_id).collect();
+        auction_ids.sort();
+        auction_ids.dedup();
+        if auction_ids.is_empty() {
+            return Ok(vec![]);
+        }
         sqlx::query_as("SELECT * FROM auction WHERE id = ANY($1)")
             .bind(auction_ids)
             .fetch_all(&self.db)
             .await
             .map_err(|e| {
                 tracing::error!("DB: Failed to fetch auctions: {}", e);
-                RestError::TemporarilyUnavailable
+                RestError::TemporarilyUnavailable(UnavailableReason::Db)
+            })
+    }
+
+    /// Pairs the bids with their auctions, fetching all the auctions in a single query.
+    async fn get_bids_with_auctions(
+        &self,
+        bids: Vec<models::Bid>,
+    ) -> Result<Vec<(models::Bid, Option<models::Auction>)>, RestError> {
+        let auctions: HashMap<models::AuctionId, models::Auction> = self
+            .get_auctions_by_bids(&bids)
+            .await?
+            .into_iter()
+            .map(|auction| (auction.id, auction))
+            .collect();
+        Ok(bids
+            .into_iter()
+            .map(|bid| {
+                let auction = bid
+                    .auction_id
+                    .and_then(|auction_id| auctions.get(&auction_id).cloned());
+                (bid, auction)
             })
+            .collect())
+    }
+
+    /// Restores the pending and submitted bids of the configured chains, along with the submitted auctions,
+    /// so the auctions which were in flight when the server stopped are carried on after a restart.
+    pub async fn restore_bids_from_db(&self) -> anyhow::Result<()> {
+        let chain_ids: Vec<ChainId> = self
+            .chains
+            .keys()
+            .chain(self.chains_svm.keys())
+            .cloned()
+            .collect();
+        let bids: Vec<models::Bid> = sqlx::query_as(
+            "SELECT * FROM bid WHERE status IN ('pending', 'submitted') AND chain_id = ANY($1)",
+        )
+        .bind(&chain_ids)
+        .fetch_all(&self.db)
+        .await?;
+        let auctions = self
+            .get_auctions_by_bids(&bids)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch auctions of the bids: {:?}", e))?;
+        let auctions_by_id: HashMap<models::AuctionId, &models::Auction> = auctions
+            .iter()
+            .map(|auction| (auction.id, auction))
+            .collect();
+
+        let mut restored_bids: HashMap<AuctionKey, Vec<SimulatedBid>> = HashMap::new();
+        for bid in bids {
+            let auction = bid
+                .auction_id
+                .and_then(|auction_id| auctions_by_id.get(&auction_id).map(|a| (*a).clone()));
+            let result: anyhow::Result<SimulatedBid> = (bid.clone(), auction).try_into();
+            match result {
+                Ok(simulated_bid) => restored_bids
+                    .entry(simulated_bid.get_auction_key())
+                    .or_default()
+                    .push(simulated_bid),
+                Err(e) => {
+                    tracing::error!("Failed to restore bid: {} - bid: {:?}", e, bid);
+                }
+            }
+        }
+
+        let mut submitted_auctions: HashMap<ChainId, Vec<models::Auction>> = HashMap::new();
+        for auction in auctions
+            .into_iter()
+            .filter(|auction| auction.tx_hash.is_some() && auction.conclusion_time.is_none())
+        {
+            submitted_auctions
+                .entry(auction.chain_id.clone())
+                .or_default()
+                .push(auction);
+        }
+
+        tracing::info!(
+            "Restored {} bids and {} submitted auctions from the database",
+            restored_bids.values().map(Vec::len).sum::<usize>(),
+            submitted_auctions.values().map(Vec::len).sum::<usize>()
+        );
+        *self.bids.write().await = restored_bids;
+        *self.submitted_auctions.write().await = submitted_auctions;
+        Ok(())
     }
 
     pub async fn get_simulated_bids_by_time(
         &self,
         profile_id: models::ProfileId,
         from_time: Option<OffsetDateTime>,
-    ) -> Result<Vec<SimulatedBid>, RestError> {
-        let bids = self.get_bids_by_time(profile_id, from_time).await?;
-        let auctions = self.get_auctions_by_bids(&bids).await?;
-
-        Ok(bids
+        to_time: Option<OffsetDateTime>,
+        after_id: Option<BidId>,
+        limit: Option<usize>,
+    ) -> Result<(Vec<SimulatedBid>, Option<BidId>), RestError> {
+        let (bids, next_cursor) = self
+            .get_bids_by_time(profile_id, from_time, to_time, after_id, limit)
+            .await?;
+        let bids = self
+            .get_bids_with_auctions(bids)
+            .await?
             .into_iter()
-            .filter_map(|b| {
-                let auction = match b.auction_id {
-                    Some(auction_id) => auctions.clone().into_iter().find(|a| a.id == auction_id),
-                    None => None,
-                };
+            .filter_map(|(b, auction)| {
                 let result: anyhow::Result<SimulatedBid> = (b.clone(), auction).try_into();
                 match result {
                     Ok(bid) => Some(bid),
@@ -1325,6 +3664,308 @@ impl Store {
                     }
                 }
             })
-            .collect())
+            .collect();
+        Ok((bids, next_cursor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid(
+        permission_key: &PermissionKey,
+        chain_id: &str,
+        bid_amount: u64,
+        status: BidStatus,
+    ) -> SimulatedBid {
+        SimulatedBid::Evm(SimulatedBidEvm {
+            core_fields:     SimulatedBidCoreFields {
+                id: Uuid::new_v4(),
+                bid_amount: BidAmount::from(bid_amount),
+                permission_key: permission_key.clone(),
+                chain_id: chain_id.to_string(),
+                status,
+                initiation_time: OffsetDateTime::UNIX_EPOCH,
+                profile_id: None,
+                good_till: None,
+            },
+            target_contract: Address::zero(),
+            target_calldata: Bytes::default(),
+            gas_limit:       U256::from(100_000),
+        })
+    }
+
+    fn add_bids(bids: &mut HashMap<AuctionKey, Vec<SimulatedBid>>, new_bids: Vec<SimulatedBid>) {
+        for bid in new_bids {
+            bids.entry(bid.get_auction_key()).or_default().push(bid);
+        }
+    }
+
+    #[test]
+    fn test_rank_permission_keys_by_best_pending_bid() {
+        let (low, high, middle) = (
+            PermissionKey::from(vec![1]),
+            PermissionKey::from(vec![2]),
+            PermissionKey::from(vec![3]),
+        );
+        let mut bids = HashMap::new();
+        add_bids(
+            &mut bids,
+            vec![
+                bid(&low, "chain", 5, BidStatus::Pending),
+                // Only the pending bids compete for the next auction of the key
+                bid(&low, "chain", 100, BidStatus::Expired),
+                bid(&high, "chain", 10, BidStatus::Pending),
+                bid(&high, "chain", 50, BidStatus::Pending),
+                bid(&middle, "chain", 20, BidStatus::Pending),
+                // The keys of the other chains are not ranked
+                bid(&middle, "other_chain", 1000, BidStatus::Pending),
+            ],
+        );
+
+        assert_eq!(
+            rank_permission_keys(&bids, &"chain".to_string()),
+            vec![
+                (high, BidAmount::from(50)),
+                (middle, BidAmount::from(20)),
+                (low, BidAmount::from(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rank_permission_keys_breaks_ties_by_recency() {
+        let bid_at = |permission_key: &PermissionKey, secs: i64| {
+            let SimulatedBid::Evm(mut bid) = bid(permission_key, "chain", 10, BidStatus::Pending)
+            else {
+                unreachable!()
+            };
+            bid.core_fields.initiation_time = OffsetDateTime::from_unix_timestamp(secs).unwrap();
+            SimulatedBid::Evm(bid)
+        };
+        let (stale, recent) = (PermissionKey::from(vec![1]), PermissionKey::from(vec![2]));
+        let mut bids = HashMap::new();
+        add_bids(
+            &mut bids,
+            vec![
+                bid_at(&stale, 100),
+                bid_at(&recent, 50),
+                // The most recent pending bid of the key is used for the tiebreak
+                bid_at(&recent, 200),
+            ],
+        );
+
+        assert_eq!(
+            rank_permission_keys(&bids, &"chain".to_string()),
+            vec![(recent, BidAmount::from(10)), (stale, BidAmount::from(10))]
+        );
+    }
+
+    #[test]
+    fn test_apply_bid_status_transition_only_once() {
+        let permission_key = PermissionKey::from(vec![1]);
+        let SimulatedBid::Evm(pending_bid) = bid(&permission_key, "chain", 10, BidStatus::Pending)
+        else {
+            unreachable!()
+        };
+        let key = pending_bid.get_auction_key();
+        let mut bids = HashMap::new();
+        add_bids(&mut bids, vec![pending_bid.clone().into()]);
+
+        let submitted_status = BidStatus::Submitted {
+            result: TxHash::Evm(H256::zero()),
+            index:  0,
+        };
+        let submitted_bid = pending_bid.update_status(submitted_status.clone());
+        assert!(apply_bid_status_transition(
+            &mut bids,
+            submitted_bid.clone().into(),
+            1
+        ));
+        assert_eq!(bids[&key][0].get_core_fields().status, submitted_status);
+
+        // Only the first of the concurrent conclusions updates the bid in the db
+        let won_bid: SimulatedBid = submitted_bid
+            .update_status(BidStatus::Won {
+                result: TxHash::Evm(H256::zero()),
+                index:  0,
+            })
+            .into();
+        let broadcasts = [1, 0]
+            .into_iter()
+            .filter(|rows_affected| {
+                apply_bid_status_transition(&mut bids, won_bid.clone(), *rows_affected)
+            })
+            .count();
+        assert_eq!(broadcasts, 1);
+        assert!(!bids.contains_key(&key));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_apply_bid_status_transition_once_for_concurrent_conclusions() {
+        let permission_key = PermissionKey::from(vec![1]);
+        let SimulatedBid::Evm(pending_bid) = bid(&permission_key, "chain", 10, BidStatus::Pending)
+        else {
+            unreachable!()
+        };
+        let key = pending_bid.get_auction_key();
+        let submitted_bid = pending_bid.update_status(BidStatus::Submitted {
+            result: TxHash::Evm(H256::zero()),
+            index:  0,
+        });
+        let mut bids = HashMap::new();
+        add_bids(&mut bids, vec![submitted_bid.clone().into()]);
+        let bids = Arc::new(RwLock::new(bids));
+        // Stands for the bid row, which is only updated by the conclusion finding it still submitted
+        let db_status = Arc::new(StdMutex::new(submitted_bid.core_fields.status.clone()));
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+
+        let conclusions = (0..2).map(|_| {
+            let (bids, db_status, barrier) = (bids.clone(), db_status.clone(), barrier.clone());
+            let won_status = BidStatus::Won {
+                result: TxHash::Evm(H256::zero()),
+                index:  0,
+            };
+            let won_bid: SimulatedBid = submitted_bid
+                .clone()
+                .update_status(won_status.clone())
+                .into();
+            tokio::spawn(async move {
+                barrier.wait().await;
+                let rows_affected = {
+                    let mut db_status = db_status.lock().unwrap();
+                    if matches!(*db_status, BidStatus::Submitted { .. }) {
+                        *db_status = won_status;
+                        1
+                    } else {
+                        0
+                    }
+                };
+                apply_bid_status_transition(&mut *bids.write().await, won_bid, rows_affected)
+            })
+        });
+        let applied = join_all(conclusions)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap())
+            .filter(|is_applied| *is_applied)
+            .count();
+
+        assert_eq!(applied, 1);
+        assert!(!bids.read().await.contains_key(&key));
+    }
+
+    #[test]
+    fn test_apply_bid_status_transition_skips_stale_update() {
+        let permission_key = PermissionKey::from(vec![1]);
+        let SimulatedBid::Evm(pending_bid) = bid(&permission_key, "chain", 10, BidStatus::Pending)
+        else {
+            unreachable!()
+        };
+        let key = pending_bid.get_auction_key();
+        let mut bids = HashMap::new();
+        add_bids(&mut bids, vec![pending_bid.clone().into()]);
+
+        let expired_bid = pending_bid.update_status(BidStatus::Expired).into();
+        assert!(!apply_bid_status_transition(&mut bids, expired_bid, 0));
+        assert_eq!(bids[&key][0].get_core_fields().status, BidStatus::Pending);
+    }
+
+    #[test]
+    fn test_to_json_value_error() {
+        // Maps are serialized as json objects, which only have string keys
+        let value = HashMap::from([((1, 2), 3)]);
+        assert!(matches!(
+            to_json_value(&value, &"test"),
+            Err(RestError::TemporarilyUnavailable(
+                UnavailableReason::Internal
+            ))
+        ));
+    }
+
+    fn amounts(sell: &[(u64, u64)], buy: &[(u64, u64)]) -> OpportunityAmounts {
+        let token_amounts = |amounts: &[(u64, u64)]| {
+            amounts
+                .iter()
+                .map(|(token, amount)| TokenAmount {
+                    token:  Address::from_low_u64_be(*token),
+                    amount: U256::from(*amount),
+                })
+                .collect()
+        };
+        OpportunityAmounts {
+            sell_tokens: token_amounts(sell),
+            buy_tokens:  token_amounts(buy),
+        }
+    }
+
+    #[test]
+    fn test_consume_opportunity_amounts_partially() {
+        let remaining = amounts(&[(1, 100), (2, 50)], &[(3, 10)]);
+        let consumed = remaining.consume(&amounts(&[(1, 40)], &[(3, 10)])).unwrap();
+        assert_eq!(consumed, amounts(&[(1, 60), (2, 50)], &[(3, 0)]));
+        assert!(!consumed.is_consumed());
+    }
+
+    #[test]
+    fn test_consume_opportunity_amounts_exactly() {
+        let remaining = amounts(&[(1, 100)], &[(2, 10)]);
+        let consumed = remaining.consume(&remaining).unwrap();
+        assert_eq!(consumed, amounts(&[(1, 0)], &[(2, 0)]));
+        assert!(consumed.is_consumed());
+    }
+
+    #[test]
+    fn test_consume_opportunity_amounts_overfill() {
+        let remaining = amounts(&[(1, 100)], &[(2, 10)]);
+        assert!(remaining.consume(&amounts(&[(1, 101)], &[])).is_err());
+        assert!(remaining.consume(&amounts(&[], &[(2, 11)])).is_err());
+        // Tokens which are not part of the opportunity can not be filled
+        assert!(remaining.consume(&amounts(&[(3, 1)], &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_consume_opportunity_keeps_amounts_when_persisting_fails() {
+        let remaining = amounts(&[(1, 100)], &[(2, 10)]);
+        let opportunity = Opportunity::new(
+            Uuid::new_v4(),
+            0,
+            OpportunityParams::V1(OpportunityParamsV1 {
+                permission_key:    Bytes::from(vec![1]),
+                chain_id:          "chain".to_string(),
+                target_contract:   Address::zero(),
+                target_calldata:   Bytes::default(),
+                target_call_value: U256::zero(),
+                sell_tokens:       remaining.sell_tokens.clone(),
+                buy_tokens:        remaining.buy_tokens.clone(),
+            }),
+        );
+        let key = OpportunityStore::get_key(&opportunity);
+        let store = OpportunityStore::default();
+        store.add_opportunity(opportunity.clone()).await;
+
+        let fill = amounts(&[(1, 40)], &[]);
+        let result = store
+            .consume(&key, opportunity.id, &fill, |_| async {
+                Err(RestError::TemporarilyUnavailable(UnavailableReason::Db))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(
+            store.get_opportunities(&key).await.unwrap()[0].remaining,
+            remaining
+        );
+
+        let consumed = store
+            .consume(&key, opportunity.id, &fill, |_| async { Ok(()) })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(consumed.remaining, amounts(&[(1, 60)], &[(2, 10)]));
+        assert_eq!(
+            store.get_opportunities(&key).await.unwrap()[0].remaining,
+            consumed.remaining
+        );
     }
 }
@@ -53,13 +53,8 @@ async fn sync_subwallets_for_chain(
 ) -> Result<()> {
     let provider = get_chain_provider(chain_id, chain_config)?;
     let id = provider.get_chainid().await?.as_u64();
-    let express_relay_contract = get_express_relay_contract(
-        chain_config.express_relay_contract,
-        provider.clone(),
-        wallet.clone(),
-        chain_config.legacy_tx,
-        id,
-    );
+    let express_relay_contract =
+        get_express_relay_contract(chain_config, provider.clone(), wallet.clone(), id);
     let current_relayer = express_relay_contract.get_relayer().call().await?;
     if current_relayer != wallet.address() {
         return Err(anyhow!(
@@ -21,6 +21,7 @@ use {
             spoof,
             Bytes,
             LocalWallet,
+            Middleware,
             Provider,
             RawCall,
             Signer,
@@ -158,3 +159,18 @@ pub async fn find_spoof_info(
         allowance_slot,
     })
 }
+
+/// Get the hash of the deployed bytecode of a token. This is used to invalidate the stored spoof info
+/// when the token contract changes
+///
+/// # Arguments
+///
+/// * `token`: ERC20 token address
+/// * `client`: Client to interact with the blockchain
+pub async fn get_bytecode_hash(
+    token: Address,
+    client: Arc<Provider<TracedClient>>,
+) -> anyhow::Result<[u8; 32]> {
+    let code = client.get_code(token, None).await?;
+    Ok(keccak256(code))
+}
@@ -0,0 +1,221 @@
+use {
+    crate::{
+        config::RunOptions,
+        models::{
+            self,
+            ProfileId,
+        },
+        server::{
+            EXIT_CHECK_INTERVAL,
+            SHOULD_EXIT,
+        },
+        state::Store,
+    },
+    anyhow::Result,
+    sqlx::{
+        types::time::{
+            Date,
+            OffsetDateTime,
+        },
+        QueryBuilder,
+    },
+    std::{
+        collections::HashMap,
+        sync::{
+            atomic::Ordering,
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::sync::{
+        Mutex,
+        RwLock,
+    },
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum UsageKind {
+    Requests,
+    Bids,
+    BytesTransferred,
+}
+
+impl models::Usage {
+    fn get(&self, kind: UsageKind) -> i64 {
+        match kind {
+            UsageKind::Requests => self.requests,
+            UsageKind::Bids => self.bids,
+            UsageKind::BytesTransferred => self.bytes_transferred,
+        }
+    }
+
+    fn add(&mut self, other: &models::Usage) {
+        self.requests += other.requests;
+        self.bids += other.bids;
+        self.bytes_transferred += other.bytes_transferred;
+    }
+
+    fn sub(&mut self, other: &models::Usage) {
+        self.requests -= other.requests;
+        self.bids -= other.bids;
+        self.bytes_transferred -= other.bytes_transferred;
+    }
+}
+
+impl models::UsageQuota {
+    fn get(&self, kind: UsageKind) -> Option<i64> {
+        match kind {
+            UsageKind::Requests => self.requests,
+            UsageKind::Bids => self.bids,
+            UsageKind::BytesTransferred => self.bytes_transferred,
+        }
+    }
+}
+
+/// Meters the API usage of the profiles in memory and periodically flushes it to the `usage` table.
+/// The quotas are checked against the flushed totals of the current month plus the pending usage.
+#[derive(Default)]
+pub struct UsageStore {
+    /// Usage recorded since the last flush.
+    pending: Mutex<HashMap<ProfileId, models::Usage>>,
+    /// Flushed usage of the current month.
+    totals:  RwLock<HashMap<ProfileId, models::Usage>>,
+    quotas:  RwLock<HashMap<ProfileId, models::UsageQuota>>,
+}
+
+fn current_period_start() -> Date {
+    let now = OffsetDateTime::now_utc();
+    now.date()
+        .replace_day(1)
+        .expect("First day of the month is always valid")
+}
+
+impl UsageStore {
+    pub async fn record(&self, profile_id: ProfileId, usage: models::Usage) {
+        self.pending
+            .lock()
+            .await
+            .entry(profile_id)
+            .or_default()
+            .add(&usage);
+    }
+
+    pub async fn is_quota_exceeded(&self, profile_id: ProfileId, kinds: &[UsageKind]) -> bool {
+        let Some(quota) = self.quotas.read().await.get(&profile_id).copied() else {
+            return false;
+        };
+        let mut usage = self
+            .totals
+            .read()
+            .await
+            .get(&profile_id)
+            .copied()
+            .unwrap_or_default();
+        if let Some(pending) = self.pending.lock().await.get(&profile_id) {
+            usage.add(pending);
+        }
+        kinds.iter().any(|kind| {
+            quota
+                .get(*kind)
+                .is_some_and(|limit| usage.get(*kind) >= limit)
+        })
+    }
+
+    async fn flush(&self, db: &sqlx::PgPool) -> Result<()> {
+        let period_start = current_period_start();
+        // The flushed usage is moved to the totals right away, so the quotas keep counting it during the insert
+        let pending = {
+            let mut pending = self.pending.lock().await;
+            let mut totals = self.totals.write().await;
+            for (profile_id, usage) in pending.iter() {
+                totals.entry(*profile_id).or_default().add(usage);
+            }
+            std::mem::take(&mut *pending)
+        };
+        if !pending.is_empty() {
+            let mut query_builder = QueryBuilder::new(
+                "INSERT INTO usage (profile_id, period_start, requests, bids, bytes_transferred) ",
+            );
+            query_builder.push_values(pending.iter(), |mut row, (profile_id, usage)| {
+                row.push_bind(profile_id)
+                    .push_bind(period_start)
+                    .push_bind(usage.requests)
+                    .push_bind(usage.bids)
+                    .push_bind(usage.bytes_transferred);
+            });
+            query_builder.push(
+                " ON CONFLICT (profile_id, period_start) DO UPDATE SET \
+                requests = usage.requests + EXCLUDED.requests, \
+                bids = usage.bids + EXCLUDED.bids, \
+                bytes_transferred = usage.bytes_transferred + EXCLUDED.bytes_transferred",
+            );
+            if let Err(e) = query_builder.build().execute(db).await {
+                // Keep the usage to retry on the next flush
+                let mut current = self.pending.lock().await;
+                let mut totals = self.totals.write().await;
+                for (profile_id, usage) in pending.iter() {
+                    current.entry(*profile_id).or_default().add(usage);
+                    if let Some(total) = totals.get_mut(profile_id) {
+                        total.sub(usage);
+                    }
+                }
+                return Err(e.into());
+            }
+        }
+
+        let totals: Vec<(ProfileId, i64, i64, i64)> = sqlx::query_as(
+            "SELECT profile_id, requests, bids, bytes_transferred FROM usage WHERE period_start = $1",
+        )
+        .bind(period_start)
+        .fetch_all(db)
+        .await?;
+        *self.totals.write().await = totals
+            .into_iter()
+            .map(|(profile_id, requests, bids, bytes_transferred)| {
+                (
+                    profile_id,
+                    models::Usage {
+                        requests,
+                        bids,
+                        bytes_transferred,
+                    },
+                )
+            })
+            .collect();
+
+        let quotas: Vec<models::UsageQuota> = sqlx::query_as("SELECT * FROM usage_quota")
+            .fetch_all(db)
+            .await?;
+        *self.quotas.write().await = quotas
+            .into_iter()
+            .map(|quota| (quota.profile_id, quota))
+            .collect();
+        Ok(())
+    }
+}
+
+pub async fn run_usage_flush_loop(store: Arc<Store>, run_options: RunOptions) -> Result<()> {
+    let Some(usage) = &store.usage else {
+        return Ok(());
+    };
+    tracing::info!("Starting usage flush loop...");
+    let mut flush_interval =
+        tokio::time::interval(Duration::from_secs(run_options.usage.flush_interval));
+    let mut exit_check_interval = tokio::time::interval(EXIT_CHECK_INTERVAL);
+    while !SHOULD_EXIT.load(Ordering::Acquire) {
+        tokio::select! {
+            _ = flush_interval.tick() => {
+                if let Err(e) = usage.flush(&store.db).await {
+                    tracing::error!("Failed to flush usage: {:?}", e);
+                }
+            }
+            _ = exit_check_interval.tick() => {}
+        }
+    }
+    // Persist the usage recorded since the last flush before shutting down
+    if let Err(e) = usage.flush(&store.db).await {
+        tracing::error!("Failed to flush usage: {:?}", e);
+    }
+    tracing::info!("Shutting down usage flush loop...");
+    Ok(())
+}
@@ -10,9 +10,14 @@ use {
         },
         providers::Middleware,
         types::{
+            BlockNumber,
             I256,
             U256,
         },
+        utils::{
+            EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
+        },
     },
 };
 
@@ -35,12 +40,37 @@ pub const EIP1559_FEE_ESTIMATION_THRESHOLD_MAX_CHANGE: i64 = 200;
 #[derive(Clone, Debug)]
 #[must_use]
 pub struct EthProviderOracle<M: Middleware> {
-    provider: M,
+    provider:          M,
+    reward_percentile: f64,
+    max_fee_per_gas:   Option<U256>,
 }
 
 impl<M: Middleware> EthProviderOracle<M> {
     pub fn new(provider: M) -> Self {
-        Self { provider }
+        Self {
+            provider,
+            reward_percentile: EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
+            max_fee_per_gas: None,
+        }
+    }
+
+    /// Sets the percentile of the rewards of the recent blocks the priority fee is estimated from.
+    pub fn with_reward_percentile(mut self, reward_percentile: f64) -> Self {
+        self.reward_percentile = reward_percentile;
+        self
+    }
+
+    /// Caps the estimated fees, so the fee per gas never exceeds the given value.
+    pub fn with_max_fee_per_gas(mut self, max_fee_per_gas: Option<U256>) -> Self {
+        self.max_fee_per_gas = max_fee_per_gas;
+        self
+    }
+
+    fn cap(&self, fee: U256) -> U256 {
+        match self.max_fee_per_gas {
+            Some(max_fee_per_gas) => std::cmp::min(fee, max_fee_per_gas),
+            None => fee,
+        }
     }
 }
 
@@ -50,17 +80,35 @@ where
     M::Error: 'static,
 {
     async fn fetch(&self) -> Result<U256> {
-        self.provider
+        let gas_price = self
+            .provider
             .get_gas_price()
             .await
-            .map_err(|err| GasOracleError::ProviderError(Box::new(err)))
+            .map_err(|err| GasOracleError::ProviderError(Box::new(err)))?;
+        Ok(self.cap(gas_price))
     }
 
     async fn estimate_eip1559_fees(&self) -> Result<(U256, U256)> {
-        self.provider
-            .estimate_eip1559_fees(Some(eip1559_default_estimator))
+        let fee_history = self
+            .provider
+            .fee_history(
+                EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+                BlockNumber::Latest,
+                &[self.reward_percentile],
+            )
             .await
-            .map_err(|err| GasOracleError::ProviderError(Box::new(err)))
+            .map_err(|err| GasOracleError::ProviderError(Box::new(err)))?;
+        // The last base fee of the history is the base fee of the next block
+        let base_fee_per_gas = *fee_history
+            .base_fee_per_gas
+            .last()
+            .ok_or(GasOracleError::Eip1559EstimationNotSupported)?;
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            eip1559_default_estimator(base_fee_per_gas, fee_history.reward);
+        Ok((
+            self.cap(max_fee_per_gas),
+            self.cap(max_priority_fee_per_gas),
+        ))
     }
 }
 